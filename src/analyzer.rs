@@ -2,13 +2,22 @@
 
 use std::collections::{HashMap, HashSet};
 
-use prost_types::FileDescriptorSet;
 use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::source_code_info::Location;
+use prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet};
 
+use crate::classify::Classification;
+use crate::decoder::{Image, ImageFileInfo};
 use crate::domain::{
-    Edge, EnumValue, FieldInfo, GraphModel, MessageDef, MethodSignature, Node, NodeDetails,
-    NodeType, Package,
+    Cardinality, Comments, Edge, EdgeKind, EdgeLabel, EnumValue, FieldInfo, FieldPresence,
+    GraphModel, MapType, MessageDef, MethodSignature, Node, NodeDetails, NodeType, NumberRange,
+    OneofGroup, Package, Reserved, Span, StreamingMode,
 };
+use crate::features::{FileFeatures, Resolver};
+use crate::imports::{ImportReport, indices};
+use crate::merge::defined_types;
+use crate::source_info::locations_by_name;
+use crate::well_known;
 
 /// Analyzer creates definition-level nodes (Service, Message, Enum) from protobuf descriptors.
 /// Each Service, Message, and Enum definition becomes its own graph node.
@@ -20,6 +29,23 @@ pub struct Analyzer {
     type_to_message_def: HashMap<String, MessageDef>,
     /// Tracks external packages (google.*, buf.*) for External node creation
     external_packages: HashSet<String>,
+    /// Maps fully-qualified external type name to the file that defines it
+    external_type_files: HashMap<String, String>,
+    /// Per-file buf image metadata; overrides path-based external detection
+    file_info: HashMap<String, ImageFileInfo>,
+    /// Per-file editions data used to resolve field presence and encoding
+    features: HashMap<String, FileFeatures>,
+    /// Fold nested messages/enums into their top-level message instead of
+    /// giving them their own nodes
+    collapse_nested: bool,
+    /// Emit a node per RPC method between each service and its messages
+    method_nodes: bool,
+    /// Source locations of first-party definitions by fully-qualified name
+    locations: HashMap<String, Location>,
+    /// Configured external paths/packages and foreign packages
+    classification: Classification,
+    /// Show singular wrapper-typed fields as nullable scalars, without edges
+    inline_wrappers: bool,
 }
 
 impl Analyzer {
@@ -29,11 +55,84 @@ impl Analyzer {
             type_to_node_id: HashMap::new(),
             type_to_message_def: HashMap::new(),
             external_packages: HashSet::new(),
+            external_type_files: HashMap::new(),
+            file_info: HashMap::new(),
+            features: HashMap::new(),
+            collapse_nested: false,
+            method_nodes: false,
+            locations: HashMap::new(),
+            classification: Classification::default(),
+            inline_wrappers: false,
         }
     }
 
+    /// When enabled, nested messages and enums get no nodes of their own:
+    /// references to them point at the enclosing top-level message, and
+    /// their fields contribute that message's outgoing edges.
+    #[must_use]
+    pub fn with_nested_collapsed(mut self, collapse: bool) -> Self {
+        self.collapse_nested = collapse;
+        self
+    }
+
+    /// When enabled, every RPC becomes a node (ID `pkg.Service.Method`)
+    /// linked from its service, and request/response edges start at the
+    /// method instead of the service.
+    #[must_use]
+    pub fn with_method_nodes(mut self, method_nodes: bool) -> Self {
+        self.method_nodes = method_nodes;
+        self
+    }
+
+    /// Files matching the configured external paths or packages become
+    /// external on top of the built-in rules, and nodes in foreign packages
+    /// are marked [`Node::foreign`].
+    #[must_use]
+    pub fn with_classification(mut self, classification: Classification) -> Self {
+        self.classification = classification;
+        self
+    }
+
+    /// When enabled, singular fields of a wrapper type such as
+    /// `google.protobuf.StringValue` read as the wrapped scalar marked
+    /// [`FieldInfo::nullable`], and get no edge to the wrapper.
+    #[must_use]
+    pub fn with_wrappers_inlined(mut self, inline: bool) -> Self {
+        self.inline_wrappers = inline;
+        self
+    }
+
+    /// Analyze a buf image, classifying files by their `is_import` flag
+    /// instead of by path prefix.
+    #[must_use]
+    pub fn analyze_image(&mut self, image: &Image) -> GraphModel {
+        self.reset(image.files.clone(), image.features.clone());
+        self.analyze_descriptors(&image.descriptors)
+    }
+
     #[must_use]
     pub fn analyze(&mut self, fds: &FileDescriptorSet) -> GraphModel {
+        self.reset(HashMap::new(), HashMap::new());
+        self.analyze_descriptors(fds)
+    }
+
+    /// Drop everything learned from a previous input, so one analyzer can be
+    /// reused across descriptor sets.
+    fn reset(
+        &mut self,
+        file_info: HashMap<String, ImageFileInfo>,
+        features: HashMap<String, FileFeatures>,
+    ) {
+        self.type_to_node_id.clear();
+        self.type_to_message_def.clear();
+        self.external_packages.clear();
+        self.external_type_files.clear();
+        self.locations.clear();
+        self.file_info = file_info;
+        self.features = features;
+    }
+
+    fn analyze_descriptors(&mut self, fds: &FileDescriptorSet) -> GraphModel {
         let mut model = GraphModel::new();
 
         // First pass: Create Message/Enum nodes and build type mappings
@@ -41,34 +140,78 @@ impl Analyzer {
         for file in &fds.file {
             let file_name = file.name.as_deref().unwrap_or("");
             let package = file.package.as_deref().unwrap_or("");
-            let is_external = Self::is_external_file(file_name);
+            let is_external = self.is_external_file(file);
+
+            // Natively parsed sources carry features as uninterpreted options
+            let features = self
+                .features
+                .entry(file_name.to_string())
+                .or_insert_with(|| FileFeatures::from_uninterpreted(file))
+                .clone();
+            let resolver = Resolver::new(file, Some(&features));
+            if !is_external {
+                self.locations.extend(locations_by_name(file));
+            }
 
-            // Create Message nodes (skip external files - just track their types)
+            // Create Message nodes, including nested types (skip external files - just track their types)
             for message in &file.message_type {
                 if is_external {
-                    self.register_external_type(message, package);
-                } else if let Some(node) = self.create_message_node(message, package, file_name) {
-                    model.nodes.push(node);
+                    self.register_external_type(message, package, file_name);
+                } else {
+                    self.create_message_nodes(
+                        message,
+                        package,
+                        file_name,
+                        None,
+                        &resolver,
+                        &mut model.nodes,
+                    );
                 }
             }
 
             // Create Enum nodes (skip external files - just track their types)
             for enum_type in &file.enum_type {
                 if is_external {
-                    self.register_external_enum(enum_type, package);
-                } else if let Some(node) = self.create_enum_node(enum_type, package, file_name) {
+                    self.register_external_enum(enum_type, package, file_name);
+                } else if let Some(node) =
+                    self.create_enum_node(enum_type, package, file_name, None, &resolver)
+                {
                     model.nodes.push(node);
                 }
             }
         }
 
-        // Second pass: Create Service nodes (now message definitions are available)
+        // Second pass: Create Service and Extension nodes (now message definitions are available)
         for file in &fds.file {
             let file_name = file.name.as_deref().unwrap_or("");
+            if self.is_external_file(file) {
+                continue;
+            }
+
             let package = file.package.as_deref().unwrap_or("");
+            let features = self.features.get(file_name).cloned();
+            let resolver = Resolver::new(file, features.as_ref());
 
             for service in &file.service {
                 if let Some(node) = self.create_service_node(service, package, file_name) {
+                    let methods = if self.method_nodes {
+                        Self::create_method_nodes(&node)
+                    } else {
+                        Vec::new()
+                    };
+                    model.nodes.push(node);
+                    model.nodes.extend(methods);
+                }
+            }
+
+            for (scope_fq, extension) in Self::file_extensions(file) {
+                if let Some(node) = self.create_extension_node(
+                    extension,
+                    package,
+                    file_name,
+                    scope_fq.as_deref(),
+                    &resolver,
+                ) {
                     model.nodes.push(node);
                 }
             }
@@ -76,8 +219,7 @@ impl Analyzer {
 
         // Third pass: Create edges based on field type references
         for file in &fds.file {
-            let file_name = file.name.as_deref().unwrap_or("");
-            if Self::is_external_file(file_name) {
+            if self.is_external_file(file) {
                 continue;
             }
 
@@ -90,23 +232,197 @@ impl Analyzer {
                     .extend(self.create_service_edges(service, package));
             }
 
-            // Edges from Message fields
+            // Edges from Message fields, including nested messages
             for message in &file.message_type {
-                model
-                    .edges
-                    .extend(self.create_message_edges(message, package, &mut model.nodes));
+                model.edges.extend(self.create_message_edges(
+                    message,
+                    package,
+                    None,
+                    &mut model.nodes,
+                ));
+            }
+
+            // Edges from extensions to the extended message and the value type
+            for (scope_fq, extension) in Self::file_extensions(file) {
+                model.edges.extend(self.create_extension_edges(
+                    extension,
+                    package,
+                    scope_fq.as_deref(),
+                    &mut model.nodes,
+                ));
             }
         }
 
+        self.resolve_type_refs(&mut model.nodes);
+        self.mark_foreign(&mut model.nodes);
+
         // Deduplicate edges
         model.edges = Self::deduplicate_edges(model.edges);
 
-        model.packages = Self::group_packages(&model.nodes);
+        model.packages = Package::tree(&model.nodes);
+        model
+    }
+
+    /// File-level graph: one node per .proto file and one edge per import,
+    /// labeled as a plain, public or weak import, plus one per extension
+    /// from the extending file to the file of the extended message. Files
+    /// classified as external become External nodes; the others carry
+    /// their [`ImportReport`] findings.
+    #[must_use]
+    pub fn analyze_files(&mut self, image: &Image) -> GraphModel {
+        let report = self.import_report(image);
+        let fds = &image.descriptors;
+        let mut model = GraphModel::new();
+
+        for file in &fds.file {
+            let file_name = file.name();
+            let (node_type, details) = if self.is_external_file(file) {
+                (
+                    NodeType::External,
+                    NodeDetails::External { well_known: None },
+                )
+            } else {
+                let imports_of = |entries: Vec<(&String, &String)>| -> Vec<String> {
+                    let mut imports: Vec<String> = entries
+                        .into_iter()
+                        .filter(|(file, _)| *file == file_name)
+                        .map(|(_, import)| import.clone())
+                        .collect();
+                    imports.sort_unstable();
+                    imports.dedup();
+                    imports
+                };
+                let details = NodeDetails::File {
+                    unused_imports: imports_of(
+                        report.unused.iter().map(|u| (&u.file, &u.import)).collect(),
+                    ),
+                    indirect_imports: imports_of(
+                        report
+                            .indirect
+                            .iter()
+                            .map(|i| (&i.file, &i.import))
+                            .collect(),
+                    ),
+                    missing_imports: imports_of(
+                        report
+                            .missing
+                            .iter()
+                            .map(|m| (&m.file, &m.import))
+                            .collect(),
+                    ),
+                };
+                (NodeType::File, details)
+            };
+            model.nodes.push(
+                Node::new(
+                    file_name.to_string(),
+                    node_type,
+                    file.package().to_string(),
+                    file_name.to_string(),
+                    file_name.to_string(),
+                    details,
+                )
+                .with_module(self.module_of(file_name)),
+            );
+        }
+
+        let known: HashSet<&str> = fds.file.iter().map(|f| f.name()).collect();
+        for file in &fds.file {
+            let public = indices(&file.public_dependency, &file.dependency);
+            let weak = indices(&file.weak_dependency, &file.dependency);
+            for dependency in file
+                .dependency
+                .iter()
+                .filter(|d| known.contains(d.as_str()))
+            {
+                let kind = if public.contains(dependency.as_str()) {
+                    EdgeKind::PublicImport
+                } else if weak.contains(dependency.as_str()) {
+                    EdgeKind::WeakImport
+                } else {
+                    EdgeKind::Import
+                };
+                model.edges.push(
+                    Edge::new(file.name().to_string(), dependency.clone())
+                        .with_label(EdgeLabel::new(kind)),
+                );
+            }
+        }
+
+        let type_files: HashMap<String, &str> = fds
+            .file
+            .iter()
+            .flat_map(|f| defined_types(f).into_iter().map(move |t| (t, f.name())))
+            .collect();
+        for file in &fds.file {
+            for (_, extension) in Self::file_extensions(file) {
+                if let Some(&extendee_file) = type_files.get(extension.extendee())
+                    && extendee_file != file.name()
+                {
+                    model.edges.push(
+                        Edge::new(file.name().to_string(), extendee_file.to_string()).with_label(
+                            EdgeLabel::new(EdgeKind::Extension).with_name(extension.name()),
+                        ),
+                    );
+                }
+            }
+        }
+        model.edges = Self::deduplicate_edges(model.edges);
+        self.mark_foreign(&mut model.nodes);
+
+        model.packages = Package::tree(&model.nodes);
         model
     }
 
-    fn is_external_file(file_path: &str) -> bool {
-        file_path.starts_with("google/") || file_path.starts_with("buf/")
+    /// [`ImportReport`] for the files that are not classified as external.
+    #[must_use]
+    pub fn import_report(&mut self, image: &Image) -> ImportReport {
+        self.file_info = image.files.clone();
+        ImportReport::compute(&image.descriptors, |file| !self.is_external_file(file))
+    }
+
+    /// Imports of a buf image (or `google/` and `buf/` files elsewhere),
+    /// plus whatever the classification adds.
+    fn is_external_file(&self, file: &FileDescriptorProto) -> bool {
+        let file_path = file.name();
+        let built_in = match self.file_info.get(file_path) {
+            Some(info) => info.is_import,
+            None => file_path.starts_with("google/") || file_path.starts_with("buf/"),
+        };
+        built_in || self.classification.is_external(file_path, file.package())
+    }
+
+    fn mark_foreign(&self, nodes: &mut [Node]) {
+        for node in nodes
+            .iter_mut()
+            .filter(|n| n.node_type != NodeType::External)
+        {
+            node.foreign = self.classification.is_foreign(&node.package);
+        }
+    }
+
+    /// Doc comments of the definition `fq_name` (with leading dot).
+    fn comments(&self, fq_name: &str) -> Comments {
+        self.locations
+            .get(fq_name)
+            .map(|l| {
+                Comments::new(
+                    l.leading_comments.as_deref(),
+                    l.trailing_comments.as_deref(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Source span of the definition `fq_name` (with leading dot).
+    fn span(&self, fq_name: &str) -> Option<Span> {
+        self.locations
+            .get(fq_name)
+            .and_then(|l| Span::from_source_code_info(&l.span))
+    }
+
+    fn module_of(&self, file_path: &str) -> Option<String> {
+        self.file_info.get(file_path).and_then(|i| i.module.clone())
     }
 
     /// Generate node ID: `{package}.{name}` or just `{name}` if no package
@@ -136,7 +452,7 @@ impl Analyzer {
         let name = service.name.as_ref()?;
         let id = Self::generate_node_id(package, name);
         let fq_type = Self::generate_fq_type(package, name);
-        self.type_to_node_id.insert(fq_type, id.clone());
+        self.type_to_node_id.insert(fq_type.clone(), id.clone());
 
         let methods: Vec<MethodSignature> = service
             .method
@@ -145,6 +461,12 @@ impl Analyzer {
                 name: m.name.clone().unwrap_or_default(),
                 input_type: Self::extract_short_type(m.input_type.as_ref()),
                 output_type: Self::extract_short_type(m.output_type.as_ref()),
+                input_type_ref: Self::type_ref(m.input_type.as_ref()),
+                output_type_ref: Self::type_ref(m.output_type.as_ref()),
+                streaming: StreamingMode::from_flags(m.client_streaming(), m.server_streaming()),
+                comments: self.comments(&format!("{fq_type}.{}", m.name())),
+                deprecated: m.options.as_ref().is_some_and(|o| o.deprecated()),
+                span: self.span(&format!("{fq_type}.{}", m.name())),
             })
             .collect();
 
@@ -164,148 +486,412 @@ impl Analyzer {
             }
         }
 
-        Some(Node::new(
-            id,
-            NodeType::Service,
-            package.to_string(),
-            name.clone(),
-            file_name.to_string(),
-            NodeDetails::Service { methods, messages },
-        ))
+        Some(
+            Node::new(
+                id,
+                NodeType::Service,
+                package.to_string(),
+                name.clone(),
+                file_name.to_string(),
+                NodeDetails::Service { methods, messages },
+            )
+            .with_module(self.module_of(file_name))
+            .with_comments(self.comments(&fq_type))
+            .with_deprecated(service.options.as_ref().is_some_and(|o| o.deprecated()))
+            .with_span(self.span(&fq_type)),
+        )
+    }
+
+    fn create_method_nodes(service: &Node) -> Vec<Node> {
+        let NodeDetails::Service { methods, .. } = &service.details else {
+            return Vec::new();
+        };
+        methods
+            .iter()
+            .map(|method| {
+                Node::new(
+                    format!("{}.{}", service.id, method.name),
+                    NodeType::Method,
+                    service.package.clone(),
+                    format!("{}.{}", service.label, method.name),
+                    service.file.clone(),
+                    NodeDetails::Method {
+                        signature: method.clone(),
+                    },
+                )
+                .with_module(service.module.clone())
+                .with_parent(Some(service.id.clone()))
+                .with_comments(method.comments.clone())
+                .with_deprecated(method.deprecated)
+                .with_span(method.span)
+            })
+            .collect()
+    }
+
+    /// Fully-qualified name of a definition declared directly in `package`
+    /// or inside the message `parent_fq`.
+    fn scoped_fq_type(package: &str, parent_fq: Option<&str>, name: &str) -> String {
+        match parent_fq {
+            Some(parent) => format!("{parent}.{name}"),
+            None => Self::generate_fq_type(package, name),
+        }
+    }
+
+    /// `".shop.v1.Order.Item"` in package `shop.v1` → `"Order.Item"`
+    fn relative_name(package: &str, fq_type: &str) -> String {
+        let id = fq_type.trim_start_matches('.');
+        match package {
+            "" => id.to_string(),
+            package => id
+                .strip_prefix(package)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .unwrap_or(id)
+                .to_string(),
+        }
+    }
+
+    /// Node ID for a definition and the ID of the node containing it.
+    /// Collapsed nested definitions map onto their top-level message.
+    fn scoped_node_id(&self, fq_type: &str, parent_fq: Option<&str>) -> (String, Option<String>) {
+        let parent_id = parent_fq.and_then(|p| self.type_to_node_id.get(p)).cloned();
+        match parent_id {
+            Some(parent_id) if self.collapse_nested => (parent_id, None),
+            parent_id => (fq_type.trim_start_matches('.').to_string(), parent_id),
+        }
     }
 
-    fn create_message_node(
+    /// Create nodes for `message` and, recursively, its nested messages and enums.
+    fn create_message_nodes(
         &mut self,
         message: &prost_types::DescriptorProto,
         package: &str,
         file_name: &str,
-    ) -> Option<Node> {
-        let name = message.name.as_ref()?;
-        let id = Self::generate_node_id(package, name);
-        let fq_type = Self::generate_fq_type(package, name);
+        parent_fq: Option<&str>,
+        resolver: &Resolver,
+        nodes: &mut Vec<Node>,
+    ) {
+        let Some(name) = message.name.as_ref() else {
+            return;
+        };
+        let fq_type = Self::scoped_fq_type(package, parent_fq, name);
+        let (id, parent_id) = self.scoped_node_id(&fq_type, parent_fq);
         self.type_to_node_id.insert(fq_type.clone(), id.clone());
-
-        // Also register nested types
-        for nested in &message.nested_type {
-            self.register_nested_message(nested, &fq_type);
-        }
-        for nested_enum in &message.enum_type {
-            self.register_nested_enum(nested_enum, &fq_type);
-        }
+        let label = Self::relative_name(package, &fq_type);
 
         let fields: Vec<FieldInfo> = message
             .field
             .iter()
-            .map(|f| FieldInfo {
-                name: f.name.clone().unwrap_or_default(),
-                number: f.number.unwrap_or(0),
-                type_name: Self::type_to_string(f.r#type, f.type_name.as_ref()),
-                label: Self::label_to_string(f.label),
+            .map(|f| {
+                let (presence, encoding) = resolver.field(&fq_type, message, f);
+                let map_fields = Self::map_fields(message, &fq_type, f);
+                let map = map_fields.map(|(key, value)| MapType {
+                    key_type: Self::type_to_string(key.r#type, key.type_name.as_ref()),
+                    value_type: Self::type_to_string(value.r#type, value.type_name.as_ref()),
+                });
+                let wrapped = self.inlined_wrapper(f);
+                let type_name = match (&map, wrapped) {
+                    (Some(map), _) => format!("map<{}, {}>", map.key_type, map.value_type),
+                    (None, Some(scalar)) => scalar.to_string(),
+                    (None, None) => Self::type_to_string(f.r#type, f.type_name.as_ref()),
+                };
+                let referenced = map_fields.map_or(f, |(_, value)| value);
+                FieldInfo {
+                    name: f.name.clone().unwrap_or_default(),
+                    number: f.number.unwrap_or(0),
+                    json_name: f.json_name.clone(),
+                    type_name,
+                    type_ref: Self::type_ref(referenced.type_name.as_ref())
+                        .filter(|_| wrapped.is_none()),
+                    // Maps are written without a label even though they encode as repeated
+                    label: if map.is_some() {
+                        String::new()
+                    } else {
+                        Self::label_to_string(f.label, presence)
+                    },
+                    presence,
+                    encoding,
+                    map,
+                    oneof: Self::oneof_name(message, f),
+                    proto3_optional: f.proto3_optional.unwrap_or(false),
+                    default_value: f.default_value.clone(),
+                    nullable: wrapped.is_some(),
+                    comments: self.comments(&format!("{fq_type}.{}", f.name())),
+                    deprecated: f.options.as_ref().is_some_and(|o| o.deprecated()),
+                    span: self.span(&format!("{fq_type}.{}", f.name())),
+                }
+            })
+            .collect();
+
+        // Synthetic oneofs have no members once proto3 optional fields are skipped
+        let oneofs: Vec<OneofGroup> = message
+            .oneof_decl
+            .iter()
+            .filter_map(|oneof| {
+                let members: Vec<String> = fields
+                    .iter()
+                    .filter(|f| f.oneof.as_deref() == oneof.name.as_deref())
+                    .map(|f| f.name.clone())
+                    .collect();
+                (!members.is_empty()).then(|| OneofGroup {
+                    name: oneof.name().to_string(),
+                    fields: members,
+                })
             })
             .collect();
 
         // Register MessageDef for expandable RPC method fields
         self.type_to_message_def.insert(
-            fq_type,
+            fq_type.clone(),
             MessageDef {
-                name: name.clone(),
+                name: label.clone(),
+                type_ref: fq_type.trim_start_matches('.').to_string(),
                 fields: fields.clone(),
             },
         );
 
-        Some(Node::new(
-            id,
-            NodeType::Message,
-            package.to_string(),
-            name.clone(),
-            file_name.to_string(),
-            NodeDetails::Message { fields },
-        ))
+        let collapsed = self.collapse_nested && parent_fq.is_some();
+        if !collapsed {
+            nodes.push(
+                Node::new(
+                    id,
+                    NodeType::Message,
+                    package.to_string(),
+                    label,
+                    file_name.to_string(),
+                    NodeDetails::Message {
+                        fields,
+                        oneofs,
+                        reserved: Reserved {
+                            ranges: message
+                                .reserved_range
+                                .iter()
+                                .map(|r| NumberRange::new(r.start(), r.end(), true))
+                                .collect(),
+                            names: message.reserved_name.clone(),
+                        },
+                        extension_ranges: message
+                            .extension_range
+                            .iter()
+                            .map(|r| NumberRange::new(r.start(), r.end(), true))
+                            .collect(),
+                    },
+                )
+                .with_module(self.module_of(file_name))
+                .with_parent(parent_id)
+                .with_comments(self.comments(&fq_type))
+                .with_deprecated(message.options.as_ref().is_some_and(|o| o.deprecated()))
+                .with_span(self.span(&fq_type)),
+            );
+        }
+
+        for nested in message
+            .nested_type
+            .iter()
+            .filter(|m| !Self::is_map_entry(m))
+        {
+            self.create_message_nodes(nested, package, file_name, Some(&fq_type), resolver, nodes);
+        }
+        for nested_enum in &message.enum_type {
+            if let Some(node) =
+                self.create_enum_node(nested_enum, package, file_name, Some(&fq_type), resolver)
+            {
+                nodes.push(node);
+            }
+        }
     }
 
+    /// Returns `None` for nameless enums and for nested enums when collapsed.
     fn create_enum_node(
         &mut self,
         enum_type: &prost_types::EnumDescriptorProto,
         package: &str,
         file_name: &str,
+        parent_fq: Option<&str>,
+        resolver: &Resolver,
     ) -> Option<Node> {
         let name = enum_type.name.as_ref()?;
-        let id = Self::generate_node_id(package, name);
-        let fq_type = Self::generate_fq_type(package, name);
-        self.type_to_node_id.insert(fq_type, id.clone());
+        let fq_type = Self::scoped_fq_type(package, parent_fq, name);
+        let (id, parent_id) = self.scoped_node_id(&fq_type, parent_fq);
+        self.type_to_node_id.insert(fq_type.clone(), id.clone());
+        if self.collapse_nested && parent_fq.is_some() {
+            return None;
+        }
+        let closed = resolver.enum_closed(&fq_type);
 
         let values = enum_type
             .value
             .iter()
-            .map(|v| EnumValue {
+            .enumerate()
+            .map(|(i, v)| EnumValue {
                 name: v.name.clone().unwrap_or_default(),
                 number: v.number.unwrap_or(0),
+                alias_of: enum_type.value[..i]
+                    .iter()
+                    .find(|earlier| earlier.number == v.number)
+                    .map(|earlier| earlier.name().to_string()),
+                comments: self.comments(&format!("{fq_type}.{}", v.name())),
+                deprecated: v.options.as_ref().is_some_and(|o| o.deprecated()),
+                span: self.span(&format!("{fq_type}.{}", v.name())),
             })
             .collect();
 
-        Some(Node::new(
-            id,
-            NodeType::Enum,
-            package.to_string(),
-            name.clone(),
-            file_name.to_string(),
-            NodeDetails::Enum { values },
-        ))
+        Some(
+            Node::new(
+                id,
+                NodeType::Enum,
+                package.to_string(),
+                Self::relative_name(package, &fq_type),
+                file_name.to_string(),
+                NodeDetails::Enum {
+                    values,
+                    closed,
+                    allow_alias: enum_type.options.as_ref().is_some_and(|o| o.allow_alias()),
+                    reserved: Reserved {
+                        ranges: enum_type
+                            .reserved_range
+                            .iter()
+                            .map(|r| NumberRange::new(r.start(), r.end(), false))
+                            .collect(),
+                        names: enum_type.reserved_name.clone(),
+                    },
+                },
+            )
+            .with_module(self.module_of(file_name))
+            .with_parent(parent_id)
+            .with_comments(self.comments(&fq_type))
+            .with_deprecated(enum_type.options.as_ref().is_some_and(|o| o.deprecated()))
+            .with_span(self.span(&fq_type)),
+        )
     }
 
-    fn register_external_type(&mut self, message: &prost_types::DescriptorProto, package: &str) {
-        if let Some(name) = &message.name {
-            let id = Self::generate_node_id(package, name);
-            let fq_type = Self::generate_fq_type(package, name);
-            self.type_to_node_id.insert(fq_type.clone(), id);
-            self.external_packages.insert(package.to_string());
-
-            // Register nested types
+    /// Every extension declared in `file`, paired with the fully-qualified
+    /// name of the message it is declared in (`None` at file level).
+    fn file_extensions(file: &FileDescriptorProto) -> Vec<(Option<String>, &FieldDescriptorProto)> {
+        fn collect<'f>(
+            message: &'f DescriptorProto,
+            fq_type: String,
+            out: &mut Vec<(Option<String>, &'f FieldDescriptorProto)>,
+        ) {
+            out.extend(message.extension.iter().map(|e| (Some(fq_type.clone()), e)));
             for nested in &message.nested_type {
-                self.register_nested_message(nested, &fq_type);
+                collect(nested, format!("{fq_type}.{}", nested.name()), out);
             }
         }
+
+        let mut extensions: Vec<_> = file.extension.iter().map(|e| (None, e)).collect();
+        for message in &file.message_type {
+            let fq_type = Analyzer::generate_fq_type(file.package(), message.name());
+            collect(message, fq_type, &mut extensions);
+        }
+        extensions
     }
 
-    fn register_external_enum(
+    /// Extension node with ID `pkg.name` (`pkg.Message.name` when declared
+    /// inside a message, which then becomes its parent).
+    fn create_extension_node(
+        &self,
+        extension: &FieldDescriptorProto,
+        package: &str,
+        file_name: &str,
+        scope_fq: Option<&str>,
+        resolver: &Resolver,
+    ) -> Option<Node> {
+        let name = extension.name.as_ref()?;
+        let fq_name = Self::scoped_fq_type(package, scope_fq, name);
+        let scope = fq_name
+            .strip_suffix(name.as_str())
+            .and_then(|s| s.strip_suffix('.'))
+            .unwrap_or_default();
+        // Extensions are never part of a oneof, so an empty scope message will do
+        let (presence, encoding) = resolver.field(scope, &DescriptorProto::default(), extension);
+
+        let field = FieldInfo {
+            name: name.clone(),
+            number: extension.number.unwrap_or(0),
+            type_name: Self::type_to_string(extension.r#type, extension.type_name.as_ref()),
+            type_ref: Self::type_ref(extension.type_name.as_ref()),
+            label: Self::label_to_string(extension.label, presence),
+            presence,
+            encoding,
+            default_value: extension.default_value.clone(),
+            ..Default::default()
+        };
+        let comments = self.comments(&fq_name);
+        let parent_id = scope_fq.and_then(|s| self.type_to_node_id.get(s)).cloned();
+
+        Some(
+            Node::new(
+                fq_name.trim_start_matches('.').to_string(),
+                NodeType::Extension,
+                package.to_string(),
+                Self::relative_name(package, &fq_name),
+                file_name.to_string(),
+                NodeDetails::Extension {
+                    extendee: extension.extendee().trim_start_matches('.').to_string(),
+                    field,
+                },
+            )
+            .with_module(self.module_of(file_name))
+            .with_parent(parent_id)
+            .with_comments(comments)
+            .with_deprecated(extension.options.as_ref().is_some_and(|o| o.deprecated()))
+            .with_span(self.span(&fq_name)),
+        )
+    }
+
+    fn register_external_type(
         &mut self,
-        enum_type: &prost_types::EnumDescriptorProto,
+        message: &prost_types::DescriptorProto,
         package: &str,
+        file_name: &str,
     ) {
-        if let Some(name) = &enum_type.name {
-            let id = Self::generate_node_id(package, name);
+        if let Some(name) = &message.name {
             let fq_type = Self::generate_fq_type(package, name);
-            self.type_to_node_id.insert(fq_type, id);
             self.external_packages.insert(package.to_string());
+            self.register_external_message(message, &fq_type, file_name);
         }
     }
 
-    fn register_nested_message(&mut self, message: &prost_types::DescriptorProto, parent_fq: &str) {
-        if let Some(name) = &message.name {
-            // Nested type FQ: .package.Parent.Nested
-            let fq_type = format!("{parent_fq}.{name}");
-            // Node ID uses dot notation: package.Parent.Nested
-            let id = fq_type.trim_start_matches('.').to_string();
-            self.type_to_node_id.insert(fq_type.clone(), id);
+    fn register_external_message(
+        &mut self,
+        message: &prost_types::DescriptorProto,
+        fq_type: &str,
+        file_name: &str,
+    ) {
+        self.register_external_fq(fq_type, file_name);
 
-            for nested in &message.nested_type {
-                self.register_nested_message(nested, &fq_type);
+        for nested in &message.nested_type {
+            if let Some(name) = &nested.name {
+                self.register_external_message(nested, &format!("{fq_type}.{name}"), file_name);
+            }
+        }
+        for nested_enum in &message.enum_type {
+            if let Some(name) = &nested_enum.name {
+                self.register_external_fq(&format!("{fq_type}.{name}"), file_name);
             }
         }
     }
 
-    fn register_nested_enum(
+    fn register_external_enum(
         &mut self,
         enum_type: &prost_types::EnumDescriptorProto,
-        parent_fq: &str,
+        package: &str,
+        file_name: &str,
     ) {
         if let Some(name) = &enum_type.name {
-            let fq_type = format!("{parent_fq}.{name}");
-            let id = fq_type.trim_start_matches('.').to_string();
-            self.type_to_node_id.insert(fq_type, id);
+            let fq_type = Self::generate_fq_type(package, name);
+            self.external_packages.insert(package.to_string());
+            self.register_external_fq(&fq_type, file_name);
         }
     }
 
+    fn register_external_fq(&mut self, fq_type: &str, file_name: &str) {
+        let id = fq_type.trim_start_matches('.').to_string();
+        self.type_to_node_id.insert(fq_type.to_string(), id);
+        self.external_type_files
+            .insert(fq_type.to_string(), file_name.to_string());
+    }
+
     fn create_service_edges(
         &self,
         service: &prost_types::ServiceDescriptorProto,
@@ -319,17 +905,28 @@ impl Analyzer {
 
         let mut edges = Vec::new();
         for method in &service.method {
-            // Edge to input type
-            if let Some(input_type) = &method.input_type
-                && let Some(target_id) = self.type_to_node_id.get(input_type)
-            {
-                edges.push(Edge::new(source_id.clone(), target_id.clone()));
-            }
-            // Edge to output type
-            if let Some(output_type) = &method.output_type
-                && let Some(target_id) = self.type_to_node_id.get(output_type)
-            {
-                edges.push(Edge::new(source_id.clone(), target_id.clone()));
+            let method_source = if self.method_nodes {
+                let method_id = format!("{source_id}.{}", method.name());
+                edges.push(
+                    Edge::new(source_id.clone(), method_id.clone())
+                        .with_label(EdgeLabel::new(EdgeKind::Method).with_name(method.name())),
+                );
+                method_id
+            } else {
+                source_id.clone()
+            };
+            for (type_name, kind) in [
+                (&method.input_type, EdgeKind::RpcInput),
+                (&method.output_type, EdgeKind::RpcOutput),
+            ] {
+                if let Some(type_name) = type_name
+                    && let Some(target_id) = self.type_to_node_id.get(type_name)
+                {
+                    edges.push(
+                        Edge::new(method_source.clone(), target_id.clone())
+                            .with_label(EdgeLabel::new(kind).with_name(method.name())),
+                    );
+                }
             }
         }
         edges
@@ -339,33 +936,152 @@ impl Analyzer {
         &self,
         message: &prost_types::DescriptorProto,
         package: &str,
+        parent_fq: Option<&str>,
         nodes: &mut Vec<Node>,
     ) -> Vec<Edge> {
         let message_name = match &message.name {
             Some(n) => n,
             None => return Vec::new(),
         };
-        let source_id = Self::generate_node_id(package, message_name);
+        let fq_type = Self::scoped_fq_type(package, parent_fq, message_name);
+        let Some(source_id) = self.type_to_node_id.get(&fq_type) else {
+            return Vec::new();
+        };
 
         let mut edges = Vec::new();
-        for field in &message.field {
-            if let Some(type_name) = &field.type_name
+        for field in message
+            .field
+            .iter()
+            .filter(|f| self.inlined_wrapper(f).is_none())
+        {
+            // Map fields depend on their value type, not the synthetic entry
+            let (referenced, label) = match Self::map_fields(message, &fq_type, field) {
+                Some((_, value)) => (
+                    value,
+                    EdgeLabel::new(EdgeKind::MapValue).with_cardinality(Cardinality::Map),
+                ),
+                None => (
+                    field,
+                    EdgeLabel::new(EdgeKind::Field).with_cardinality(
+                        if field.label() == prost_types::field_descriptor_proto::Label::Repeated {
+                            Cardinality::Repeated
+                        } else {
+                            Cardinality::Singular
+                        },
+                    ),
+                ),
+            };
+            if let Some(type_name) = &referenced.type_name
                 && let Some(target_id) = self.type_to_node_id.get(type_name)
             {
+                // References between types folded into the same node are internal
+                if target_id == source_id && *type_name != fq_type {
+                    continue;
+                }
                 // Create External node if referenced type is from external package
                 if self.is_external_type(type_name) {
                     self.ensure_external_node(target_id, type_name, nodes);
                 }
-                edges.push(Edge::new(source_id.clone(), target_id.clone()));
+                edges.push(
+                    Edge::new(source_id.clone(), target_id.clone())
+                        .with_label(label.with_name(field.name())),
+                );
+            }
+        }
+
+        // Containment edges to nested types that have nodes of their own
+        let nested_names = message
+            .nested_type
+            .iter()
+            .filter(|m| !Self::is_map_entry(m))
+            .map(|m| m.name())
+            .chain(message.enum_type.iter().map(|e| e.name()));
+        for name in nested_names {
+            if let Some(nested_id) = self.type_to_node_id.get(&format!("{fq_type}.{name}"))
+                && nested_id != source_id
+            {
+                edges.push(
+                    Edge::new(source_id.clone(), nested_id.clone())
+                        .with_label(EdgeLabel::new(EdgeKind::Nesting)),
+                );
+            }
+        }
+
+        for nested in message
+            .nested_type
+            .iter()
+            .filter(|m| !Self::is_map_entry(m))
+        {
+            edges.extend(self.create_message_edges(nested, package, Some(&fq_type), nodes));
+        }
+        edges
+    }
+
+    /// Edges from an extension to the message it extends and to its value
+    /// type, and from the message it is declared in.
+    fn create_extension_edges(
+        &self,
+        extension: &FieldDescriptorProto,
+        package: &str,
+        scope_fq: Option<&str>,
+        nodes: &mut Vec<Node>,
+    ) -> Vec<Edge> {
+        let Some(name) = extension.name.as_ref() else {
+            return Vec::new();
+        };
+        let source_id = Self::scoped_fq_type(package, scope_fq, name)
+            .trim_start_matches('.')
+            .to_string();
+
+        let mut edges = Vec::new();
+        if let Some(scope_id) = scope_fq.and_then(|s| self.type_to_node_id.get(s)) {
+            edges.push(
+                Edge::new(scope_id.clone(), source_id.clone())
+                    .with_label(EdgeLabel::new(EdgeKind::Nesting)),
+            );
+        }
+
+        let cardinality = if extension.label() == Label::Repeated {
+            Cardinality::Repeated
+        } else {
+            Cardinality::Singular
+        };
+        for (type_name, label) in [
+            (
+                &extension.extendee,
+                EdgeLabel::new(EdgeKind::Extension).with_name(name),
+            ),
+            (
+                &extension.type_name,
+                EdgeLabel::new(EdgeKind::Field)
+                    .with_name(name)
+                    .with_cardinality(cardinality),
+            ),
+        ] {
+            if let Some(type_name) = type_name
+                && let Some(target_id) = self.type_to_node_id.get(type_name)
+            {
+                if self.is_external_type(type_name) {
+                    self.ensure_external_node(target_id, type_name, nodes);
+                }
+                edges.push(Edge::new(source_id.clone(), target_id.clone()).with_label(label));
             }
         }
         edges
     }
 
+    /// Scalar shown instead of a singular wrapper-typed field, when wrappers
+    /// are inlined. Repeated wrappers keep their type, as their elements
+    /// cannot be null.
+    fn inlined_wrapper(&self, field: &FieldDescriptorProto) -> Option<&'static str> {
+        if !self.inline_wrappers || field.label() == Label::Repeated {
+            return None;
+        }
+        well_known::wrapped_scalar(field.type_name())
+    }
+
     fn is_external_type(&self, fq_type: &str) -> bool {
-        // Check if type starts with external packages
-        let type_without_dot = fq_type.trim_start_matches('.');
-        type_without_dot.starts_with("google.") || type_without_dot.starts_with("buf.")
+        self.external_type_files.contains_key(fq_type)
     }
 
     fn ensure_external_node(&self, id: &str, fq_type: &str, nodes: &mut Vec<Node>) {
@@ -382,25 +1098,146 @@ impl Analyzer {
             (type_without_dot.to_string(), String::new())
         };
 
-        // Determine file path from package
-        let file = format!("{}.proto", package.replace('.', "/"));
-
-        nodes.push(Node::new(
-            id.to_string(),
-            NodeType::External,
-            package,
-            label,
-            file,
-            NodeDetails::External,
-        ));
+        let well_known = well_known::lookup(fq_type);
+        let file = self
+            .external_type_files
+            .get(fq_type)
+            .map(String::as_str)
+            .or(well_known.as_ref().map(|(file, _)| *file))
+            .unwrap_or_default()
+            .to_string();
+        let module = self.module_of(&file);
+
+        nodes.push(
+            Node::new(
+                id.to_string(),
+                NodeType::External,
+                package,
+                label,
+                file,
+                NodeDetails::External {
+                    well_known: well_known.map(|(_, semantics)| semantics),
+                },
+            )
+            .with_module(module),
+        );
     }
 
+    /// Merge edges between the same pair of nodes, keeping every label.
     fn deduplicate_edges(edges: Vec<Edge>) -> Vec<Edge> {
-        let mut seen = HashSet::new();
-        edges
-            .into_iter()
-            .filter(|e| seen.insert((e.source.clone(), e.target.clone())))
-            .collect()
+        let mut index: HashMap<(String, String), usize> = HashMap::new();
+        let mut merged: Vec<Edge> = Vec::new();
+        for edge in edges {
+            let key = (edge.source.clone(), edge.target.clone());
+            match index.get(&key) {
+                Some(&i) => merged[i].merge(edge),
+                None => {
+                    index.insert(key, merged.len());
+                    merged.push(edge);
+                }
+            }
+        }
+        merged
+    }
+
+    /// The real oneof `field` is declared in; proto3 `optional` fields live in
+    /// a synthetic single-field oneof that is not part of the schema.
+    fn oneof_name(
+        message: &prost_types::DescriptorProto,
+        field: &prost_types::FieldDescriptorProto,
+    ) -> Option<String> {
+        if field.proto3_optional() {
+            return None;
+        }
+        let index = usize::try_from(field.oneof_index?).ok()?;
+        message.oneof_decl.get(index)?.name.clone()
+    }
+
+    /// Synthetic `*Entry` message generated for a map field.
+    fn is_map_entry(message: &prost_types::DescriptorProto) -> bool {
+        message
+            .options
+            .as_ref()
+            .and_then(|o| o.map_entry)
+            .unwrap_or(false)
+    }
+
+    /// Key and value fields of the map entry `field` refers to, if it is a
+    /// map field of `message` (whose fully-qualified name is `fq_type`).
+    fn map_fields<'m>(
+        message: &'m prost_types::DescriptorProto,
+        fq_type: &str,
+        field: &prost_types::FieldDescriptorProto,
+    ) -> Option<(
+        &'m prost_types::FieldDescriptorProto,
+        &'m prost_types::FieldDescriptorProto,
+    )> {
+        let type_name = field.type_name.as_deref()?;
+        let entry = message.nested_type.iter().find(|nested| {
+            Self::is_map_entry(nested)
+                && type_name
+                    .strip_prefix(fq_type)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    == Some(nested.name())
+        })?;
+        let key = entry.field.iter().find(|f| f.number == Some(1))?;
+        let value = entry.field.iter().find(|f| f.number == Some(2))?;
+        Some((key, value))
+    }
+
+    /// Fully-qualified type reference without the leading dot; node IDs for
+    /// collapsed nested types are substituted by [`Self::resolve_type_refs`].
+    fn type_ref(type_name: Option<&String>) -> Option<String> {
+        type_name
+            .filter(|n| !n.is_empty())
+            .map(|n| n.trim_start_matches('.').to_string())
+    }
+
+    /// Point type refs at the node that represents the type, once every
+    /// type (including ones declared after their first use) is registered.
+    fn resolve_type_refs(&self, nodes: &mut [Node]) {
+        let resolve = |type_ref: &mut String| {
+            if let Some(id) = self.type_to_node_id.get(&format!(".{type_ref}")) {
+                type_ref.clone_from(id);
+            }
+        };
+        let resolve_fields = |fields: &mut [FieldInfo]| {
+            for type_ref in fields.iter_mut().filter_map(|f| f.type_ref.as_mut()) {
+                resolve(type_ref);
+            }
+        };
+
+        let resolve_method = |method: &mut MethodSignature| {
+            for type_ref in [&mut method.input_type_ref, &mut method.output_type_ref]
+                .into_iter()
+                .flatten()
+            {
+                resolve(type_ref);
+            }
+        };
+
+        for node in nodes {
+            match &mut node.details {
+                NodeDetails::Message { fields, .. } => resolve_fields(fields),
+                NodeDetails::Service { methods, messages } => {
+                    for method in methods {
+                        resolve_method(method);
+                    }
+                    for message in messages {
+                        resolve(&mut message.type_ref);
+                        resolve_fields(&mut message.fields);
+                    }
+                }
+                NodeDetails::Method { signature } => resolve_method(signature),
+                NodeDetails::Extension { extendee, field } => {
+                    resolve(extendee);
+                    resolve_fields(std::slice::from_mut(field));
+                }
+                NodeDetails::Enum { .. }
+                | NodeDetails::External { .. }
+                | NodeDetails::File { .. } => {}
+            }
+        }
     }
 
     /// `".user.v1.GetUserRequest"` → `"GetUserRequest"`
@@ -410,7 +1247,11 @@ impl Analyzer {
             .unwrap_or_default()
     }
 
-    fn label_to_string(label: Option<i32>) -> String {
+    /// Editions express `required` through features rather than the label.
+    fn label_to_string(label: Option<i32>, presence: Option<FieldPresence>) -> String {
+        if presence == Some(FieldPresence::Required) {
+            return "required".to_string();
+        }
         label
             .and_then(|l| Label::try_from(l).ok())
             .map(|l| match l {
@@ -452,22 +1293,6 @@ impl Analyzer {
             .unwrap_or("unknown")
             .to_string()
     }
-
-    fn group_packages(nodes: &[Node]) -> Vec<Package> {
-        let mut package_map: HashMap<String, Vec<String>> = HashMap::new();
-
-        for node in nodes {
-            package_map
-                .entry(node.package.clone())
-                .or_default()
-                .push(node.id.clone());
-        }
-
-        package_map
-            .into_iter()
-            .map(|(id, node_ids)| Package::new(id, node_ids))
-            .collect()
-    }
 }
 
 impl Default for Analyzer {
@@ -484,6 +1309,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::domain::{FieldEncoding, WellKnownCategory};
 
     #[test]
     fn test_definition_level_nodes() {
@@ -671,6 +1497,945 @@ mod tests {
         assert_eq!(graph.edges[0].target, "google.protobuf.Timestamp");
     }
 
+    #[test]
+    fn test_buf_image_import_classification() {
+        let fds = FileDescriptorSet {
+            file: vec![
+                FileDescriptorProto {
+                    name: Some("acme/partner/v1/partner.proto".to_string()),
+                    package: Some("acme.partner.v1".to_string()),
+                    message_type: vec![DescriptorProto {
+                        name: Some("Partner".to_string()),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                FileDescriptorProto {
+                    name: Some("user/v1/user.proto".to_string()),
+                    package: Some("user.v1".to_string()),
+                    message_type: vec![DescriptorProto {
+                        name: Some("User".to_string()),
+                        field: vec![FieldDescriptorProto {
+                            name: Some("partner".to_string()),
+                            number: Some(1),
+                            r#type: Some(Type::Message as i32),
+                            type_name: Some(".acme.partner.v1.Partner".to_string()),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+        };
+        let image = Image {
+            descriptors: fds,
+            files: HashMap::from([
+                (
+                    "acme/partner/v1/partner.proto".to_string(),
+                    ImageFileInfo {
+                        is_import: true,
+                        module: Some("buf.build/acme/partner".to_string()),
+                        commit: None,
+                    },
+                ),
+                ("user/v1/user.proto".to_string(), ImageFileInfo::default()),
+            ]),
+            ..Default::default()
+        };
+
+        let mut analyzer = Analyzer::new();
+        let graph = analyzer.analyze_image(&image);
+
+        // Imported file is External despite not living under google/ or buf/
+        assert_eq!(graph.nodes.len(), 2);
+        let partner = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == "acme.partner.v1.Partner")
+            .expect("External partner should exist");
+        assert_eq!(partner.node_type, NodeType::External);
+        assert_eq!(partner.file, "acme/partner/v1/partner.proto");
+        assert_eq!(partner.module.as_deref(), Some("buf.build/acme/partner"));
+
+        let user = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == "user.v1.User")
+            .expect("User should exist");
+        assert_eq!(user.node_type, NodeType::Message);
+        assert_eq!(graph.edges.len(), 1);
+
+        // A later plain descriptor set does not inherit the image's imports
+        let graph = analyzer.analyze(&image.descriptors);
+        let partner = graph
+            .find_node("acme.partner.v1.Partner")
+            .expect("partner should exist");
+        assert_eq!(partner.node_type, NodeType::Message);
+        assert_eq!(partner.module, None);
+    }
+
+    #[test]
+    fn test_editions_presence_and_encoding() {
+        let source = r#"
+            edition = "2023";
+            package shop.v1;
+            option features.field_presence = IMPLICIT;
+
+            message Order {
+              string id = 1;
+              int32 quantity = 2 [features.field_presence = LEGACY_REQUIRED];
+              repeated int32 tags = 3 [features.repeated_field_encoding = EXPANDED];
+              Order parent = 4 [features.message_encoding = DELIMITED];
+              Status status = 5;
+            }
+            enum Status {
+              option features.enum_type = CLOSED;
+              STATUS_UNSPECIFIED = 0;
+            }
+        "#;
+        let fds = compile_one("shop/v1/order.proto", source);
+
+        let graph = Analyzer::new().analyze(&fds);
+        let fields = message_fields(&graph, "shop.v1.Order");
+        let resolved: Vec<(&str, &str, Option<FieldPresence>, Option<FieldEncoding>)> = fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.label.as_str(), f.presence, f.encoding))
+            .collect();
+        assert_eq!(
+            resolved,
+            vec![
+                ("id", "optional", Some(FieldPresence::Implicit), None),
+                ("quantity", "required", Some(FieldPresence::Required), None),
+                ("tags", "repeated", None, Some(FieldEncoding::Expanded)),
+                (
+                    "parent",
+                    "optional",
+                    Some(FieldPresence::Explicit),
+                    Some(FieldEncoding::Delimited)
+                ),
+                ("status", "optional", Some(FieldPresence::Implicit), None),
+            ]
+        );
+
+        let closed = graph.nodes.iter().any(|n| {
+            matches!(n.details, NodeDetails::Enum { closed: true, .. }) && n.label == "Status"
+        });
+        assert!(closed);
+    }
+
+    fn nested_types_fds() -> FileDescriptorSet {
+        let source = r#"
+            syntax = "proto3";
+            package shop.v1;
+
+            message Order {
+              message Item {
+                Sku sku = 1;
+                Kind kind = 2;
+                enum Kind { KIND_UNSPECIFIED = 0; }
+              }
+              repeated Item items = 1;
+              Money total = 2;
+            }
+            message Sku { string code = 1; }
+            message Money { string currency = 1; }
+        "#;
+        compile_one("shop/v1/order.proto", source)
+    }
+
+    fn compile_one(name: &str, source: &str) -> FileDescriptorSet {
+        crate::parser::compile_with(&[name.to_string()], |file| {
+            Ok((file == name).then(|| source.to_string()))
+        })
+        .expect("compile")
+    }
+
+    fn message_fields<'a>(graph: &'a GraphModel, id: &str) -> &'a [FieldInfo] {
+        match graph.find_node(id).map(|n| &n.details) {
+            Some(NodeDetails::Message { fields, .. }) => fields,
+            _ => panic!("expected message details for {id}"),
+        }
+    }
+
+    fn edge_pairs(graph: &GraphModel) -> Vec<(&str, &str)> {
+        let mut pairs: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str()))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    #[test]
+    fn test_nested_types_become_nodes() {
+        let graph = Analyzer::new().analyze(&nested_types_fds());
+
+        let item = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == "shop.v1.Order.Item")
+            .expect("nested message node");
+        assert_eq!(item.label, "Order.Item");
+        assert_eq!(item.parent.as_deref(), Some("shop.v1.Order"));
+
+        let kind = graph
+            .nodes
+            .iter()
+            .find(|n| n.id == "shop.v1.Order.Item.Kind")
+            .expect("nested enum node");
+        assert_eq!(kind.node_type, NodeType::Enum);
+        assert_eq!(kind.parent.as_deref(), Some("shop.v1.Order.Item"));
+
+        assert_eq!(
+            edge_pairs(&graph),
+            vec![
+                ("shop.v1.Order", "shop.v1.Money"),
+                ("shop.v1.Order", "shop.v1.Order.Item"),
+                ("shop.v1.Order.Item", "shop.v1.Order.Item.Kind"),
+                ("shop.v1.Order.Item", "shop.v1.Sku"),
+            ]
+        );
+
+        // The field and the containment relation are merged into one edge
+        let items = graph
+            .edges
+            .iter()
+            .find(|e| e.target == "shop.v1.Order.Item")
+            .expect("Order -> Order.Item");
+        assert_eq!(
+            items.labels,
+            vec![
+                EdgeLabel::new(EdgeKind::Field)
+                    .with_name("items")
+                    .with_cardinality(Cardinality::Repeated),
+                EdgeLabel::new(EdgeKind::Nesting),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapse_nested_types() {
+        let graph = Analyzer::new()
+            .with_nested_collapsed(true)
+            .analyze(&nested_types_fds());
+
+        let mut ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec!["shop.v1.Money", "shop.v1.Order", "shop.v1.Sku"]);
+        assert!(graph.nodes.iter().all(|n| n.parent.is_none()));
+
+        // Nested fields are attributed to Order; references inside it vanish
+        assert_eq!(
+            edge_pairs(&graph),
+            vec![
+                ("shop.v1.Order", "shop.v1.Money"),
+                ("shop.v1.Order", "shop.v1.Sku"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_fields() {
+        let source = r#"
+            syntax = "proto3";
+            package shop.v1;
+
+            message Cart {
+              map<string, Money> prices = 1;
+              map<int32, string> notes = 2;
+            }
+            message Money { string currency = 1; }
+        "#;
+        let fds = compile_one("shop/v1/cart.proto", source);
+        let graph = Analyzer::new().analyze(&fds);
+
+        assert!(!graph.nodes.iter().any(|n| n.label.ends_with("Entry")));
+        assert_eq!(edge_pairs(&graph), vec![("shop.v1.Cart", "shop.v1.Money")]);
+        assert_eq!(
+            graph.edges[0].labels,
+            vec![
+                EdgeLabel::new(EdgeKind::MapValue)
+                    .with_name("prices")
+                    .with_cardinality(Cardinality::Map)
+            ]
+        );
+
+        let fields = message_fields(&graph, "shop.v1.Cart");
+        assert_eq!(fields[0].type_name, "map<string, Money>");
+        assert_eq!(
+            fields[0].map,
+            Some(MapType {
+                key_type: "string".to_string(),
+                value_type: "Money".to_string(),
+            })
+        );
+        assert_eq!(fields[0].label, "");
+        assert_eq!(fields[1].type_name, "map<int32, string>");
+        assert_eq!(fields[1].label, "");
+    }
+
+    #[test]
+    fn test_oneof_and_proto3_optional() {
+        let source = r#"
+            syntax = "proto3";
+            package pay.v1;
+
+            message Payment {
+              optional string note = 1;
+              oneof method {
+                string card = 2;
+                string iban = 3;
+              }
+              string id = 4;
+            }
+        "#;
+        let fds = compile_one("pay/v1/pay.proto", source);
+        let graph = Analyzer::new().analyze(&fds);
+
+        let fields = message_fields(&graph, "pay.v1.Payment");
+        let modeled: Vec<(&str, Option<&str>, bool)> = fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.oneof.as_deref(), f.proto3_optional))
+            .collect();
+        assert_eq!(
+            modeled,
+            vec![
+                ("note", None, true),
+                ("card", Some("method"), false),
+                ("iban", Some("method"), false),
+                ("id", None, false),
+            ]
+        );
+
+        let Some(NodeDetails::Message { oneofs, .. }) =
+            graph.find_node("pay.v1.Payment").map(|n| &n.details)
+        else {
+            panic!("expected message details");
+        };
+        assert_eq!(
+            oneofs,
+            &vec![OneofGroup {
+                name: "method".to_string(),
+                fields: vec!["card".to_string(), "iban".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_type_refs_disambiguate_short_names() {
+        let sources = HashMap::from([
+            (
+                "billing/v1/billing.proto",
+                r#"
+                syntax = "proto3";
+                package billing.v1;
+                import "shop/v1/shop.proto";
+                service BillingService {
+                  rpc Charge(shop.v1.Money) returns (Money);
+                }
+                message Money { shop.v1.Money amount = 1; Invoice.Line line = 2; }
+                message Invoice { message Line { string sku = 1; } }
+                "#,
+            ),
+            (
+                "shop/v1/shop.proto",
+                r#"
+                syntax = "proto3";
+                package shop.v1;
+                message Money { string currency = 1; }
+                "#,
+            ),
+        ]);
+        let fds = crate::parser::compile_with(&["billing/v1/billing.proto".to_string()], |name| {
+            Ok(sources.get(name).map(|s| s.to_string()))
+        })
+        .expect("compile");
+
+        let field_refs = |graph: &GraphModel| -> Vec<Option<String>> {
+            message_fields(graph, "billing.v1.Money")
+                .iter()
+                .map(|f| f.type_ref.clone())
+                .collect()
+        };
+
+        let graph = Analyzer::new().analyze(&fds);
+        assert_eq!(
+            field_refs(&graph),
+            vec![
+                Some("shop.v1.Money".to_string()),
+                Some("billing.v1.Invoice.Line".to_string()),
+            ]
+        );
+        let NodeDetails::Service { methods, messages } = &graph
+            .nodes
+            .iter()
+            .find(|n| n.node_type == NodeType::Service)
+            .expect("service")
+            .details
+        else {
+            panic!("service details");
+        };
+        assert_eq!(methods[0].input_type, methods[0].output_type);
+        assert_eq!(methods[0].input_type_ref.as_deref(), Some("shop.v1.Money"));
+        assert_eq!(
+            methods[0].output_type_ref.as_deref(),
+            Some("billing.v1.Money")
+        );
+        let message_refs: Vec<&str> = messages.iter().map(|m| m.type_ref.as_str()).collect();
+        assert!(message_refs.contains(&"billing.v1.Money"));
+
+        // Collapsed nested types resolve to their enclosing node, even when
+        // declared after the field that uses them
+        let collapsed = Analyzer::new().with_nested_collapsed(true).analyze(&fds);
+        assert_eq!(
+            field_refs(&collapsed)[1].as_deref(),
+            Some("billing.v1.Invoice")
+        );
+    }
+
+    #[test]
+    fn test_method_nodes() {
+        let source = r#"
+            syntax = "proto3";
+            package shop.v1;
+
+            service OrderService {
+              rpc GetOrder(GetOrderRequest) returns (Order);
+              rpc WatchOrders(GetOrderRequest) returns (stream Order);
+            }
+            message GetOrderRequest { string id = 1; }
+            message Order { string id = 1; }
+        "#;
+        let fds = compile_one("shop/v1/order.proto", source);
+        let graph = Analyzer::new().with_method_nodes(true).analyze(&fds);
+
+        let watch = graph
+            .find_node("shop.v1.OrderService.WatchOrders")
+            .expect("method node");
+        assert_eq!(watch.node_type, NodeType::Method);
+        assert_eq!(watch.label, "OrderService.WatchOrders");
+        assert_eq!(watch.parent.as_deref(), Some("shop.v1.OrderService"));
+        assert!(matches!(
+            &watch.details,
+            NodeDetails::Method { signature } if signature.streaming == StreamingMode::ServerStreaming
+        ));
+
+        assert_eq!(
+            edge_pairs(&graph),
+            vec![
+                ("shop.v1.OrderService", "shop.v1.OrderService.GetOrder"),
+                ("shop.v1.OrderService", "shop.v1.OrderService.WatchOrders"),
+                ("shop.v1.OrderService.GetOrder", "shop.v1.GetOrderRequest"),
+                ("shop.v1.OrderService.GetOrder", "shop.v1.Order"),
+                (
+                    "shop.v1.OrderService.WatchOrders",
+                    "shop.v1.GetOrderRequest"
+                ),
+                ("shop.v1.OrderService.WatchOrders", "shop.v1.Order"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_level_import_graph() {
+        let sources = HashMap::from([
+            (
+                "app/v1/app.proto",
+                r#"
+                syntax = "proto3";
+                package app.v1;
+                import public "app/v1/common.proto";
+                import weak "app/v1/legacy.proto";
+                import "google/protobuf/timestamp.proto";
+                message Event { google.protobuf.Timestamp at = 1; }
+                "#,
+            ),
+            (
+                "app/v1/common.proto",
+                "syntax = \"proto3\"; package app.v1; message Common {}",
+            ),
+            (
+                "app/v1/legacy.proto",
+                "syntax = \"proto3\"; package app.v1; message Legacy {}",
+            ),
+        ]);
+        let fds = crate::parser::compile_with(&["app/v1/app.proto".to_string()], |name| {
+            Ok(sources.get(name).map(|s| s.to_string()))
+        })
+        .expect("compile");
+        let graph = Analyzer::new().analyze_files(&fds.into());
+
+        let app = graph.find_node("app/v1/app.proto").expect("file node");
+        assert_eq!(app.node_type, NodeType::File);
+        assert_eq!(
+            app.details,
+            NodeDetails::File {
+                unused_imports: vec!["app/v1/legacy.proto".to_string()],
+                indirect_imports: vec![],
+                missing_imports: vec![],
+            }
+        );
+        let timestamp = graph
+            .find_node("google/protobuf/timestamp.proto")
+            .expect("external file node");
+        assert_eq!(timestamp.node_type, NodeType::External);
+
+        let imports: Vec<(&str, EdgeKind)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.target.as_str(), e.labels[0].kind))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("app/v1/common.proto", EdgeKind::PublicImport),
+                ("app/v1/legacy.proto", EdgeKind::WeakImport),
+                ("google/protobuf/timestamp.proto", EdgeKind::Import),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_level_imports_sorted() {
+        let sources = HashMap::from([
+            (
+                "app/v1/app.proto",
+                r#"
+                syntax = "proto3";
+                package app.v1;
+                import "app/v1/hub.proto";
+                message Event { Zone zone = 1; Badge badge = 2; }
+                "#,
+            ),
+            (
+                "app/v1/hub.proto",
+                r#"
+                syntax = "proto3";
+                package app.v1;
+                import public "app/v1/zone.proto";
+                import public "app/v1/badge.proto";
+                "#,
+            ),
+            (
+                "app/v1/zone.proto",
+                "syntax = \"proto3\"; package app.v1; message Zone {}",
+            ),
+            (
+                "app/v1/badge.proto",
+                "syntax = \"proto3\"; package app.v1; message Badge {}",
+            ),
+        ]);
+        let fds = crate::parser::compile_with(&["app/v1/app.proto".to_string()], |name| {
+            Ok(sources.get(name).map(|s| s.to_string()))
+        })
+        .expect("compile");
+        let graph = Analyzer::new().analyze_files(&fds.into());
+
+        // Reported in name order, not in the order the types are used
+        let Some(NodeDetails::File {
+            indirect_imports, ..
+        }) = graph.find_node("app/v1/app.proto").map(|n| &n.details)
+        else {
+            panic!("expected file details");
+        };
+        assert_eq!(
+            indirect_imports,
+            &vec![
+                "app/v1/badge.proto".to_string(),
+                "app/v1/zone.proto".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extensions() {
+        let source = r#"
+            syntax = "proto2";
+            package acme.v1;
+            import "google/protobuf/descriptor.proto";
+
+            message Audit { optional string reason = 1; }
+            message Order {
+              optional string id = 1;
+              extensions 100 to 199;
+            }
+            message Legacy {
+              extend Order { repeated Audit audits = 100; }
+            }
+            extend google.protobuf.FieldOptions { optional bool sensitive = 50001; }
+        "#;
+        let fds = compile_one("acme/v1/acme.proto", source);
+        let graph = Analyzer::new().analyze(&fds);
+
+        let sensitive = graph
+            .find_node("acme.v1.sensitive")
+            .expect("file extension");
+        assert_eq!(sensitive.node_type, NodeType::Extension);
+        let NodeDetails::Extension { extendee, field } = &sensitive.details else {
+            panic!("expected extension details");
+        };
+        assert_eq!(extendee, "google.protobuf.FieldOptions");
+        assert_eq!((field.number, field.type_name.as_str()), (50001, "bool"));
+        assert_eq!(
+            graph
+                .find_node("google.protobuf.FieldOptions")
+                .map(|n| &n.node_type),
+            Some(&NodeType::External)
+        );
+
+        let audits = graph
+            .find_node("acme.v1.Legacy.audits")
+            .expect("nested extension");
+        assert_eq!(audits.label, "Legacy.audits");
+        assert_eq!(audits.parent.as_deref(), Some("acme.v1.Legacy"));
+
+        let edges = edge_pairs(&graph);
+        for pair in [
+            ("acme.v1.sensitive", "google.protobuf.FieldOptions"),
+            ("acme.v1.Legacy", "acme.v1.Legacy.audits"),
+            ("acme.v1.Legacy.audits", "acme.v1.Order"),
+            ("acme.v1.Legacy.audits", "acme.v1.Audit"),
+        ] {
+            assert!(edges.contains(&pair), "missing edge {pair:?}");
+        }
+        let extends = graph
+            .edges
+            .iter()
+            .find(|e| e.target == "acme.v1.Order")
+            .expect("extendee edge");
+        assert_eq!(
+            extends.labels,
+            vec![EdgeLabel::new(EdgeKind::Extension).with_name("audits")]
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let source = r#"
+            syntax = "proto3";
+            package shop.v1;
+
+            // Places orders.
+            service OrderService {
+              // Create an order.
+              rpc Create(Order) returns (Order);
+            }
+
+            /* An order. */
+            message Order {
+              string id = 1; // Opaque ID.
+              Status status = 2;
+            }
+
+            enum Status {
+              // Not set.
+              STATUS_UNSPECIFIED = 0;
+            }
+        "#;
+        let fds = compile_one("shop/v1/shop.proto", source);
+        let graph = Analyzer::new().with_method_nodes(true).analyze(&fds);
+
+        let leading = |id: &str| graph.find_node(id).and_then(|n| n.comments.leading.clone());
+        assert_eq!(
+            leading("shop.v1.OrderService").as_deref(),
+            Some("Places orders.")
+        );
+        assert_eq!(leading("shop.v1.Order").as_deref(), Some("An order."));
+        assert_eq!(
+            leading("shop.v1.OrderService.Create").as_deref(),
+            Some("Create an order.")
+        );
+
+        let fields = message_fields(&graph, "shop.v1.Order");
+        assert_eq!(fields[0].comments.trailing.as_deref(), Some("Opaque ID."));
+        assert!(fields[1].comments.is_empty());
+
+        let Some(NodeDetails::Enum { values, .. }) =
+            graph.find_node("shop.v1.Status").map(|n| &n.details)
+        else {
+            panic!("expected enum details");
+        };
+        assert_eq!(values[0].comments.description(), Some("Not set."));
+    }
+
+    #[test]
+    fn test_spans() {
+        let source = "syntax = \"proto3\";\npackage shop.v1;\n\n\
+            service OrderService {\n  rpc Create(Order) returns (Order);\n}\n\n\
+            message Order {\n  string id = 1;\n}\n\n\
+            enum Status {\n  STATUS_UNSPECIFIED = 0;\n}\n";
+        let fds = compile_one("shop/v1/shop.proto", source);
+        let graph = Analyzer::new().with_method_nodes(true).analyze(&fds);
+
+        let span = |start_line, start_column, end_line, end_column| {
+            Some(Span {
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+            })
+        };
+        let node_span = |id: &str| graph.find_node(id).and_then(|n| n.span);
+        assert_eq!(node_span("shop.v1.OrderService"), span(4, 1, 6, 1));
+        assert_eq!(node_span("shop.v1.OrderService.Create"), span(5, 3, 5, 36));
+        assert_eq!(node_span("shop.v1.Order"), span(8, 1, 10, 1));
+        assert_eq!(node_span("shop.v1.Status"), span(12, 1, 14, 1));
+
+        let fields = message_fields(&graph, "shop.v1.Order");
+        assert_eq!(fields[0].span, span(9, 3, 9, 16));
+
+        let Some(NodeDetails::Enum { values, .. }) =
+            graph.find_node("shop.v1.Status").map(|n| &n.details)
+        else {
+            panic!("expected enum details");
+        };
+        assert_eq!(values[0].span, span(13, 3, 13, 25));
+    }
+
+    #[test]
+    fn test_classification() {
+        let sources: HashMap<&str, &str> = HashMap::from([
+            (
+                "shop/v1/order.proto",
+                r#"
+                syntax = "proto3";
+                package shop.v1;
+                import "third_party/envoy/route.proto";
+                import "platform/auth.proto";
+                message Order {
+                  envoy.route.Route route = 1;
+                  platform.auth.User buyer = 2;
+                }
+                "#,
+            ),
+            (
+                "third_party/envoy/route.proto",
+                r#"
+                syntax = "proto3";
+                package envoy.route;
+                message Route { string name = 1; }
+                "#,
+            ),
+            (
+                "platform/auth.proto",
+                r#"
+                syntax = "proto3";
+                package platform.auth;
+                message User { string id = 1; }
+                "#,
+            ),
+        ]);
+        let fds = crate::parser::compile_with(&["shop/v1/order.proto".to_string()], |name| {
+            Ok(sources.get(name).map(|s| s.to_string()))
+        })
+        .expect("compile");
+
+        let plain = Analyzer::new().analyze(&fds);
+        let route = plain.find_node("envoy.route.Route").expect("Route");
+        assert_eq!(route.node_type, NodeType::Message);
+
+        let graph = Analyzer::new()
+            .with_classification(Classification {
+                external_paths: vec!["third_party/**".to_string()],
+                foreign_packages: vec!["platform".to_string()],
+                ..Default::default()
+            })
+            .analyze(&fds);
+        let node = |id: &str| graph.find_node(id).expect(id);
+        assert_eq!(node("envoy.route.Route").node_type, NodeType::External);
+        assert_eq!(node("platform.auth.User").node_type, NodeType::Message);
+        assert!(node("platform.auth.User").foreign);
+        assert!(!node("shop.v1.Order").foreign);
+        assert!(!node("envoy.route.Route").foreign);
+    }
+
+    #[test]
+    fn test_well_known_types() {
+        let source = r#"
+            syntax = "proto3";
+            package acme.v1;
+            import "google/protobuf/timestamp.proto";
+            import "google/protobuf/wrappers.proto";
+            message Profile {
+              google.protobuf.StringValue nickname = 1;
+              repeated google.protobuf.Int64Value scores = 2;
+              google.protobuf.Timestamp updated_at = 3;
+            }
+        "#;
+        let fds = compile_one("acme/v1/profile.proto", source);
+
+        let graph = Analyzer::new().analyze(&fds);
+        let timestamp = graph
+            .find_node("google.protobuf.Timestamp")
+            .expect("Timestamp");
+        assert_eq!(timestamp.file, "google/protobuf/timestamp.proto");
+        let NodeDetails::External {
+            well_known: Some(well_known),
+        } = &timestamp.details
+        else {
+            panic!("expected well-known details");
+        };
+        assert_eq!(well_known.category, WellKnownCategory::Time);
+        let string_value = graph
+            .find_node("google.protobuf.StringValue")
+            .expect("StringValue");
+        assert!(matches!(
+            &string_value.details,
+            NodeDetails::External { well_known: Some(w) } if w.wraps.as_deref() == Some("string")
+        ));
+
+        let graph = Analyzer::new().with_wrappers_inlined(true).analyze(&fds);
+        assert!(graph.find_node("google.protobuf.StringValue").is_none());
+        assert!(graph.find_node("google.protobuf.Int64Value").is_some());
+        let targets: Vec<&str> = graph.edges.iter().map(|e| e.target.as_str()).collect();
+        assert_eq!(
+            targets,
+            vec!["google.protobuf.Int64Value", "google.protobuf.Timestamp"]
+        );
+
+        let fields = message_fields(&graph, "acme.v1.Profile");
+        assert_eq!(fields[0].type_name, "string");
+        assert!(fields[0].nullable);
+        assert_eq!(fields[0].type_ref, None);
+        assert_eq!(fields[1].type_name, "Int64Value");
+        assert!(!fields[1].nullable);
+    }
+
+    #[test]
+    fn test_reserved_and_extension_ranges() {
+        let source = r#"
+            syntax = "proto2";
+            package acme.v1;
+            message Order {
+              reserved 2, 9 to 11;
+              reserved "legacy_id";
+              extensions 100 to max;
+              optional string id = 1;
+            }
+            enum Status {
+              reserved 3 to max;
+              reserved "RETIRED";
+              STATUS_UNKNOWN = 0;
+            }
+        "#;
+        let fds = compile_one("acme/v1/order.proto", source);
+
+        let graph = Analyzer::new().analyze(&fds);
+        let Some(NodeDetails::Message {
+            reserved,
+            extension_ranges,
+            ..
+        }) = graph.find_node("acme.v1.Order").map(|n| &n.details)
+        else {
+            panic!("expected message details");
+        };
+        let ranges: Vec<String> = reserved.ranges.iter().map(ToString::to_string).collect();
+        assert_eq!(ranges, vec!["2", "9 to 11"]);
+        assert_eq!(reserved.names, vec!["legacy_id"]);
+        assert_eq!(
+            extension_ranges,
+            &vec![NumberRange::new(100, NumberRange::MAX_FIELD_NUMBER, false)]
+        );
+
+        let Some(NodeDetails::Enum { reserved, .. }) =
+            graph.find_node("acme.v1.Status").map(|n| &n.details)
+        else {
+            panic!("expected enum details");
+        };
+        assert!(reserved.contains_number(i32::MAX));
+        assert!(!reserved.contains_number(2));
+        assert_eq!(reserved.ranges[0].to_string(), "3 to max");
+        assert!(reserved.contains_name("RETIRED"));
+    }
+
+    #[test]
+    fn test_json_names_defaults_and_aliases() {
+        let source = r#"
+            syntax = "proto2";
+            package acme.v1;
+            message Order {
+              optional string order_id = 1;
+              optional string total_cents = 2 [json_name = "total"];
+              optional int32 quantity = 3 [default = 1];
+            }
+            enum Status {
+              option allow_alias = true;
+              STATUS_UNKNOWN = 0;
+              STATUS_STARTED = 1;
+              STATUS_RUNNING = 1;
+            }
+        "#;
+        let fds = compile_one("acme/v1/order.proto", source);
+
+        let graph = Analyzer::new().analyze(&fds);
+        let fields = message_fields(&graph, "acme.v1.Order");
+        assert_eq!(fields[0].json_name.as_deref(), Some("orderId"));
+        assert_eq!(fields[1].json_name.as_deref(), Some("total"));
+        assert_eq!(fields[2].default_value.as_deref(), Some("1"));
+        assert_eq!(fields[0].default_value, None);
+
+        let Some(NodeDetails::Enum {
+            values,
+            allow_alias,
+            ..
+        }) = graph.find_node("acme.v1.Status").map(|n| &n.details)
+        else {
+            panic!("expected enum details");
+        };
+        assert!(allow_alias);
+        assert_eq!(values[1].alias_of, None);
+        assert_eq!(values[2].alias_of.as_deref(), Some("STATUS_STARTED"));
+    }
+
+    #[test]
+    fn test_deprecated() {
+        let source = r#"
+            syntax = "proto3";
+            package shop.v1;
+
+            service OrderService {
+              option deprecated = true;
+              rpc Create(Order) returns (Order) { option deprecated = true; }
+              rpc Get(Order) returns (Order);
+            }
+
+            message Order {
+              option deprecated = true;
+              string id = 1 [deprecated = true];
+              string note = 2;
+            }
+
+            enum Status {
+              option deprecated = true;
+              STATUS_UNSPECIFIED = 0 [deprecated = true];
+              STATUS_OPEN = 1;
+            }
+        "#;
+        let fds = compile_one("shop/v1/shop.proto", source);
+        let graph = Analyzer::new().with_method_nodes(true).analyze(&fds);
+
+        let deprecated = |id: &str| graph.find_node(id).is_some_and(|n| n.deprecated);
+        assert!(deprecated("shop.v1.OrderService"));
+        assert!(deprecated("shop.v1.OrderService.Create"));
+        assert!(!deprecated("shop.v1.OrderService.Get"));
+        assert!(deprecated("shop.v1.Order"));
+        assert!(deprecated("shop.v1.Status"));
+
+        let fields = message_fields(&graph, "shop.v1.Order");
+        assert!(fields[0].deprecated);
+        assert!(!fields[1].deprecated);
+
+        let Some(NodeDetails::Enum { values, .. }) =
+            graph.find_node("shop.v1.Status").map(|n| &n.details)
+        else {
+            panic!("expected enum details");
+        };
+        assert!(values[0].deprecated);
+        assert!(!values[1].deprecated);
+    }
+
     #[test]
     fn test_analyze_empty() {
         let fds = FileDescriptorSet { file: vec![] };
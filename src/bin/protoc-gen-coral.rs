@@ -0,0 +1,13 @@
+//! protoc / buf plugin entry point: `CodeGeneratorRequest` on stdin,
+//! `CodeGeneratorResponse` on stdout.
+
+use std::io::Write;
+
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    let input = coral::read_stdin()?;
+    let output = coral::plugin::run(&input)?;
+    std::io::stdout().write_all(&output)?;
+    Ok(())
+}
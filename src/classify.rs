@@ -0,0 +1,154 @@
+//! User-configured classification of files and packages.
+//!
+//! Files are external when a buf image marks them as imports or when they
+//! live under `google/` or `buf/`. Vendored third-party protos usually do
+//! neither, so extra path globs and package prefixes can be configured here,
+//! from the command line or a JSON config file:
+//!
+//! ```json
+//! {
+//!   "externalPaths": ["third_party/**", "envoy/**"],
+//!   "externalPackages": ["grpc.gateway"],
+//!   "foreignPackages": ["platform"]
+//! }
+//! ```
+//!
+//! Foreign packages are analyzed like first-party ones, but their nodes are
+//! marked so that reports and the UI can keep them apart from our own.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoralError, Result};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Classification {
+    /// Globs over file paths: `*` and `?` stay within one directory, `**`
+    /// spans any number of them (e.g. `third_party/**`).
+    #[serde(default)]
+    pub external_paths: Vec<String>,
+    /// Package prefixes; `envoy` covers `envoy` and every `envoy.*` package.
+    #[serde(default)]
+    pub external_packages: Vec<String>,
+    /// Package prefixes shown in full but marked as foreign.
+    #[serde(default)]
+    pub foreign_packages: Vec<String>,
+}
+
+impl Classification {
+    /// Read a JSON config file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_json(&text).map_err(|e| CoralError::InvalidConfig {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    pub fn from_json(text: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(text)
+    }
+
+    /// Add the rules of `other` (e.g. command-line flags) to these.
+    pub fn extend(&mut self, other: Self) {
+        self.external_paths.extend(other.external_paths);
+        self.external_packages.extend(other.external_packages);
+        self.foreign_packages.extend(other.foreign_packages);
+    }
+
+    /// Whether the file `path` declaring `package` is configured as external.
+    #[must_use]
+    pub fn is_external(&self, path: &str, package: &str) -> bool {
+        self.external_paths
+            .iter()
+            .any(|glob| glob_match(glob.as_bytes(), path.as_bytes()))
+            || has_prefix(&self.external_packages, package)
+    }
+
+    #[must_use]
+    pub fn is_foreign(&self, package: &str) -> bool {
+        has_prefix(&self.foreign_packages, package)
+    }
+}
+
+/// Whether `package` is one of `prefixes` or nested below one of them.
+fn has_prefix(prefixes: &[String], package: &str) -> bool {
+    prefixes.iter().any(|prefix| {
+        let prefix = prefix.trim_end_matches(".*").trim_end_matches('.');
+        package
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+fn glob_match(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // `**/` also matches no directory at all
+            (0..=path.len()).any(|i| glob_match(rest, &path[i..]))
+                || rest
+                    .strip_prefix(b"/")
+                    .is_some_and(|rest| glob_match(rest, path))
+        }
+        [b'*', rest @ ..] => {
+            let segment = path.iter().position(|&b| b == b'/').unwrap_or(path.len());
+            (0..=segment).any(|i| glob_match(rest, &path[i..]))
+        }
+        [b'?', rest @ ..] => path
+            .split_first()
+            .is_some_and(|(&c, path)| c != b'/' && glob_match(rest, path)),
+        [c, rest @ ..] => path
+            .split_first()
+            .is_some_and(|(p, path)| p == c && glob_match(rest, path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        let matches = |glob: &str, path: &str| glob_match(glob.as_bytes(), path.as_bytes());
+        assert!(matches(
+            "third_party/**",
+            "third_party/envoy/api/v3/route.proto"
+        ));
+        assert!(matches(
+            "**/annotations.proto",
+            "google/api/annotations.proto"
+        ));
+        assert!(matches("**/annotations.proto", "annotations.proto"));
+        assert!(matches("envoy/*/v3/*.proto", "envoy/api/v3/route.proto"));
+        assert!(!matches("envoy/*.proto", "envoy/api/route.proto"));
+        assert!(matches("v?/x.proto", "v1/x.proto"));
+        assert!(!matches("third_party/**", "src/third_party.proto"));
+    }
+
+    #[test]
+    fn test_package_prefixes() {
+        let classification = Classification {
+            external_packages: vec!["envoy".to_string()],
+            foreign_packages: vec!["platform.*".to_string()],
+            ..Default::default()
+        };
+        assert!(classification.is_external("x.proto", "envoy"));
+        assert!(classification.is_external("x.proto", "envoy.api.v3"));
+        assert!(!classification.is_external("x.proto", "envoyproxy.v1"));
+        assert!(classification.is_foreign("platform.auth.v1"));
+        assert!(!classification.is_foreign("shop.v1"));
+    }
+
+    #[test]
+    fn test_from_json_rejects_unknown_keys() {
+        let parsed =
+            Classification::from_json(r#"{"externalPaths": ["third_party/**"]}"#).expect("parse");
+        assert_eq!(parsed.external_paths, vec!["third_party/**"]);
+        assert!(parsed.foreign_packages.is_empty());
+
+        assert!(Classification::from_json(r#"{"externalPath": []}"#).is_err());
+    }
+}
@@ -1,26 +1,317 @@
 //! Protobuf decoder module.
+//!
+//! Accepts both plain `FileDescriptorSet`s and buf images. A buf image
+//! (`buf.alpha.image.v1.Image`) is wire-compatible with `FileDescriptorSet`
+//! but each file additionally carries a `buf_extension` (field 8042) with
+//! its import status and module identity.
+//!
+//! Binary, proto3-JSON and text-format encodings are detected automatically,
+//! optionally wrapped in gzip.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
 
 use crate::error::{CoralError, Result};
+use crate::features::{self, FileFeatures};
+use flate2::read::GzDecoder;
 use prost::Message;
+use prost_reflect::{DeserializeOptions, DynamicMessage, ReflectMessage};
 use prost_types::FileDescriptorSet;
 
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Encoding of a descriptor set input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Protobuf wire format (`buf build -o image.binpb`).
+    Binary,
+    /// Proto3 JSON mapping (`buf build -o image.json`).
+    Json,
+    /// Protobuf text format (`buf build -o image.txtpb`).
+    Text,
+}
+
+impl InputFormat {
+    /// Guess the format from the first significant byte.
+    ///
+    /// Text inputs contain no control characters besides whitespace, whereas
+    /// a binary set starts with the `file` field tag (`0x0a`) and almost
+    /// always contains further tag bytes below `0x20`.
+    pub fn detect(bytes: &[u8]) -> Result<Self> {
+        let is_text = std::str::from_utf8(bytes)
+            .is_ok_and(|s| !s.chars().any(|c| c.is_control() && !c.is_whitespace()));
+
+        if is_text {
+            let first = bytes
+                .iter()
+                .find(|b| !b.is_ascii_whitespace())
+                .ok_or(CoralError::EmptyInput)?;
+            return match first {
+                b'{' => Ok(Self::Json),
+                b'#' | b'a'..=b'z' | b'A'..=b'Z' | b'_' => Ok(Self::Text),
+                other => Err(CoralError::UnrecognizedFormat {
+                    detail: format!("unexpected leading character {:?}", char::from(*other)),
+                }),
+            };
+        }
+
+        match bytes.first() {
+            Some(0x0a) => Ok(Self::Binary),
+            Some(other) => Err(CoralError::UnrecognizedFormat {
+                detail: format!("unexpected leading byte 0x{other:02x}"),
+            }),
+            None => Err(CoralError::EmptyInput),
+        }
+    }
+}
+
+/// Per-file metadata carried by a buf image.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageFileInfo {
+    /// The file was pulled in as a dependency rather than being part of the module being built.
+    pub is_import: bool,
+    /// Module identity, e.g. `buf.build/bufbuild/protovalidate`.
+    pub module: Option<String>,
+    /// Module commit the file was resolved from.
+    pub commit: Option<String>,
+}
+
+/// Descriptors together with any per-file buf image metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Image {
+    pub descriptors: FileDescriptorSet,
+    /// Keyed by file name; empty when the input was a plain `FileDescriptorSet`.
+    pub files: HashMap<String, ImageFileInfo>,
+    /// Editions data `prost-types` cannot represent, keyed by file name;
+    /// only files with an edition or explicit features are present.
+    pub features: HashMap<String, FileFeatures>,
+}
+
+impl Image {
+    /// Whether the input carried buf image metadata.
+    #[must_use]
+    pub fn is_buf_image(&self) -> bool {
+        !self.files.is_empty()
+    }
+}
+
+impl From<FileDescriptorSet> for Image {
+    fn from(descriptors: FileDescriptorSet) -> Self {
+        Self {
+            descriptors,
+            ..Default::default()
+        }
+    }
+}
+
+/// `buf.alpha.image.v1.Image`, decoding only what `FileDescriptorSet` drops.
+#[derive(Clone, PartialEq, Message)]
+struct ImageExtensions {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<ImageFileExtensions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ImageFileExtensions {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "8042")]
+    buf_extension: Option<ImageFileExtension>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ImageFileExtension {
+    #[prost(bool, optional, tag = "1")]
+    is_import: Option<bool>,
+    #[prost(message, optional, tag = "2")]
+    module_info: Option<ModuleInfo>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ModuleInfo {
+    #[prost(message, optional, tag = "1")]
+    name: Option<ModuleName>,
+    #[prost(string, optional, tag = "2")]
+    commit: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ModuleName {
+    #[prost(string, optional, tag = "1")]
+    remote: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    owner: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    repository: Option<String>,
+}
+
+impl ImageFileExtension {
+    fn into_file_info(self) -> ImageFileInfo {
+        let module_info = self.module_info.unwrap_or_default();
+        ImageFileInfo {
+            is_import: self.is_import.unwrap_or(false),
+            module: module_info.name.map(|n| n.full_name()),
+            commit: module_info.commit,
+        }
+    }
+}
+
+impl ModuleName {
+    fn full_name(&self) -> String {
+        [&self.remote, &self.owner, &self.repository]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Decode a descriptor set in any supported format, discarding image metadata.
 pub fn decode(bytes: &[u8]) -> Result<FileDescriptorSet> {
+    decode_image(bytes).map(|image| image.descriptors)
+}
+
+/// Decode a `FileDescriptorSet` or buf image, keeping buf's per-file metadata.
+pub fn decode_image(bytes: &[u8]) -> Result<Image> {
     if bytes.is_empty() {
         return Err(CoralError::EmptyInput);
     }
 
-    let fds = FileDescriptorSet::decode(bytes)?;
+    let bytes = gunzip(bytes)?;
+    let image = match InputFormat::detect(&bytes)? {
+        InputFormat::Binary => decode_binary(&bytes)?,
+        InputFormat::Json => decode_json(&bytes)?,
+        InputFormat::Text => decode_text(&bytes)?,
+    };
 
-    if fds.file.is_empty() {
+    if image.descriptors.file.is_empty() {
         return Err(CoralError::NoProtoFiles);
     }
 
-    Ok(fds)
+    Ok(image)
+}
+
+fn gunzip(bytes: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(Cow::Borrowed(bytes));
+    }
+
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut decompressed)
+        .map_err(|source| CoralError::InvalidGzip { source })?;
+
+    if decompressed.is_empty() {
+        return Err(CoralError::EmptyInput);
+    }
+    Ok(Cow::Owned(decompressed))
+}
+
+fn decode_binary(bytes: &[u8]) -> Result<Image> {
+    let descriptors = FileDescriptorSet::decode(bytes)?;
+    let extensions = ImageExtensions::decode(bytes)?;
+
+    let files = extensions
+        .file
+        .into_iter()
+        .filter_map(|file| {
+            let name = file.name?;
+            let extension = file.buf_extension?;
+            Some((name, extension.into_file_info()))
+        })
+        .collect();
+
+    Ok(Image {
+        descriptors,
+        files,
+        features: features::decode_descriptor_set(bytes)?,
+    })
+}
+
+fn decode_json(bytes: &[u8]) -> Result<Image> {
+    let invalid = |message: String| CoralError::InvalidJson { message };
+
+    let descriptor = FileDescriptorSet::default().descriptor();
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    // buf images add `bufExtension`, unknown to descriptor.proto
+    let options = DeserializeOptions::new().deny_unknown_fields(false);
+    let message = DynamicMessage::deserialize_with_options(descriptor, &mut deserializer, &options)
+        .map_err(|e| invalid(e.to_string()))?;
+    deserializer.end().map_err(|e| invalid(e.to_string()))?;
+    // Round-trip through the wire format to keep editions data
+    let mut image = decode_binary(&message.encode_to_vec())?;
+
+    let raw: serde_json::Value =
+        serde_json::from_slice(bytes).map_err(|e| invalid(e.to_string()))?;
+    image.files = raw["file"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|file| {
+            let name = file["name"].as_str()?;
+            let extension = file.get("bufExtension")?;
+            let module_info = &extension["moduleInfo"];
+            let module_name = &module_info["name"];
+            let module = ["remote", "owner", "repository"]
+                .iter()
+                .filter_map(|key| module_name[key].as_str())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some((
+                name.to_string(),
+                ImageFileInfo {
+                    is_import: extension["isImport"].as_bool().unwrap_or(false),
+                    module: (!module.is_empty()).then_some(module),
+                    commit: module_info["commit"].as_str().map(str::to_string),
+                },
+            ))
+        })
+        .collect();
+
+    Ok(image)
+}
+
+fn decode_text(bytes: &[u8]) -> Result<Image> {
+    let invalid = |message: String| CoralError::InvalidTextFormat { message };
+
+    let text = std::str::from_utf8(bytes).map_err(|e| invalid(e.to_string()))?;
+    let descriptor = FileDescriptorSet::default().descriptor();
+    let message =
+        DynamicMessage::parse_text_format(descriptor, text).map_err(|e| invalid(e.to_string()))?;
+    decode_binary(&message.encode_to_vec())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use prost::encoding::{WireType, encode_key, encode_varint};
+    use prost_types::FileDescriptorProto;
+
+    /// Field number of `ImageFile.buf_extension`.
+    const BUF_EXTENSION_TAG: u32 = 8042;
+
+    /// Encode an image by appending `buf_extension` to each encoded file.
+    fn encode_image(files: &[(&str, Option<ImageFileExtension>)]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for (name, extension) in files {
+            let mut file = FileDescriptorProto {
+                name: Some(name.to_string()),
+                ..Default::default()
+            }
+            .encode_to_vec();
+            if let Some(extension) = extension {
+                encode_key(BUF_EXTENSION_TAG, WireType::LengthDelimited, &mut file);
+                encode_varint(extension.encoded_len() as u64, &mut file);
+                extension.encode(&mut file).expect("encode extension");
+            }
+            encode_key(1, WireType::LengthDelimited, &mut buf);
+            encode_varint(file.len() as u64, &mut buf);
+            buf.extend(file);
+        }
+        buf
+    }
 
     #[test]
     fn test_empty_input() {
@@ -30,8 +321,188 @@ mod tests {
 
     #[test]
     fn test_invalid_protobuf() {
-        let invalid = b"not a valid protobuf";
+        // `file` tag followed by a truncated length
+        let invalid = b"\x0a\xff";
         let result = decode(invalid);
         assert!(matches!(result, Err(CoralError::InvalidProtobuf { .. })));
     }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            InputFormat::detect(b"\x0a\x02\x0a\x00").unwrap(),
+            InputFormat::Binary
+        );
+        assert_eq!(
+            InputFormat::detect(b"  {\"file\": []}").unwrap(),
+            InputFormat::Json
+        );
+        assert_eq!(
+            InputFormat::detect(b"file {\n}").unwrap(),
+            InputFormat::Text
+        );
+        assert_eq!(
+            InputFormat::detect(b"# comment\n").unwrap(),
+            InputFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_detect_format_unrecognized() {
+        assert!(matches!(
+            InputFormat::detect(b"<xml/>"),
+            Err(CoralError::UnrecognizedFormat { .. })
+        ));
+        assert!(matches!(
+            InputFormat::detect(b"\x12\x00"),
+            Err(CoralError::UnrecognizedFormat { .. })
+        ));
+        assert!(matches!(
+            InputFormat::detect(b"  \n"),
+            Err(CoralError::EmptyInput)
+        ));
+    }
+
+    #[test]
+    fn test_decode_json() {
+        let json = br#"{
+            "file": [{
+                "name": "user/v1/user.proto",
+                "package": "user.v1",
+                "messageType": [{"name": "User"}],
+                "bufExtension": {
+                    "isImport": true,
+                    "moduleInfo": {
+                        "name": {"remote": "buf.build", "owner": "acme", "repository": "user"}
+                    }
+                }
+            }]
+        }"#;
+        let image = decode_image(json).expect("decode");
+
+        let file = &image.descriptors.file[0];
+        assert_eq!(file.package(), "user.v1");
+        assert_eq!(file.message_type[0].name(), "User");
+
+        let info = &image.files["user/v1/user.proto"];
+        assert!(info.is_import);
+        assert_eq!(info.module.as_deref(), Some("buf.build/acme/user"));
+    }
+
+    #[test]
+    fn test_decode_json_keeps_editions_features() {
+        let json = br#"{
+            "file": [{
+                "name": "e.proto",
+                "package": "e",
+                "syntax": "editions",
+                "edition": "EDITION_2023",
+                "options": {"features": {"fieldPresence": "IMPLICIT"}}
+            }]
+        }"#;
+        let image = decode_image(json).expect("decode");
+
+        let features = &image.features["e.proto"];
+        assert_eq!(features.edition, Some(crate::features::EDITION_2023));
+        assert_eq!(features.sets[""].field_presence, Some(2));
+    }
+
+    #[test]
+    fn test_decode_text_format() {
+        let text = br#"
+            file {
+              name: "user/v1/user.proto"
+              package: "user.v1"
+              message_type { name: "User" }
+            }
+        "#;
+        let fds = decode(text).expect("decode");
+        assert_eq!(fds.file[0].name(), "user/v1/user.proto");
+        assert_eq!(fds.file[0].message_type[0].name(), "User");
+    }
+
+    #[test]
+    fn test_decode_invalid_json_and_text() {
+        assert!(matches!(
+            decode(b"{\"file\": 1}"),
+            Err(CoralError::InvalidJson { .. })
+        ));
+        assert!(matches!(
+            decode(b"not a valid protobuf"),
+            Err(CoralError::InvalidTextFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_gzip() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let bytes = encode_image(&[("user/v1/user.proto", None)]);
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&bytes).expect("compress");
+        let compressed = encoder.finish().expect("compress");
+
+        let fds = decode(&compressed).expect("decode");
+        assert_eq!(fds.file[0].name(), "user/v1/user.proto");
+    }
+
+    #[test]
+    fn test_decode_invalid_gzip() {
+        let result = decode(&[0x1f, 0x8b, 0x00]);
+        assert!(matches!(result, Err(CoralError::InvalidGzip { .. })));
+    }
+
+    #[test]
+    fn test_decode_image_plain_descriptor_set() {
+        let bytes = encode_image(&[("user/v1/user.proto", None)]);
+        let image = decode_image(&bytes).expect("decode");
+
+        assert_eq!(image.descriptors.file.len(), 1);
+        assert!(!image.is_buf_image());
+    }
+
+    #[test]
+    fn test_decode_image_buf_extension() {
+        let bytes = encode_image(&[
+            (
+                "buf/validate/validate.proto",
+                Some(ImageFileExtension {
+                    is_import: Some(true),
+                    module_info: Some(ModuleInfo {
+                        name: Some(ModuleName {
+                            remote: Some("buf.build".to_string()),
+                            owner: Some("bufbuild".to_string()),
+                            repository: Some("protovalidate".to_string()),
+                        }),
+                        commit: Some("abc123".to_string()),
+                    }),
+                }),
+            ),
+            (
+                "user/v1/user.proto",
+                Some(ImageFileExtension {
+                    is_import: Some(false),
+                    module_info: None,
+                }),
+            ),
+        ]);
+        let image = decode_image(&bytes).expect("decode");
+
+        assert!(image.is_buf_image());
+        assert_eq!(image.descriptors.file.len(), 2);
+
+        let validate = &image.files["buf/validate/validate.proto"];
+        assert!(validate.is_import);
+        assert_eq!(
+            validate.module.as_deref(),
+            Some("buf.build/bufbuild/protovalidate")
+        );
+        assert_eq!(validate.commit.as_deref(), Some("abc123"));
+
+        let user = &image.files["user/v1/user.proto"];
+        assert!(!user.is_import);
+        assert_eq!(user.module, None);
+    }
 }
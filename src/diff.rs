@@ -6,7 +6,9 @@ use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
-use crate::domain::node::{EnumValue, FieldInfo, MethodSignature};
+use crate::domain::node::{
+    EnumValue, FieldInfo, FieldPresence, MethodSignature, Reserved, StreamingMode,
+};
 use crate::domain::{GraphModel, Node, NodeDetails, NodeType};
 
 /// Represents changes between two GraphModel snapshots.
@@ -17,12 +19,14 @@ pub struct DiffReport {
     pub modified: Vec<ModifiedItem>,
 }
 
-/// Collection of items by type (services, messages, enums).
+/// Collection of items by type (services, messages, enums, extensions).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DiffItems {
     pub services: Vec<DiffNode>,
     pub messages: Vec<DiffNode>,
     pub enums: Vec<DiffNode>,
+    #[serde(default)]
+    pub extensions: Vec<DiffNode>,
 }
 
 /// Simplified node representation for diff output.
@@ -57,12 +61,164 @@ pub struct ModifiedItem {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Change {
-    FieldAdded { field: FieldInfo },
-    FieldRemoved { field: FieldInfo },
-    MethodAdded { method: MethodSignature },
-    MethodRemoved { method: MethodSignature },
-    EnumValueAdded { value: EnumValue },
-    EnumValueRemoved { value: EnumValue },
+    FieldAdded {
+        field: FieldInfo,
+    },
+    FieldRemoved {
+        field: FieldInfo,
+    },
+    /// Field moved into, out of or between `oneof` groups.
+    FieldOneofChanged {
+        field: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// Presence tracking changed, e.g. proto3 `optional` added or removed.
+    FieldPresenceChanged {
+        field: String,
+        from: FieldPresence,
+        to: FieldPresence,
+    },
+    MethodAdded {
+        method: MethodSignature,
+    },
+    MethodRemoved {
+        method: MethodSignature,
+    },
+    /// Method switched between unary and client/server/bidi streaming.
+    MethodStreamingChanged {
+        method: String,
+        from: StreamingMode,
+        to: StreamingMode,
+    },
+    EnumValueAdded {
+        value: EnumValue,
+    },
+    EnumValueRemoved {
+        value: EnumValue,
+    },
+    /// The item itself (`member` is `None`) or one of its fields, methods
+    /// or values became deprecated.
+    Deprecated {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        member: Option<String>,
+    },
+    /// A `deprecated` marking was removed again.
+    Undeprecated {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        member: Option<String>,
+    },
+    /// The field's proto3 JSON name changed, e.g. via the `json_name` option.
+    JsonNameChanged {
+        field: String,
+        from: String,
+        to: String,
+    },
+    /// A field or enum value takes a number that the base reserved
+    /// (`previous` is `None`) or gave to a member that head no longer has.
+    NumberReused {
+        member: String,
+        number: i32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous: Option<String>,
+    },
+    /// A field or enum value takes a name that the base reserved
+    /// (`previous_number` is `None`) or used with a different number.
+    NameReused {
+        member: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        previous_number: Option<i32>,
+    },
+}
+
+impl Change {
+    /// Whether the change can break existing clients.
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self,
+            Self::FieldRemoved { .. }
+                | Self::FieldOneofChanged { .. }
+                | Self::MethodRemoved { .. }
+                | Self::MethodStreamingChanged { .. }
+                | Self::EnumValueRemoved { .. }
+                | Self::NumberReused { .. }
+                | Self::NameReused { .. }
+        )
+    }
+
+    /// Whether the change can break clients using the proto3 JSON mapping.
+    /// Every wire-breaking change is also JSON-breaking.
+    #[must_use]
+    pub fn is_json_breaking(&self) -> bool {
+        self.is_breaking() || matches!(self, Self::JsonNameChanged { .. })
+    }
+
+    /// One-line human-readable description.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match self {
+            Self::FieldAdded { field } => format!("field `{}` added", field.name),
+            Self::FieldRemoved { field } => format!("field `{}` removed", field.name),
+            Self::FieldOneofChanged { field, from, to } => match (from, to) {
+                (None, Some(to)) => format!("field `{field}` moved into oneof `{to}`"),
+                (Some(from), None) => format!("field `{field}` moved out of oneof `{from}`"),
+                (Some(from), Some(to)) => {
+                    format!("field `{field}` moved from oneof `{from}` to `{to}`")
+                }
+                (None, None) => format!("field `{field}` oneof changed"),
+            },
+            Self::FieldPresenceChanged { field, from, to } => format!(
+                "field `{field}` presence changed from {} to {}",
+                presence_name(*from),
+                presence_name(*to)
+            ),
+            Self::JsonNameChanged { field, from, to } => {
+                format!("field `{field}` JSON name changed from `{from}` to `{to}`")
+            }
+            Self::MethodAdded { method } => format!("method `{}` added", method.name),
+            Self::MethodRemoved { method } => format!("method `{}` removed", method.name),
+            Self::MethodStreamingChanged { method, from, to } => {
+                format!("method `{method}` changed from {from} to {to}")
+            }
+            Self::EnumValueAdded { value } => format!("value `{}` added", value.name),
+            Self::EnumValueRemoved { value } => format!("value `{}` removed", value.name),
+            Self::Deprecated { member: None } => "deprecated".to_string(),
+            Self::Deprecated {
+                member: Some(member),
+            } => format!("`{member}` deprecated"),
+            Self::Undeprecated { member: None } => "no longer deprecated".to_string(),
+            Self::Undeprecated {
+                member: Some(member),
+            } => format!("`{member}` no longer deprecated"),
+            Self::NumberReused {
+                member,
+                number,
+                previous: None,
+            } => format!("`{member}` reuses reserved number {number}"),
+            Self::NumberReused {
+                member,
+                number,
+                previous: Some(previous),
+            } => format!("`{member}` reuses number {number} of removed `{previous}`"),
+            Self::NameReused {
+                member,
+                previous_number: None,
+            } => format!("`{member}` reuses a reserved name"),
+            Self::NameReused {
+                member,
+                previous_number: Some(number),
+            } => format!("`{member}` reuses the name of removed number {number}"),
+        }
+    }
+}
+
+fn presence_name(presence: FieldPresence) -> &'static str {
+    match presence {
+        FieldPresence::Explicit => "explicit",
+        FieldPresence::Implicit => "implicit",
+        FieldPresence::Required => "required",
+    }
 }
 
 impl DiffReport {
@@ -105,6 +261,51 @@ impl DiffReport {
         !self.added.is_empty() || !self.removed.is_empty() || !self.modified.is_empty()
     }
 
+    /// Every breaking change, paired with the item it belongs to.
+    #[must_use]
+    pub fn breaking_changes(&self) -> Vec<(&ModifiedItem, &Change)> {
+        self.modified
+            .iter()
+            .flat_map(|item| {
+                item.changes
+                    .iter()
+                    .filter(|c| c.is_breaking())
+                    .map(move |c| (item, c))
+            })
+            .collect()
+    }
+
+    /// Changes that only break JSON clients, paired with the item they
+    /// belong to; wire-breaking ones are in [`Self::breaking_changes`].
+    #[must_use]
+    pub fn json_breaking_changes(&self) -> Vec<(&ModifiedItem, &Change)> {
+        self.modified
+            .iter()
+            .flat_map(|item| {
+                item.changes
+                    .iter()
+                    .filter(|c| c.is_json_breaking() && !c.is_breaking())
+                    .map(move |c| (item, c))
+            })
+            .collect()
+    }
+
+    /// Every deprecation or un-deprecation, paired with the item it belongs to.
+    #[must_use]
+    pub fn deprecation_changes(&self) -> Vec<(&ModifiedItem, &Change)> {
+        self.modified
+            .iter()
+            .flat_map(|item| {
+                item.changes
+                    .iter()
+                    .filter(|c| {
+                        matches!(c, Change::Deprecated { .. } | Change::Undeprecated { .. })
+                    })
+                    .map(move |c| (item, c))
+            })
+            .collect()
+    }
+
     /// Generate Markdown representation of the diff.
     #[must_use]
     pub fn to_markdown(&self) -> String {
@@ -128,6 +329,12 @@ impl DiffReport {
             for enm in &self.added.enums {
                 output.push_str(&format!("| Enum | {} | {} |\n", enm.label, enm.package));
             }
+            for ext in &self.added.extensions {
+                output.push_str(&format!(
+                    "| Extension | {} | {} |\n",
+                    ext.label, ext.package
+                ));
+            }
             output.push('\n');
         }
 
@@ -141,7 +348,10 @@ impl DiffReport {
                     NodeType::Service => "Service",
                     NodeType::Message => "Message",
                     NodeType::Enum => "Enum",
+                    NodeType::Extension => "Extension",
+                    NodeType::Method => "Method",
                     NodeType::External => "External",
+                    NodeType::File => "File",
                 };
                 let changes_summary = Self::summarize_changes(&item.changes);
                 output.push_str(&format!(
@@ -152,6 +362,36 @@ impl DiffReport {
             output.push('\n');
         }
 
+        let breaking = self.breaking_changes();
+        if !breaking.is_empty() {
+            output.push_str(&format!("#### 🚨 Breaking Changes ({})\n", breaking.len()));
+            for (item, change) in breaking {
+                output.push_str(&format!("- **{}**: {}\n", item.label, change.describe()));
+            }
+            output.push('\n');
+        }
+
+        let json_breaking = self.json_breaking_changes();
+        if !json_breaking.is_empty() {
+            output.push_str(&format!(
+                "#### 🌐 JSON-breaking Changes ({})\n",
+                json_breaking.len()
+            ));
+            for (item, change) in json_breaking {
+                output.push_str(&format!("- **{}**: {}\n", item.label, change.describe()));
+            }
+            output.push('\n');
+        }
+
+        let deprecations = self.deprecation_changes();
+        if !deprecations.is_empty() {
+            output.push_str(&format!("#### 🚧 Deprecations ({})\n", deprecations.len()));
+            for (item, change) in deprecations {
+                output.push_str(&format!("- **{}**: {}\n", item.label, change.describe()));
+            }
+            output.push('\n');
+        }
+
         if !self.removed.is_empty() {
             output.push_str(&format!(
                 "#### ❌ Removed (-{})\n",
@@ -169,6 +409,12 @@ impl DiffReport {
             for enm in &self.removed.enums {
                 output.push_str(&format!("| Enum | {} | {} |\n", enm.label, enm.package));
             }
+            for ext in &self.removed.extensions {
+                output.push_str(&format!(
+                    "| Extension | {} | {} |\n",
+                    ext.label, ext.package
+                ));
+            }
             output.push('\n');
         }
 
@@ -188,7 +434,9 @@ impl DiffReport {
                     NodeType::Service => items.services.push(diff_node),
                     NodeType::Message => items.messages.push(diff_node),
                     NodeType::Enum => items.enums.push(diff_node),
-                    NodeType::External => {}
+                    NodeType::Extension => items.extensions.push(diff_node),
+                    // Method changes are reported on their service
+                    NodeType::Method | NodeType::External | NodeType::File => {}
                 }
             }
         }
@@ -196,12 +444,13 @@ impl DiffReport {
         items.services.sort_by(|a, b| a.id.cmp(&b.id));
         items.messages.sort_by(|a, b| a.id.cmp(&b.id));
         items.enums.sort_by(|a, b| a.id.cmp(&b.id));
+        items.extensions.sort_by(|a, b| a.id.cmp(&b.id));
 
         items
     }
 
     fn compute_node_changes(base: &Node, head: &Node) -> Option<ModifiedItem> {
-        let changes = match (&base.details, &head.details) {
+        let mut changes = match (&base.details, &head.details) {
             (
                 NodeDetails::Service {
                     methods: base_methods,
@@ -216,23 +465,58 @@ impl DiffReport {
             (
                 NodeDetails::Message {
                     fields: base_fields,
+                    reserved,
+                    ..
                 },
                 NodeDetails::Message {
                     fields: head_fields,
+                    ..
                 },
-            ) => Self::compute_field_changes(base_fields, head_fields),
+            ) => {
+                let mut changes = Self::compute_field_changes(base_fields, head_fields);
+                changes.extend(Self::compute_reuse(
+                    &base_fields
+                        .iter()
+                        .map(|f| (f.name.as_str(), f.number))
+                        .collect::<Vec<_>>(),
+                    reserved,
+                    &head_fields
+                        .iter()
+                        .map(|f| (f.name.as_str(), f.number))
+                        .collect::<Vec<_>>(),
+                ));
+                changes
+            }
 
             (
                 NodeDetails::Enum {
                     values: base_values,
+                    reserved,
+                    ..
                 },
                 NodeDetails::Enum {
                     values: head_values,
+                    ..
                 },
-            ) => Self::compute_enum_changes(base_values, head_values),
+            ) => {
+                let mut changes = Self::compute_enum_changes(base_values, head_values);
+                changes.extend(Self::compute_reuse(
+                    &base_values
+                        .iter()
+                        .map(|v| (v.name.as_str(), v.number))
+                        .collect::<Vec<_>>(),
+                    reserved,
+                    &head_values
+                        .iter()
+                        .map(|v| (v.name.as_str(), v.number))
+                        .collect::<Vec<_>>(),
+                ));
+                changes
+            }
 
             _ => vec![],
         };
+        changes.extend(Self::compute_deprecation_changes(base, head));
 
         if changes.is_empty() {
             None
@@ -272,6 +556,18 @@ impl DiffReport {
             }
         }
 
+        for head in head_methods {
+            if let Some(base) = base_methods.iter().find(|m| m.name == head.name)
+                && base.streaming != head.streaming
+            {
+                changes.push(Change::MethodStreamingChanged {
+                    method: head.name.clone(),
+                    from: base.streaming,
+                    to: head.streaming,
+                });
+            }
+        }
+
         changes
     }
 
@@ -297,6 +593,38 @@ impl DiffReport {
             }
         }
 
+        for head in head_fields {
+            let Some(base) = base_fields.iter().find(|f| f.name == head.name) else {
+                continue;
+            };
+            if base.oneof != head.oneof {
+                changes.push(Change::FieldOneofChanged {
+                    field: head.name.clone(),
+                    from: base.oneof.clone(),
+                    to: head.oneof.clone(),
+                });
+            }
+            // Graphs written before presence was modeled have none to compare
+            if let (Some(from), Some(to)) = (base.presence, head.presence)
+                && from != to
+            {
+                changes.push(Change::FieldPresenceChanged {
+                    field: head.name.clone(),
+                    from,
+                    to,
+                });
+            }
+            if let (Some(from), Some(to)) = (&base.json_name, &head.json_name)
+                && from != to
+            {
+                changes.push(Change::JsonNameChanged {
+                    field: head.name.clone(),
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+        }
+
         changes
     }
 
@@ -325,22 +653,124 @@ impl DiffReport {
         changes
     }
 
+    /// Head members (`(name, number)` of fields or enum values) that take a
+    /// number or name the base reserved or gave to a member head dropped.
+    /// Members unchanged in both, and enum aliases of a kept value, pass.
+    fn compute_reuse(
+        base: &[(&str, i32)],
+        reserved: &Reserved,
+        head: &[(&str, i32)],
+    ) -> Vec<Change> {
+        let mut changes = vec![];
+        for &(name, number) in head {
+            if base.contains(&(name, number)) {
+                continue;
+            }
+
+            let dropped = base
+                .iter()
+                .find(|&&(n, k)| k == number && n != name && !head.contains(&(n, k)));
+            if reserved.contains_number(number) || dropped.is_some() {
+                changes.push(Change::NumberReused {
+                    member: name.to_string(),
+                    number,
+                    previous: dropped.map(|(n, _)| n.to_string()),
+                });
+            }
+
+            let renumbered = base.iter().find(|&&(n, _)| n == name);
+            if reserved.contains_name(name) || renumbered.is_some() {
+                changes.push(Change::NameReused {
+                    member: name.to_string(),
+                    previous_number: renumbered.map(|&(_, k)| k),
+                });
+            }
+        }
+        changes
+    }
+
+    /// Deprecation flips of the node itself and of members present in both.
+    fn compute_deprecation_changes(base: &Node, head: &Node) -> Vec<Change> {
+        let change = |member: Option<String>, deprecated: bool| {
+            if deprecated {
+                Change::Deprecated { member }
+            } else {
+                Change::Undeprecated { member }
+            }
+        };
+        let mut changes = vec![];
+
+        // Method nodes repeat their service's methods, which are compared below
+        let own_flag = matches!(
+            head.node_type,
+            NodeType::Service | NodeType::Message | NodeType::Enum | NodeType::Extension
+        );
+        if own_flag && base.deprecated != head.deprecated {
+            changes.push(change(None, head.deprecated));
+        }
+
+        let base_members = Self::member_deprecation(&base.details);
+        for (name, deprecated) in Self::member_deprecation(&head.details) {
+            if base_members
+                .iter()
+                .any(|(n, was)| *n == name && *was != deprecated)
+            {
+                changes.push(change(Some(name.to_string()), deprecated));
+            }
+        }
+
+        changes
+    }
+
+    /// `(name, deprecated)` of every method, field or enum value.
+    fn member_deprecation(details: &NodeDetails) -> Vec<(&str, bool)> {
+        match details {
+            NodeDetails::Service { methods, .. } => methods
+                .iter()
+                .map(|m| (m.name.as_str(), m.deprecated))
+                .collect(),
+            NodeDetails::Message { fields, .. } => fields
+                .iter()
+                .map(|f| (f.name.as_str(), f.deprecated))
+                .collect(),
+            NodeDetails::Enum { values, .. } => values
+                .iter()
+                .map(|v| (v.name.as_str(), v.deprecated))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
     fn summarize_changes(changes: &[Change]) -> String {
         let mut added_fields = 0;
         let mut removed_fields = 0;
+        let mut changed_fields = 0;
         let mut added_methods = 0;
         let mut removed_methods = 0;
+        let mut changed_methods = 0;
         let mut added_values = 0;
         let mut removed_values = 0;
+        let mut deprecated = 0;
+        let mut undeprecated = 0;
+        let mut reused = 0;
 
         for change in changes {
             match change {
                 Change::FieldAdded { .. } => added_fields += 1,
                 Change::FieldRemoved { .. } => removed_fields += 1,
+                Change::FieldOneofChanged { .. }
+                | Change::FieldPresenceChanged { .. }
+                | Change::JsonNameChanged { .. } => {
+                    changed_fields += 1;
+                }
                 Change::MethodAdded { .. } => added_methods += 1,
                 Change::MethodRemoved { .. } => removed_methods += 1,
+                Change::MethodStreamingChanged { .. } => changed_methods += 1,
                 Change::EnumValueAdded { .. } => added_values += 1,
                 Change::EnumValueRemoved { .. } => removed_values += 1,
+                Change::Deprecated { .. } => deprecated += 1,
+                Change::Undeprecated { .. } => undeprecated += 1,
+                Change::NumberReused { .. } | Change::NameReused { .. } => reused += 1,
             }
         }
 
@@ -352,18 +782,33 @@ impl DiffReport {
         if removed_fields > 0 {
             parts.push(format!("-{} field(s)", removed_fields));
         }
+        if changed_fields > 0 {
+            parts.push(format!("~{} field(s)", changed_fields));
+        }
         if added_methods > 0 {
             parts.push(format!("+{} method(s)", added_methods));
         }
         if removed_methods > 0 {
             parts.push(format!("-{} method(s)", removed_methods));
         }
+        if changed_methods > 0 {
+            parts.push(format!("~{} method(s)", changed_methods));
+        }
         if added_values > 0 {
             parts.push(format!("+{} value(s)", added_values));
         }
         if removed_values > 0 {
             parts.push(format!("-{} value(s)", removed_values));
         }
+        if deprecated > 0 {
+            parts.push(format!("{} deprecated", deprecated));
+        }
+        if undeprecated > 0 {
+            parts.push(format!("{} undeprecated", undeprecated));
+        }
+        if reused > 0 {
+            parts.push(format!("{} reused", reused));
+        }
 
         parts.join(", ")
     }
@@ -373,19 +818,23 @@ impl DiffItems {
     /// Check if there are no items.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.services.is_empty() && self.messages.is_empty() && self.enums.is_empty()
+        self.services.is_empty()
+            && self.messages.is_empty()
+            && self.enums.is_empty()
+            && self.extensions.is_empty()
     }
 
     /// Get total count of all items.
     #[must_use]
     pub fn total_count(&self) -> usize {
-        self.services.len() + self.messages.len() + self.enums.len()
+        self.services.len() + self.messages.len() + self.enums.len() + self.extensions.len()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::NumberRange;
 
     fn create_base_model() -> GraphModel {
         GraphModel {
@@ -401,6 +850,7 @@ mod tests {
                             name: "GetUser".to_string(),
                             input_type: "GetUserRequest".to_string(),
                             output_type: "User".to_string(),
+                            ..Default::default()
                         }],
                         messages: vec![],
                     },
@@ -412,11 +862,15 @@ mod tests {
                     "User".to_string(),
                     "user/v1/user.proto".to_string(),
                     NodeDetails::Message {
+                        reserved: Reserved::default(),
+                        extension_ranges: vec![],
+                        oneofs: vec![],
                         fields: vec![FieldInfo {
                             name: "id".to_string(),
                             number: 1,
                             type_name: "string".to_string(),
                             label: "optional".to_string(),
+                            ..Default::default()
                         }],
                     },
                 ),
@@ -426,7 +880,12 @@ mod tests {
                     "user.v1".to_string(),
                     "OldMessage".to_string(),
                     "user/v1/user.proto".to_string(),
-                    NodeDetails::Message { fields: vec![] },
+                    NodeDetails::Message {
+                        fields: vec![],
+                        reserved: Reserved::default(),
+                        extension_ranges: vec![],
+                        oneofs: vec![],
+                    },
                 ),
             ],
             edges: vec![],
@@ -449,11 +908,13 @@ mod tests {
                                 name: "GetUser".to_string(),
                                 input_type: "GetUserRequest".to_string(),
                                 output_type: "User".to_string(),
+                                ..Default::default()
                             },
                             MethodSignature {
                                 name: "CreateUser".to_string(),
                                 input_type: "CreateUserRequest".to_string(),
                                 output_type: "User".to_string(),
+                                ..Default::default()
                             },
                         ],
                         messages: vec![],
@@ -466,18 +927,23 @@ mod tests {
                     "User".to_string(),
                     "user/v1/user.proto".to_string(),
                     NodeDetails::Message {
+                        reserved: Reserved::default(),
+                        extension_ranges: vec![],
+                        oneofs: vec![],
                         fields: vec![
                             FieldInfo {
                                 name: "id".to_string(),
                                 number: 1,
                                 type_name: "string".to_string(),
                                 label: "optional".to_string(),
+                                ..Default::default()
                             },
                             FieldInfo {
                                 name: "email".to_string(),
                                 number: 2,
                                 type_name: "string".to_string(),
                                 label: "optional".to_string(),
+                                ..Default::default()
                             },
                         ],
                     },
@@ -488,7 +954,12 @@ mod tests {
                     "user.v1".to_string(),
                     "NewMessage".to_string(),
                     "user/v1/user.proto".to_string(),
-                    NodeDetails::Message { fields: vec![] },
+                    NodeDetails::Message {
+                        fields: vec![],
+                        reserved: Reserved::default(),
+                        extension_ranges: vec![],
+                        oneofs: vec![],
+                    },
                 ),
             ],
             edges: vec![],
@@ -513,6 +984,38 @@ mod tests {
         assert_eq!(diff.added.messages[0].label, "NewMessage");
     }
 
+    #[test]
+    fn test_extension_added_and_removed() {
+        let extension = |id: &str, label: &str| {
+            Node::new(
+                id.to_string(),
+                NodeType::Extension,
+                "acme.v1".to_string(),
+                label.to_string(),
+                "acme/v1/options.proto".to_string(),
+                NodeDetails::Extension {
+                    extendee: "google.protobuf.FieldOptions".to_string(),
+                    field: FieldInfo::default(),
+                },
+            )
+        };
+        let base = GraphModel {
+            nodes: vec![extension("acme.v1.legacy", "legacy")],
+            ..Default::default()
+        };
+        let head = GraphModel {
+            nodes: vec![extension("acme.v1.sensitive", "sensitive")],
+            ..Default::default()
+        };
+
+        let diff = DiffReport::compute(&base, &head);
+        assert_eq!(diff.added.extensions[0].id, "acme.v1.sensitive");
+        assert_eq!(diff.removed.extensions[0].id, "acme.v1.legacy");
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("| Extension | sensitive | acme.v1 |"));
+        assert!(markdown.contains("| Extension | legacy | acme.v1 |"));
+    }
+
     #[test]
     fn test_removed_detection() {
         let base = create_base_model();
@@ -555,6 +1058,258 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_oneof_and_presence_changes() {
+        let field = |oneof: Option<&str>, presence: FieldPresence| FieldInfo {
+            name: "card".to_string(),
+            number: 1,
+            type_name: "Card".to_string(),
+            label: "optional".to_string(),
+            presence: Some(presence),
+            oneof: oneof.map(str::to_string),
+            ..Default::default()
+        };
+        let model = |fields: Vec<FieldInfo>| GraphModel {
+            nodes: vec![Node::new(
+                "pay.v1.Payment".to_string(),
+                NodeType::Message,
+                "pay.v1".to_string(),
+                "Payment".to_string(),
+                "pay/v1/pay.proto".to_string(),
+                NodeDetails::Message {
+                    fields,
+                    reserved: Reserved::default(),
+                    extension_ranges: vec![],
+                    oneofs: vec![],
+                },
+            )],
+            edges: vec![],
+            packages: vec![],
+        };
+
+        let base = model(vec![field(None, FieldPresence::Implicit)]);
+        let head = model(vec![field(Some("method"), FieldPresence::Explicit)]);
+        let diff = DiffReport::compute(&base, &head);
+
+        let changes = &diff.modified[0].changes;
+        assert!(changes.iter().any(|c| matches!(
+            c,
+            Change::FieldOneofChanged { field, from: None, to: Some(to) }
+                if field == "card" && to == "method"
+        )));
+        assert!(changes.iter().any(|c| matches!(
+            c,
+            Change::FieldPresenceChanged {
+                from: FieldPresence::Implicit,
+                to: FieldPresence::Explicit,
+                ..
+            }
+        )));
+        assert!(diff.to_markdown().contains("~2 field(s)"));
+    }
+
+    #[test]
+    fn test_reused_numbers_and_names() {
+        let field = |name: &str, number: i32| FieldInfo {
+            name: name.to_string(),
+            number,
+            type_name: "string".to_string(),
+            label: "optional".to_string(),
+            ..Default::default()
+        };
+        let model = |fields: Vec<FieldInfo>, reserved: Reserved| GraphModel {
+            nodes: vec![Node::new(
+                "user.v1.User".to_string(),
+                NodeType::Message,
+                "user.v1".to_string(),
+                "User".to_string(),
+                "user/v1/user.proto".to_string(),
+                NodeDetails::Message {
+                    fields,
+                    reserved,
+                    extension_ranges: vec![],
+                    oneofs: vec![],
+                },
+            )],
+            edges: vec![],
+            packages: vec![],
+        };
+        let base = model(
+            vec![field("id", 1), field("name", 2), field("phone", 3)],
+            Reserved {
+                ranges: vec![NumberRange::new(4, 6, false)],
+                names: vec!["email".to_string()],
+            },
+        );
+        let head = model(
+            vec![
+                field("id", 1),
+                field("nickname", 2),
+                field("phone", 7),
+                field("email", 5),
+            ],
+            Reserved::default(),
+        );
+
+        let diff = DiffReport::compute(&base, &head);
+        let reuse: Vec<String> = diff.modified[0]
+            .changes
+            .iter()
+            .filter(|c| matches!(c, Change::NumberReused { .. } | Change::NameReused { .. }))
+            .map(Change::describe)
+            .collect();
+        assert_eq!(
+            reuse,
+            vec![
+                "`nickname` reuses number 2 of removed `name`",
+                "`phone` reuses the name of removed number 3",
+                "`email` reuses reserved number 5",
+                "`email` reuses a reserved name",
+            ]
+        );
+        assert_eq!(diff.breaking_changes().len(), 5); // + `name` removed
+        assert!(diff.to_markdown().contains("4 reused"));
+
+        // Unchanged members and unrelated additions pass
+        let head = model(
+            vec![
+                field("id", 1),
+                field("name", 2),
+                field("phone", 3),
+                field("age", 8),
+            ],
+            Reserved::default(),
+        );
+        assert!(
+            DiffReport::compute(&base, &head)
+                .breaking_changes()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_json_name_change_is_json_breaking() {
+        let model = |json_name: &str| GraphModel {
+            nodes: vec![Node::new(
+                "user.v1.User".to_string(),
+                NodeType::Message,
+                "user.v1".to_string(),
+                "User".to_string(),
+                "user/v1/user.proto".to_string(),
+                NodeDetails::Message {
+                    fields: vec![FieldInfo {
+                        name: "user_id".to_string(),
+                        number: 1,
+                        json_name: Some(json_name.to_string()),
+                        type_name: "string".to_string(),
+                        label: "optional".to_string(),
+                        ..Default::default()
+                    }],
+                    reserved: Reserved::default(),
+                    extension_ranges: vec![],
+                    oneofs: vec![],
+                },
+            )],
+            edges: vec![],
+            packages: vec![],
+        };
+
+        let diff = DiffReport::compute(&model("userId"), &model("id"));
+        assert!(diff.breaking_changes().is_empty());
+        let json_breaking = diff.json_breaking_changes();
+        assert_eq!(json_breaking.len(), 1);
+        assert_eq!(
+            json_breaking[0].1.describe(),
+            "field `user_id` JSON name changed from `userId` to `id`"
+        );
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("#### 🌐 JSON-breaking Changes (1)"));
+        assert!(!markdown.contains("🚨 Breaking Changes"));
+    }
+
+    #[test]
+    fn test_streaming_change_is_breaking() {
+        let model = |streaming: StreamingMode| GraphModel {
+            nodes: vec![Node::new(
+                "chat.v1.ChatService".to_string(),
+                NodeType::Service,
+                "chat.v1".to_string(),
+                "ChatService".to_string(),
+                "chat/v1/chat.proto".to_string(),
+                NodeDetails::Service {
+                    methods: vec![MethodSignature {
+                        name: "Chat".to_string(),
+                        input_type: "ChatRequest".to_string(),
+                        output_type: "ChatResponse".to_string(),
+                        streaming,
+                        ..Default::default()
+                    }],
+                    messages: vec![],
+                },
+            )],
+            edges: vec![],
+            packages: vec![],
+        };
+
+        let diff = DiffReport::compute(
+            &model(StreamingMode::ServerStreaming),
+            &model(StreamingMode::BidiStreaming),
+        );
+        let breaking = diff.breaking_changes();
+        assert_eq!(breaking.len(), 1);
+        assert!(matches!(
+            breaking[0].1,
+            Change::MethodStreamingChanged {
+                from: StreamingMode::ServerStreaming,
+                to: StreamingMode::BidiStreaming,
+                ..
+            }
+        ));
+
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("🚨 Breaking Changes (1)"));
+        assert!(markdown.contains(
+            "- **ChatService**: method `Chat` changed from server streaming to bidi streaming"
+        ));
+    }
+
+    #[test]
+    fn test_deprecation_changes() {
+        let base = create_base_model();
+        let mut head = create_base_model();
+        let user = head
+            .nodes
+            .iter_mut()
+            .find(|n| n.label == "User")
+            .expect("User");
+        user.deprecated = true;
+        if let NodeDetails::Message { fields, .. } = &mut user.details {
+            fields[0].deprecated = true;
+        }
+
+        let diff = DiffReport::compute(&base, &head);
+        let changes: Vec<String> = diff
+            .deprecation_changes()
+            .iter()
+            .map(|(item, change)| format!("{}: {}", item.label, change.describe()))
+            .collect();
+        assert_eq!(changes, vec!["User: deprecated", "User: `id` deprecated"]);
+        assert!(diff.breaking_changes().is_empty());
+
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("| Message | User | 2 deprecated |"));
+        assert!(markdown.contains("🚧 Deprecations (2)"));
+
+        let reverse = DiffReport::compute(&head, &base);
+        assert!(matches!(
+            reverse.modified[0].changes[..],
+            [
+                Change::Undeprecated { member: None },
+                Change::Undeprecated { member: Some(_) }
+            ]
+        ));
+    }
+
     #[test]
     fn test_to_markdown_no_changes() {
         let model = create_base_model();
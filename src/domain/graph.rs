@@ -1,32 +1,258 @@
 //! Graph model types for the proto dependency graph.
 
-use super::node::Node;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::node::{Node, NodeType};
 use serde::{Deserialize, Serialize};
 
+/// What kind of relationship connects two nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EdgeKind {
+    /// Service to one of its RPC method nodes.
+    Method,
+    RpcInput,
+    RpcOutput,
+    Field,
+    MapValue,
+    Extension,
+    /// Parent message to a message/enum declared inside it.
+    Nesting,
+    /// Importing file to imported file.
+    Import,
+    PublicImport,
+    WeakImport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Cardinality {
+    Singular,
+    Repeated,
+    Map,
+}
+
+/// One relationship behind an [`Edge`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeLabel {
+    pub kind: EdgeKind,
+    /// Field or method inducing the relationship; absent for nesting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Set for field, map value and extension relationships.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cardinality: Option<Cardinality>,
+}
+
+impl EdgeLabel {
+    #[must_use]
+    pub fn new(kind: EdgeKind) -> Self {
+        Self {
+            kind,
+            name: None,
+            cardinality: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_cardinality(mut self, cardinality: Cardinality) -> Self {
+        self.cardinality = Some(cardinality);
+        self
+    }
+}
+
+impl fmt::Display for EdgeLabel {
+    /// Short description such as ``field `items` (repeated)`` or `nested type`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            EdgeKind::Method => "rpc",
+            EdgeKind::RpcInput => "rpc input",
+            EdgeKind::RpcOutput => "rpc output",
+            EdgeKind::Field => "field",
+            EdgeKind::MapValue => "map value",
+            EdgeKind::Extension => "extension",
+            EdgeKind::Nesting => "nested type",
+            EdgeKind::Import => "import",
+            EdgeKind::PublicImport => "public import",
+            EdgeKind::WeakImport => "weak import",
+        };
+        f.write_str(kind)?;
+        if let Some(name) = &self.name {
+            write!(f, " `{name}`")?;
+        }
+        match self.cardinality {
+            Some(Cardinality::Repeated) => f.write_str(" (repeated)"),
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Edge {
     pub source: String,
     pub target: String,
+    /// Every relationship between `source` and `target`, in schema order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<EdgeLabel>,
 }
 
 impl Edge {
     #[must_use]
     pub fn new(source: String, target: String) -> Self {
-        Self { source, target }
+        Self {
+            source,
+            target,
+            labels: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_label(mut self, label: EdgeLabel) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Add `other`'s labels to this edge, skipping ones already present.
+    pub fn merge(&mut self, other: Edge) {
+        for label in other.labels {
+            if !self.labels.contains(&label) {
+                self.labels.push(label);
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Definitions in a package and all of its subpackages, by node type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCounts {
+    pub services: usize,
+    pub messages: usize,
+    pub enums: usize,
+    pub extensions: usize,
+    /// Every node, including methods, externals and files.
+    pub nodes: usize,
+}
+
+impl PackageCounts {
+    pub fn add(&mut self, node: &Node) {
+        match node.node_type {
+            NodeType::Service => self.services += 1,
+            NodeType::Message => self.messages += 1,
+            NodeType::Enum => self.enums += 1,
+            NodeType::Extension => self.extensions += 1,
+            NodeType::Method | NodeType::External | NodeType::File => {}
+        }
+        self.nodes += 1;
+    }
+}
+
+/// A node in the package tree. Packages nest by dotted segments
+/// (`order` → `order.v1`); intermediate packages without definitions of
+/// their own are part of the tree too, with empty `node_ids`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Package {
     pub id: String,
+    /// Nodes declared directly in this package.
     pub node_ids: Vec<String>,
+    /// Enclosing package; `None` for top-level packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Direct subpackages, sorted by ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<String>,
+    #[serde(default)]
+    pub counts: PackageCounts,
 }
 
 impl Package {
     #[must_use]
     pub fn new(id: String, node_ids: Vec<String>) -> Self {
-        Self { id, node_ids }
+        Self {
+            id,
+            node_ids,
+            ..Default::default()
+        }
+    }
+
+    /// Last segment of the ID, e.g. `v1` for `order.v1`.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.id.rsplit('.').next().unwrap_or_default()
+    }
+
+    /// Number of ancestors; top-level packages have depth 0.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        if self.id.is_empty() {
+            0
+        } else {
+            self.id.matches('.').count()
+        }
+    }
+
+    /// Build the package tree for `nodes`, in depth-first order with
+    /// siblings sorted by ID.
+    #[must_use]
+    pub fn tree(nodes: &[Node]) -> Vec<Self> {
+        let mut packages: BTreeMap<&str, Self> = BTreeMap::new();
+        for node in nodes {
+            for id in Self::ancestors_and_self(&node.package) {
+                packages
+                    .entry(id)
+                    .or_insert_with(|| Self {
+                        parent: id.rsplit_once('.').map(|(parent, _)| parent.to_string()),
+                        ..Self::new(id.to_string(), Vec::new())
+                    })
+                    .counts
+                    .add(node);
+            }
+            if let Some(package) = packages.get_mut(node.package.as_str()) {
+                package.node_ids.push(node.id.clone());
+            }
+        }
+
+        let links: Vec<(String, String)> = packages
+            .values()
+            .filter_map(|p| Some((p.parent.clone()?, p.id.clone())))
+            .collect();
+        for (parent, child) in links {
+            if let Some(parent) = packages.get_mut(parent.as_str()) {
+                parent.children.push(child);
+            }
+        }
+
+        // `a.b` sorts after `a-b` but must directly follow `a`, so walk the tree.
+        let mut ordered = Vec::with_capacity(packages.len());
+        let mut pending: Vec<String> = packages
+            .values()
+            .filter(|p| p.parent.is_none())
+            .map(|p| p.id.clone())
+            .rev()
+            .collect();
+        while let Some(id) = pending.pop() {
+            if let Some(package) = packages.remove(id.as_str()) {
+                pending.extend(package.children.iter().rev().cloned());
+                ordered.push(package);
+            }
+        }
+        ordered
+    }
+
+    /// `order`, `order.v1` for `order.v1`; just `""` for the empty package.
+    fn ancestors_and_self(id: &str) -> impl Iterator<Item = &str> {
+        id.match_indices('.')
+            .map(move |(i, _)| &id[..i])
+            .chain(std::iter::once(id))
     }
 }
 
@@ -62,6 +288,11 @@ impl GraphModel {
     pub fn find_node(&self, id: &str) -> Option<&Node> {
         self.nodes.iter().find(|n| n.id == id)
     }
+
+    #[must_use]
+    pub fn find_package(&self, id: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| p.id == id)
+    }
 }
 
 impl Default for GraphModel {
@@ -73,25 +304,49 @@ impl Default for GraphModel {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::node::{FieldInfo, MethodSignature, Node, NodeDetails, NodeType};
+    use crate::domain::node::{FieldInfo, MethodSignature, Node, NodeDetails, NodeType, Reserved};
 
     #[test]
     fn test_edge_roundtrip() {
-        let original = Edge {
-            source: "user.v1.UserService".to_string(),
-            target: "user.v1.User".to_string(),
-        };
+        let original = Edge::new(
+            "user.v1.UserService".to_string(),
+            "user.v1.User".to_string(),
+        )
+        .with_label(EdgeLabel::new(EdgeKind::RpcOutput).with_name("GetUser"));
 
         let json = serde_json::to_string(&original).expect("serialize");
         let restored: Edge = serde_json::from_str(&json).expect("deserialize");
         assert_eq!(restored, original);
     }
 
+    #[test]
+    fn test_edge_merge_and_display() {
+        let field = EdgeLabel::new(EdgeKind::Field)
+            .with_name("items")
+            .with_cardinality(Cardinality::Repeated);
+        let mut edge =
+            Edge::new("a.Order".to_string(), "a.Item".to_string()).with_label(field.clone());
+        edge.merge(
+            Edge::new("a.Order".to_string(), "a.Item".to_string())
+                .with_label(field)
+                .with_label(EdgeLabel::new(EdgeKind::Nesting)),
+        );
+
+        let labels: Vec<String> = edge.labels.iter().map(ToString::to_string).collect();
+        assert_eq!(labels, vec!["field `items` (repeated)", "nested type"]);
+
+        let json = serde_json::to_string(&edge).expect("serialize");
+        assert!(json.contains(r#"{"kind":"field","name":"items","cardinality":"repeated"}"#));
+        assert!(json.contains(r#"{"kind":"nesting"}"#));
+    }
+
     #[test]
     fn test_package_roundtrip() {
         let original = Package {
             id: "user.v1".to_string(),
             node_ids: vec!["user.v1.A".to_string(), "user.v1.B".to_string()],
+            parent: Some("user".to_string()),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&original).expect("serialize");
@@ -101,6 +356,63 @@ mod tests {
         assert_eq!(restored, original);
     }
 
+    #[test]
+    fn test_package_tree() {
+        let node = |id: &str, package: &str, node_type: NodeType| {
+            Node::new(
+                id.to_string(),
+                node_type,
+                package.to_string(),
+                id.rsplit('.').next().unwrap_or_default().to_string(),
+                "x.proto".to_string(),
+                NodeDetails::External { well_known: None },
+            )
+        };
+        let nodes = vec![
+            node("order.v2.Order", "order.v2", NodeType::Message),
+            node("order.v1.OrderService", "order.v1", NodeType::Service),
+            node("order.v1.Order", "order.v1", NodeType::Message),
+            node("order-legacy.Order", "order-legacy", NodeType::Message),
+            node("order.Status", "order", NodeType::Enum),
+            node("Root", "", NodeType::Message),
+        ];
+
+        let packages = Package::tree(&nodes);
+        let ids: Vec<&str> = packages.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["", "order", "order.v1", "order.v2", "order-legacy"]
+        );
+
+        let order = &packages[1];
+        assert_eq!(order.parent, None);
+        assert_eq!(order.children, vec!["order.v1", "order.v2"]);
+        assert_eq!(order.node_ids, vec!["order.Status"]);
+        assert_eq!(
+            order.counts,
+            PackageCounts {
+                services: 1,
+                messages: 2,
+                enums: 1,
+                extensions: 0,
+                nodes: 4,
+            }
+        );
+
+        let v1 = &packages[2];
+        assert_eq!(v1.parent.as_deref(), Some("order"));
+        assert_eq!(v1.name(), "v1");
+        assert_eq!(v1.depth(), 1);
+        assert_eq!(v1.node_ids, vec!["order.v1.OrderService", "order.v1.Order"]);
+        assert_eq!(v1.counts.nodes, 2);
+
+        // Intermediate packages are created even without definitions
+        let packages = Package::tree(&nodes[..1]);
+        assert_eq!(packages[0].id, "order");
+        assert!(packages[0].node_ids.is_empty());
+        assert_eq!(packages[0].counts.messages, 1);
+    }
+
     #[test]
     fn test_graph_model_empty_and_default() {
         let from_new = GraphModel::new();
@@ -128,6 +440,7 @@ mod tests {
                             name: "GetUser".to_string(),
                             input_type: "GetUserRequest".to_string(),
                             output_type: "User".to_string(),
+                            ..Default::default()
                         }],
                         messages: vec![],
                     },
@@ -139,26 +452,30 @@ mod tests {
                     "User".to_string(),
                     "user/v1/user.proto".to_string(),
                     NodeDetails::Message {
+                        reserved: Reserved::default(),
+                        extension_ranges: vec![],
+                        oneofs: vec![],
                         fields: vec![FieldInfo {
                             name: "id".to_string(),
                             number: 1,
                             type_name: "string".to_string(),
                             label: "optional".to_string(),
+                            ..Default::default()
                         }],
                     },
                 ),
             ],
-            edges: vec![Edge {
-                source: "user.v1.UserService".to_string(),
-                target: "user.v1.User".to_string(),
-            }],
-            packages: vec![Package {
-                id: "user.v1".to_string(),
-                node_ids: vec![
+            edges: vec![Edge::new(
+                "user.v1.UserService".to_string(),
+                "user.v1.User".to_string(),
+            )],
+            packages: vec![Package::new(
+                "user.v1".to_string(),
+                vec![
                     "user.v1.UserService".to_string(),
                     "user.v1.User".to_string(),
                 ],
-            }],
+            )],
         };
 
         let json = serde_json::to_string(&original).expect("serialize");
@@ -11,7 +11,9 @@
 pub mod graph;
 pub mod node;
 
-pub use graph::{Edge, GraphModel, Package};
+pub use graph::{Cardinality, Edge, EdgeKind, EdgeLabel, GraphModel, Package, PackageCounts};
 pub use node::{
-    EnumInfo, EnumValue, FieldInfo, MessageDef, MethodSignature, Node, NodeDetails, NodeType,
+    Comments, EnumInfo, EnumValue, FieldEncoding, FieldInfo, FieldPresence, MapType, MessageDef,
+    MethodSignature, Node, NodeDetails, NodeType, NumberRange, OneofGroup, Reserved, Span,
+    StreamingMode, WellKnown, WellKnownCategory,
 };
@@ -6,33 +6,335 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "lowercase")]
 pub enum NodeType {
     Service,
+    /// One RPC of a service; only emitted in method-node mode.
+    Method,
     Message,
     Enum,
+    /// A field declared in an `extend` block.
+    Extension,
     External,
+    /// A .proto file; only emitted by the file-level import graph.
+    File,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MethodSignature {
     pub name: String,
     pub input_type: String,
     pub output_type: String,
+    /// Node ID of the input type (its fully-qualified name without the
+    /// leading dot when no node exists for it).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_type_ref: Option<String>,
+    /// Node ID of the output type, like `input_type_ref`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_type_ref: Option<String>,
+    #[serde(default)]
+    pub streaming: StreamingMode,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    pub comments: Comments,
+    /// Marked `deprecated = true` in the schema.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Where the definition is written in its file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Doc comments written next to a definition, from `SourceCodeInfo`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comments {
+    /// Comment block directly above the definition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leading: Option<String>,
+    /// Comment following the definition on the same line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing: Option<String>,
+}
+
+impl Comments {
+    /// Normalize raw `SourceCodeInfo` comment text: the space after `//`
+    /// and surrounding blank lines are dropped.
+    #[must_use]
+    pub fn new(leading: Option<&str>, trailing: Option<&str>) -> Self {
+        let normalize = |text: &str| {
+            let lines: Vec<&str> = text
+                .lines()
+                .map(|l| l.strip_prefix(' ').unwrap_or(l).trim_end())
+                .collect();
+            let text = lines.join("\n").trim_matches('\n').to_string();
+            (!text.is_empty()).then_some(text)
+        };
+        Self {
+            leading: leading.and_then(normalize),
+            trailing: trailing.and_then(normalize),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_none() && self.trailing.is_none()
+    }
+
+    /// The leading comment, or the trailing one when there is none.
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.leading.as_deref().or(self.trailing.as_deref())
+    }
+}
+
+/// Position of a definition in its .proto file. Lines and columns are
+/// 1-based and both ends are inclusive, as editors and code hosts count them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub start_line: u32,
+    pub start_column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Span {
+    /// Convert a `SourceCodeInfo` span: zero-based `[line, column, end_column]`
+    /// or `[line, column, end_line, end_column]` with an exclusive end column.
+    #[must_use]
+    pub fn from_source_code_info(span: &[i32]) -> Option<Self> {
+        let span: Vec<u32> = span
+            .iter()
+            .map(|&n| u32::try_from(n).ok())
+            .collect::<Option<_>>()?;
+        let (line, column, end_line, end_column) = match span[..] {
+            [line, column, end_column] => (line, column, line, end_column),
+            [line, column, end_line, end_column] => (line, column, end_line, end_column),
+            _ => return None,
+        };
+        Some(Self {
+            start_line: line + 1,
+            start_column: column + 1,
+            end_line: end_line + 1,
+            end_column,
+        })
+    }
+}
+
+/// Which sides of an RPC send a stream of messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamingMode {
+    #[default]
+    Unary,
+    ClientStreaming,
+    ServerStreaming,
+    BidiStreaming,
+}
+
+impl StreamingMode {
+    #[must_use]
+    pub fn from_flags(client_streaming: bool, server_streaming: bool) -> Self {
+        match (client_streaming, server_streaming) {
+            (false, false) => Self::Unary,
+            (true, false) => Self::ClientStreaming,
+            (false, true) => Self::ServerStreaming,
+            (true, true) => Self::BidiStreaming,
+        }
+    }
+
+    #[must_use]
+    pub fn client_streams(self) -> bool {
+        matches!(self, Self::ClientStreaming | Self::BidiStreaming)
+    }
+
+    #[must_use]
+    pub fn server_streams(self) -> bool {
+        matches!(self, Self::ServerStreaming | Self::BidiStreaming)
+    }
+}
+
+impl std::fmt::Display for StreamingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Unary => "unary",
+            Self::ClientStreaming => "client streaming",
+            Self::ServerStreaming => "server streaming",
+            Self::BidiStreaming => "bidi streaming",
+        })
+    }
+}
+
+/// Whether a singular field tracks presence, after resolving the file's
+/// syntax or edition features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldPresence {
+    /// `has_` semantics: unset is distinguishable from the default value.
+    Explicit,
+    /// proto3-style: the default value is indistinguishable from unset.
+    Implicit,
+    /// proto2 `required` / `LEGACY_REQUIRED`.
+    Required,
+}
+
+/// Wire encoding of a field, where it varies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FieldEncoding {
+    /// Message field encoded with a length prefix.
+    LengthPrefixed,
+    /// Message field encoded as a group (start/end tags).
+    Delimited,
+    /// Repeated scalar field in a single length-prefixed block.
+    Packed,
+    /// Repeated scalar field with one tag per element.
+    Expanded,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FieldInfo {
     pub name: String,
     pub number: i32,
+    /// Key in the proto3 JSON mapping: the `json_name` option, or the
+    /// lowerCamelCase form of `name` that compilers fill in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_name: Option<String>,
     pub type_name: String,
+    /// Node ID of the referenced message or enum (the map value type for
+    /// map fields); absent for scalars.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_ref: Option<String>,
     pub label: String,
+    /// Effective presence; absent for repeated and map fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence: Option<FieldPresence>,
+    /// Effective encoding; absent for singular scalar fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<FieldEncoding>,
+    /// Key and value types of a map field, whose `type_name` reads
+    /// `map<K, V>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<MapType>,
+    /// Name of the `oneof` group the field belongs to. Synthetic oneofs
+    /// generated for proto3 `optional` fields are not reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oneof: Option<String>,
+    /// Declared with the proto3 `optional` keyword.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub proto3_optional: bool,
+    /// proto2 `[default = ...]`, in descriptor text form (enum defaults
+    /// name the value, bytes are C-escaped).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+    /// A wrapper type shown as the scalar it wraps (`type_name`), which
+    /// may be null; only set when wrappers are inlined.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub nullable: bool,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    pub comments: Comments,
+    /// Marked `deprecated = true` in the schema.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Where the definition is written in its file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct MapType {
+    pub key_type: String,
+    pub value_type: String,
+}
+
+/// A `oneof` declared in a message, with its member fields in declaration
+/// order. Synthetic oneofs of proto3 `optional` fields are not listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OneofGroup {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct EnumValue {
     pub name: String,
     pub number: i32,
+    /// First value declared with the same number, when this one is an
+    /// alias of it (`option allow_alias = true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_of: Option<String>,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    pub comments: Comments,
+    /// Marked `deprecated = true` in the schema.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Where the definition is written in its file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+/// Inclusive range of field or enum value numbers, e.g. `reserved 5 to 9;`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NumberRange {
+    pub start: i32,
+    pub end: i32,
+}
+
+impl NumberRange {
+    /// Largest field number; ranges written `to max` end here (or at
+    /// `i32::MAX` for enums).
+    pub const MAX_FIELD_NUMBER: i32 = 536_870_911;
+
+    /// Convert a descriptor range, whose `end` is exclusive for messages
+    /// and inclusive for enums.
+    #[must_use]
+    pub fn new(start: i32, end: i32, end_exclusive: bool) -> Self {
+        Self {
+            start,
+            end: if end_exclusive { end - 1 } else { end },
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, number: i32) -> bool {
+        (self.start..=self.end).contains(&number)
+    }
+}
+
+impl std::fmt::Display for NumberRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.end {
+            end if end == self.start => write!(f, "{end}"),
+            Self::MAX_FIELD_NUMBER | i32::MAX => write!(f, "{} to max", self.start),
+            end => write!(f, "{} to {end}", self.start),
+        }
+    }
+}
+
+/// Numbers and names that a message's fields or an enum's values must not use.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reserved {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ranges: Vec<NumberRange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+}
+
+impl Reserved {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty() && self.names.is_empty()
+    }
+
+    #[must_use]
+    pub fn contains_number(&self, number: i32) -> bool {
+        self.ranges.iter().any(|r| r.contains(number))
+    }
+
+    #[must_use]
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +342,37 @@ pub struct EnumValue {
 pub struct EnumInfo {
     pub name: String,
     pub values: Vec<EnumValue>,
+    /// Closed enums reject unknown values (proto2 semantics).
+    #[serde(default)]
+    pub closed: bool,
+}
+
+/// Kinds of protobuf well-known types, by what they represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WellKnownCategory {
+    /// `Timestamp` and `Duration`.
+    Time,
+    /// `Any`: a message of any type, named by its type URL.
+    Any,
+    /// `Struct`, `Value`, `ListValue` and `NullValue`: arbitrary JSON.
+    Struct,
+    FieldMask,
+    /// `StringValue`, `Int64Value`, ...: a scalar that can be unset.
+    Wrapper,
+    Empty,
+}
+
+/// Semantics of a protobuf well-known type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WellKnown {
+    pub category: WellKnownCategory,
+    /// How values are written in the proto3 JSON mapping.
+    pub json: String,
+    /// Scalar type boxed by a wrapper, e.g. `string` for `StringValue`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wraps: Option<String>,
 }
 
 /// Message definition with its fields (for Service nodes).
@@ -47,6 +380,9 @@ pub struct EnumInfo {
 #[serde(rename_all = "camelCase")]
 pub struct MessageDef {
     pub name: String,
+    /// Node ID of the message, matching `MethodSignature` type refs.
+    #[serde(default)]
+    pub type_ref: String,
     pub fields: Vec<FieldInfo>,
 }
 
@@ -60,13 +396,57 @@ pub enum NodeDetails {
         methods: Vec<MethodSignature>,
         messages: Vec<MessageDef>,
     },
+    Method {
+        signature: MethodSignature,
+    },
+    #[serde(rename_all = "camelCase")]
     Message {
         fields: Vec<FieldInfo>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        oneofs: Vec<OneofGroup>,
+        #[serde(default, skip_serializing_if = "Reserved::is_empty")]
+        reserved: Reserved,
+        /// Numbers set aside for extensions of this message.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        extension_ranges: Vec<NumberRange>,
     },
+    #[serde(rename_all = "camelCase")]
     Enum {
         values: Vec<EnumValue>,
+        /// Closed enums reject unknown values (proto2 semantics).
+        #[serde(default)]
+        closed: bool,
+        /// Declared with `option allow_alias = true`.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        allow_alias: bool,
+        #[serde(default, skip_serializing_if = "Reserved::is_empty")]
+        reserved: Reserved,
+    },
+    /// `field` is added to the `extendee` message.
+    Extension {
+        /// Node ID of the extended message (e.g. `google.protobuf.FieldOptions`).
+        extendee: String,
+        field: FieldInfo,
+    },
+    /// Defined in a file classified as external.
+    #[serde(rename_all = "camelCase")]
+    External {
+        /// Set for the protobuf well-known types.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        well_known: Option<WellKnown>,
+    },
+    /// Import problems of the file, as reported by
+    /// [`ImportReport`](crate::imports::ImportReport).
+    #[serde(rename_all = "camelCase")]
+    File {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        unused_imports: Vec<String>,
+        /// Files only reachable through another import's `import public`.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        indirect_imports: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        missing_imports: Vec<String>,
     },
-    External,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -78,6 +458,23 @@ pub struct Node {
     pub package: String,
     pub label: String,
     pub file: String,
+    /// buf module the defining file belongs to, when known from a buf image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// ID of the message node this definition is nested in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    pub comments: Comments,
+    /// Marked `deprecated = true` in the schema.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
+    /// Where the definition is written in `file`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    /// In a package configured as foreign: third-party, but shown in full.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub foreign: bool,
     pub details: NodeDetails,
 }
 
@@ -97,15 +494,71 @@ impl Node {
             package,
             label,
             file,
+            module: None,
+            parent: None,
+            comments: Comments::default(),
+            deprecated: false,
+            span: None,
+            foreign: false,
             details,
         }
     }
+
+    #[must_use]
+    pub fn with_module(mut self, module: Option<String>) -> Self {
+        self.module = module;
+        self
+    }
+
+    #[must_use]
+    pub fn with_parent(mut self, parent: Option<String>) -> Self {
+        self.parent = parent;
+        self
+    }
+
+    #[must_use]
+    pub fn with_comments(mut self, comments: Comments) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    #[must_use]
+    pub fn with_deprecated(mut self, deprecated: bool) -> Self {
+        self.deprecated = deprecated;
+        self
+    }
+
+    #[must_use]
+    pub fn with_span(mut self, span: Option<Span>) -> Self {
+        self.span = span;
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_span_from_source_code_info() {
+        let span = |start_line, start_column, end_line, end_column| Span {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        };
+        assert_eq!(
+            Span::from_source_code_info(&[8, 2, 16]),
+            Some(span(9, 3, 9, 16))
+        );
+        assert_eq!(
+            Span::from_source_code_info(&[7, 0, 12, 1]),
+            Some(span(8, 1, 13, 1))
+        );
+        assert_eq!(Span::from_source_code_info(&[1, 2]), None);
+        assert_eq!(Span::from_source_code_info(&[-1, 0, 1]), None);
+    }
+
     #[test]
     fn test_node_type_roundtrip() {
         let cases = [
@@ -130,6 +583,7 @@ mod tests {
             name: "GetUser".to_string(),
             input_type: "GetUserRequest".to_string(),
             output_type: "GetUserResponse".to_string(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&original).expect("serialize");
@@ -148,6 +602,7 @@ mod tests {
             number: 1,
             type_name: "string".to_string(),
             label: "optional".to_string(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&original).expect("serialize");
@@ -169,12 +624,15 @@ mod tests {
                 EnumValue {
                     name: "UNKNOWN".to_string(),
                     number: 0,
+                    ..Default::default()
                 },
                 EnumValue {
                     name: "ACTIVE".to_string(),
                     number: 1,
+                    ..Default::default()
                 },
             ],
+            closed: false,
         };
 
         let json = serde_json::to_string(&original).expect("serialize");
@@ -194,14 +652,17 @@ mod tests {
                 name: "Get".to_string(),
                 input_type: "Req".to_string(),
                 output_type: "Res".to_string(),
+                ..Default::default()
             }],
             messages: vec![MessageDef {
                 name: "Req".to_string(),
+                type_ref: "pkg.Req".to_string(),
                 fields: vec![FieldInfo {
                     name: "id".to_string(),
                     number: 1,
                     type_name: "string".to_string(),
                     label: "optional".to_string(),
+                    ..Default::default()
                 }],
             }],
         };
@@ -211,11 +672,15 @@ mod tests {
         assert!(json.contains("\"messages\":["));
 
         let message = NodeDetails::Message {
+            reserved: Reserved::default(),
+            extension_ranges: vec![],
+            oneofs: vec![],
             fields: vec![FieldInfo {
                 name: "id".to_string(),
                 number: 1,
                 type_name: "string".to_string(),
                 label: "optional".to_string(),
+                ..Default::default()
             }],
         };
         let json = serde_json::to_string(&message).expect("serialize");
@@ -223,24 +688,41 @@ mod tests {
         assert!(json.contains("\"fields\":["));
 
         let enum_details = NodeDetails::Enum {
+            reserved: Reserved::default(),
+            allow_alias: false,
             values: vec![
                 EnumValue {
                     name: "UNKNOWN".to_string(),
                     number: 0,
+                    ..Default::default()
                 },
                 EnumValue {
                     name: "ACTIVE".to_string(),
                     number: 1,
+                    ..Default::default()
                 },
             ],
+            closed: false,
         };
         let json = serde_json::to_string(&enum_details).expect("serialize");
         assert!(json.contains("\"kind\":\"Enum\""));
         assert!(json.contains("\"values\":["));
 
-        let external = NodeDetails::External;
+        let external = NodeDetails::External { well_known: None };
         let json = serde_json::to_string(&external).expect("serialize");
-        assert!(json.contains("\"kind\":\"External\""));
+        assert_eq!(json, "{\"kind\":\"External\"}");
+        let restored: NodeDetails = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(restored, external);
+
+        let timestamp = NodeDetails::External {
+            well_known: Some(WellKnown {
+                category: WellKnownCategory::Time,
+                json: "RFC 3339 string".to_string(),
+                wraps: None,
+            }),
+        };
+        let json = serde_json::to_string(&timestamp).expect("serialize");
+        assert!(json.contains("\"wellKnown\":{\"category\":\"time\""));
     }
 
     #[test]
@@ -257,24 +739,29 @@ mod tests {
                         name: "GetUser".to_string(),
                         input_type: "GetUserRequest".to_string(),
                         output_type: "User".to_string(),
+                        ..Default::default()
                     }],
                     messages: vec![
                         MessageDef {
                             name: "GetUserRequest".to_string(),
+                            type_ref: "user.v1.GetUserRequest".to_string(),
                             fields: vec![FieldInfo {
                                 name: "user_id".to_string(),
                                 number: 1,
                                 type_name: "string".to_string(),
                                 label: "optional".to_string(),
+                                ..Default::default()
                             }],
                         },
                         MessageDef {
                             name: "User".to_string(),
+                            type_ref: "user.v1.User".to_string(),
                             fields: vec![FieldInfo {
                                 name: "id".to_string(),
                                 number: 1,
                                 type_name: "string".to_string(),
                                 label: "optional".to_string(),
+                                ..Default::default()
                             }],
                         },
                     ],
@@ -287,11 +774,15 @@ mod tests {
                 "User".to_string(),
                 "user/v1/user.proto".to_string(),
                 NodeDetails::Message {
+                    reserved: Reserved::default(),
+                    extension_ranges: vec![],
+                    oneofs: vec![],
                     fields: vec![FieldInfo {
                         name: "id".to_string(),
                         number: 1,
                         type_name: "string".to_string(),
                         label: "optional".to_string(),
+                        ..Default::default()
                     }],
                 },
             ),
@@ -302,16 +793,21 @@ mod tests {
                 "Status".to_string(),
                 "user/v1/user.proto".to_string(),
                 NodeDetails::Enum {
+                    reserved: Reserved::default(),
+                    allow_alias: false,
                     values: vec![
                         EnumValue {
                             name: "UNKNOWN".to_string(),
                             number: 0,
+                            ..Default::default()
                         },
                         EnumValue {
                             name: "ACTIVE".to_string(),
                             number: 1,
+                            ..Default::default()
                         },
                     ],
+                    closed: false,
                 },
             ),
             Node::new(
@@ -320,7 +816,7 @@ mod tests {
                 "google.protobuf".to_string(),
                 "Timestamp".to_string(),
                 "google/protobuf/timestamp.proto".to_string(),
-                NodeDetails::External,
+                NodeDetails::External { well_known: None },
             ),
         ];
 
@@ -16,6 +16,67 @@ pub enum CoralError {
     #[error("No proto files found in FileDescriptorSet")]
     NoProtoFiles,
 
+    #[error("Unrecognized input format: {detail}")]
+    UnrecognizedFormat { detail: String },
+
+    #[error("Invalid JSON descriptor set: {message}")]
+    InvalidJson { message: String },
+
+    #[error("Invalid text-format descriptor set: {message}")]
+    InvalidTextFormat { message: String },
+
+    #[error("Invalid gzip data: {source}")]
+    InvalidGzip {
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Conflicting definitions of {item} in {first} and {second}")]
+    MergeConflict {
+        item: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("{file}:{line}:{column}: {message}")]
+    ProtoSyntax {
+        file: String,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("Import \"{import}\" not found (imported by {importer})")]
+    ImportNotFound { import: String, importer: String },
+
+    #[error("Import cycle detected: {cycle}")]
+    ImportCycle { cycle: String },
+
+    #[error("{file}: unresolved type \"{type_name}\"")]
+    UnresolvedType { file: String, type_name: String },
+
+    #[error("{file}: imported file \"{dependency}\" is missing from the descriptor set")]
+    MissingDependency { file: String, dependency: String },
+
+    #[error("{file}: imported file \"{dependency}\" appears after its importer")]
+    DependencyOrder { file: String, dependency: String },
+
+    #[error("{file}: {element} references unknown type \"{type_name}\"")]
+    DanglingTypeReference {
+        file: String,
+        element: String,
+        type_name: String,
+    },
+
+    #[error("{}", format_diagnostics(diagnostics))]
+    Validation { diagnostics: Vec<CoralError> },
+
+    #[error("Invalid config file {path}: {message}")]
+    InvalidConfig { path: String, message: String },
+
+    #[error("gRPC reflection against {endpoint} failed: {message}")]
+    Reflection { endpoint: String, message: String },
+
     #[error("I/O error: {source}")]
     Io {
         #[from]
@@ -25,6 +86,18 @@ pub enum CoralError {
 
 pub type Result<T> = std::result::Result<T, CoralError>;
 
+fn format_diagnostics(diagnostics: &[CoralError]) -> String {
+    let count = match diagnostics.len() {
+        1 => "1 problem".to_string(),
+        n => format!("{n} problems"),
+    };
+    let lines: Vec<String> = diagnostics.iter().map(|d| format!("  - {d}")).collect();
+    format!(
+        "Descriptor set validation found {count}:\n{}",
+        lines.join("\n")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,14 +114,14 @@ mod tests {
 
     #[test]
     fn test_invalid_protobuf_error_message() {
-        let err = CoralError::InvalidProtobuf {
-            source: prost::DecodeError::new("invalid protobuf binary"),
-        };
-        assert!(
-            err.to_string()
-                .starts_with("Invalid protobuf binary format:")
-        );
-        assert!(err.to_string().contains("invalid protobuf binary"));
+        use prost::Message;
+
+        // Field 1, length-delimited, with a truncated length varint
+        let source = prost_types::FileDescriptorSet::decode(&b"\x0a\xff"[..]).unwrap_err();
+        let expected = format!("Invalid protobuf binary format: {source}");
+        let err = CoralError::InvalidProtobuf { source };
+        assert_eq!(err.to_string(), expected);
+        assert!(err.to_string().ends_with("invalid varint"));
     }
 
     #[test]
@@ -57,6 +130,69 @@ mod tests {
         assert_eq!(err.to_string(), "No proto files found in FileDescriptorSet");
     }
 
+    #[test]
+    fn test_unrecognized_format_error_message() {
+        let err = CoralError::UnrecognizedFormat {
+            detail: "unexpected leading byte 0x12".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Unrecognized input format: unexpected leading byte 0x12"
+        );
+    }
+
+    #[test]
+    fn test_proto_syntax_error_message() {
+        let err = CoralError::ProtoSyntax {
+            file: "user/v1/user.proto".to_string(),
+            line: 3,
+            column: 9,
+            message: "expected identifier".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "user/v1/user.proto:3:9: expected identifier"
+        );
+    }
+
+    #[test]
+    fn test_import_not_found_error_message() {
+        let err = CoralError::ImportNotFound {
+            import: "common/v1/money.proto".to_string(),
+            importer: "order/v1/order.proto".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Import \"common/v1/money.proto\" not found (imported by order/v1/order.proto)"
+        );
+    }
+
+    #[test]
+    fn test_validation_error_message() {
+        let err = CoralError::Validation {
+            diagnostics: vec![CoralError::MissingDependency {
+                file: "order/v1/order.proto".to_string(),
+                dependency: "common/v1/money.proto".to_string(),
+            }],
+        };
+        assert_eq!(
+            err.to_string(),
+            "Descriptor set validation found 1 problem:\n  - order/v1/order.proto: imported file \"common/v1/money.proto\" is missing from the descriptor set"
+        );
+    }
+
+    #[test]
+    fn test_reflection_error_message() {
+        let err = CoralError::Reflection {
+            endpoint: "http://localhost:8080".to_string(),
+            message: "connection failed".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "gRPC reflection against http://localhost:8080 failed: connection failed"
+        );
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
@@ -0,0 +1,790 @@
+//! Protobuf Editions: edition numbers and resolved language features.
+//!
+//! `prost-types` predates editions and drops `FileDescriptorProto.edition`
+//! as well as the `features` field of every options message. Like buf's
+//! image extensions, they are decoded here from the raw bytes with minimal
+//! wire-compatible structs and kept next to the descriptors as
+//! [`FileFeatures`].
+//!
+//! Sources parsed by Coral's own `.proto` parser carry features the way
+//! `protoc`'s parser emits them before option interpretation: as
+//! `uninterpreted_option` entries named `features.<name>`.
+//!
+//! Resolution follows the usual precedence: edition defaults, then file,
+//! enclosing messages, oneof and finally the element itself. proto2 and
+//! proto3 files are mapped onto the equivalent edition defaults, with
+//! `required`, groups and `[packed = ...]` taking the place of features.
+
+use std::collections::HashMap;
+
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorProto,
+    UninterpretedOption,
+};
+
+use crate::domain::{FieldEncoding, FieldPresence};
+use crate::error::Result;
+
+/// `google.protobuf.Edition` values.
+pub const EDITION_PROTO2: i32 = 998;
+pub const EDITION_PROTO3: i32 = 999;
+pub const EDITION_2023: i32 = 1000;
+pub const EDITION_2024: i32 = 1001;
+
+/// The subset of `google.protobuf.FeatureSet` that affects the model.
+/// Unset fields inherit from the enclosing scope.
+#[derive(Clone, Copy, PartialEq, Eq, Message)]
+pub struct FeatureSet {
+    /// `EXPLICIT = 1`, `IMPLICIT = 2`, `LEGACY_REQUIRED = 3`
+    #[prost(int32, optional, tag = "1")]
+    pub field_presence: Option<i32>,
+    /// `OPEN = 1`, `CLOSED = 2`
+    #[prost(int32, optional, tag = "2")]
+    pub enum_type: Option<i32>,
+    /// `PACKED = 1`, `EXPANDED = 2`
+    #[prost(int32, optional, tag = "3")]
+    pub repeated_field_encoding: Option<i32>,
+    /// `LENGTH_PREFIXED = 1`, `DELIMITED = 2`
+    #[prost(int32, optional, tag = "5")]
+    pub message_encoding: Option<i32>,
+}
+
+impl FeatureSet {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Set one feature from its text-format name and enum value name.
+    fn set(&mut self, feature: &str, value: &str) {
+        let number = match value {
+            "EXPLICIT" | "OPEN" | "PACKED" | "LENGTH_PREFIXED" => 1,
+            "IMPLICIT" | "CLOSED" | "EXPANDED" | "DELIMITED" => 2,
+            "LEGACY_REQUIRED" => 3,
+            _ => return,
+        };
+        match feature {
+            "field_presence" => self.field_presence = Some(number),
+            "enum_type" => self.enum_type = Some(number),
+            "repeated_field_encoding" => self.repeated_field_encoding = Some(number),
+            "message_encoding" => self.message_encoding = Some(number),
+            _ => {}
+        }
+    }
+}
+
+/// Edition and explicitly set features of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileFeatures {
+    /// `FileDescriptorProto.edition`; `None` for proto2/proto3 files and
+    /// for parsed editions sources, which are treated as edition 2023.
+    pub edition: Option<i32>,
+    /// Feature sets keyed by fully-qualified element name (`.pkg.Msg.field`);
+    /// the file's own features are under `""`.
+    pub sets: HashMap<String, FeatureSet>,
+}
+
+impl FileFeatures {
+    /// Collect `features.*` uninterpreted options, as produced by the native parser.
+    #[must_use]
+    pub fn from_uninterpreted(file: &FileDescriptorProto) -> Self {
+        let mut collector = HashMap::new();
+        let scope = package_scope(file);
+
+        let file_options = file.options.as_ref().map(|o| &o.uninterpreted_option);
+        insert_uninterpreted(&mut collector, String::new(), file_options);
+        for message in &file.message_type {
+            collect_uninterpreted_message(&mut collector, message, &scope);
+        }
+        for enum_type in &file.enum_type {
+            collect_uninterpreted_enum(&mut collector, enum_type, &scope);
+        }
+        for extension in &file.extension {
+            let options = extension.options.as_ref().map(|o| &o.uninterpreted_option);
+            insert_uninterpreted(
+                &mut collector,
+                format!("{scope}.{}", extension.name()),
+                options,
+            );
+        }
+
+        Self {
+            edition: None,
+            sets: collector,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.edition.is_none() && self.sets.is_empty()
+    }
+}
+
+/// Decode the features of every file in an encoded `FileDescriptorSet`
+/// (or buf image), keyed by file name. Files without an edition or any
+/// features are omitted.
+pub fn decode_descriptor_set(bytes: &[u8]) -> Result<HashMap<String, FileFeatures>> {
+    Ok(collect_raw_files(RawFileSet::decode(bytes)?.file))
+}
+
+/// Same as [`decode_descriptor_set`] for `CodeGeneratorRequest.proto_file`.
+pub fn decode_generator_request(bytes: &[u8]) -> Result<HashMap<String, FileFeatures>> {
+    Ok(collect_raw_files(
+        RawGeneratorRequest::decode(bytes)?.proto_file,
+    ))
+}
+
+/// Decode the features of a single encoded `FileDescriptorProto`.
+pub fn decode_file(bytes: &[u8]) -> Result<FileFeatures> {
+    Ok(RawFile::decode(bytes)?.into_file_features())
+}
+
+/// Effective features for one file, ready to answer per-element queries.
+pub struct Resolver<'a> {
+    syntax: Syntax,
+    file: Resolved,
+    sets: Option<&'a HashMap<String, FeatureSet>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Proto2,
+    Proto3,
+    Editions,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Resolved {
+    field_presence: i32,
+    enum_type: i32,
+    repeated_field_encoding: i32,
+    message_encoding: i32,
+}
+
+impl Resolved {
+    fn defaults(syntax: Syntax) -> Self {
+        match syntax {
+            Syntax::Proto2 => Self {
+                field_presence: 1,
+                enum_type: 2,
+                repeated_field_encoding: 2,
+                message_encoding: 1,
+            },
+            Syntax::Proto3 => Self {
+                field_presence: 2,
+                enum_type: 1,
+                repeated_field_encoding: 1,
+                message_encoding: 1,
+            },
+            Syntax::Editions => Self {
+                field_presence: 1,
+                enum_type: 1,
+                repeated_field_encoding: 1,
+                message_encoding: 1,
+            },
+        }
+    }
+
+    fn apply(mut self, set: Option<&FeatureSet>) -> Self {
+        if let Some(set) = set {
+            self.field_presence = set.field_presence.unwrap_or(self.field_presence);
+            self.enum_type = set.enum_type.unwrap_or(self.enum_type);
+            self.repeated_field_encoding = set
+                .repeated_field_encoding
+                .unwrap_or(self.repeated_field_encoding);
+            self.message_encoding = set.message_encoding.unwrap_or(self.message_encoding);
+        }
+        self
+    }
+}
+
+impl<'a> Resolver<'a> {
+    /// `features` is the file's [`FileFeatures`]; without them only the
+    /// file's syntax is taken into account.
+    #[must_use]
+    pub fn new(file: &FileDescriptorProto, features: Option<&'a FileFeatures>) -> Self {
+        let edition = features.and_then(|f| f.edition);
+        let syntax = match (edition, file.syntax()) {
+            (Some(EDITION_PROTO2), _) => Syntax::Proto2,
+            (Some(EDITION_PROTO3), _) => Syntax::Proto3,
+            (Some(_), _) | (None, "editions") => Syntax::Editions,
+            (None, "proto3") => Syntax::Proto3,
+            (None, _) => Syntax::Proto2,
+        };
+        let sets = features.map(|f| &f.sets);
+        Self {
+            syntax,
+            file: Resolved::defaults(syntax).apply(sets.and_then(|s| s.get(""))),
+            sets,
+        }
+    }
+
+    /// Apply the sets of every enclosing scope of `fq_name` (`.pkg.A.B`),
+    /// outermost first, including `fq_name` itself.
+    fn scoped(&self, fq_name: &str) -> Resolved {
+        let Some(sets) = self.sets else {
+            return self.file;
+        };
+        fq_name
+            .match_indices('.')
+            .map(|(index, _)| &fq_name[..index])
+            .chain(std::iter::once(fq_name))
+            .filter(|scope| !scope.is_empty())
+            .fold(self.file, |resolved, scope| resolved.apply(sets.get(scope)))
+    }
+
+    /// Effective presence and encoding of `field` declared in `message`
+    /// whose fully-qualified name is `message_fq`.
+    #[must_use]
+    pub fn field(
+        &self,
+        message_fq: &str,
+        message: &DescriptorProto,
+        field: &FieldDescriptorProto,
+    ) -> (Option<FieldPresence>, Option<FieldEncoding>) {
+        let mut resolved = self.scoped(message_fq);
+        if let Some(oneof) = field
+            .oneof_index
+            .and_then(|i| message.oneof_decl.get(usize::try_from(i).ok()?))
+        {
+            let oneof_fq = format!("{message_fq}.{}", oneof.name());
+            resolved = resolved.apply(self.sets.and_then(|s| s.get(&oneof_fq)));
+        }
+        let field_fq = format!("{message_fq}.{}", field.name());
+        resolved = resolved.apply(self.sets.and_then(|s| s.get(&field_fq)));
+
+        let is_message = matches!(field.r#type(), Type::Message | Type::Group);
+        let presence = match field.label() {
+            Label::Repeated => None,
+            Label::Required => Some(FieldPresence::Required),
+            Label::Optional if resolved.field_presence == 3 => Some(FieldPresence::Required),
+            Label::Optional
+                if is_message || field.oneof_index.is_some() || field.proto3_optional() =>
+            {
+                Some(FieldPresence::Explicit)
+            }
+            Label::Optional if resolved.field_presence == 2 => Some(FieldPresence::Implicit),
+            Label::Optional => Some(FieldPresence::Explicit),
+        };
+
+        let encoding = if field.r#type() == Type::Group
+            || (field.r#type() == Type::Message
+                && self.syntax == Syntax::Editions
+                && resolved.message_encoding == 2)
+        {
+            Some(FieldEncoding::Delimited)
+        } else if is_message {
+            Some(FieldEncoding::LengthPrefixed)
+        } else if field.label() == Label::Repeated && is_packable(field.r#type()) {
+            let packed = match field.options.as_ref().and_then(|o| o.packed) {
+                Some(packed) if self.syntax != Syntax::Editions => packed,
+                _ => resolved.repeated_field_encoding == 1,
+            };
+            Some(if packed {
+                FieldEncoding::Packed
+            } else {
+                FieldEncoding::Expanded
+            })
+        } else {
+            None
+        };
+
+        (presence, encoding)
+    }
+
+    /// Whether the enum `enum_fq` is closed.
+    #[must_use]
+    pub fn enum_closed(&self, enum_fq: &str) -> bool {
+        self.scoped(enum_fq).enum_type == 2
+    }
+}
+
+/// Scalar types that may use packed encoding (everything but strings and bytes).
+fn is_packable(field_type: Type) -> bool {
+    !matches!(
+        field_type,
+        Type::String | Type::Bytes | Type::Message | Type::Group
+    )
+}
+
+fn package_scope(file: &FileDescriptorProto) -> String {
+    match file.package() {
+        "" => String::new(),
+        package => format!(".{package}"),
+    }
+}
+
+fn insert_uninterpreted(
+    sets: &mut HashMap<String, FeatureSet>,
+    key: String,
+    options: Option<&Vec<UninterpretedOption>>,
+) {
+    let mut set = FeatureSet::default();
+    for option in options.into_iter().flatten() {
+        if let [prefix, feature] = option.name.as_slice()
+            && prefix.name_part == "features"
+            && !prefix.is_extension
+            && let Some(value) = &option.identifier_value
+        {
+            set.set(&feature.name_part, value);
+        }
+    }
+    if !set.is_empty() {
+        sets.insert(key, set);
+    }
+}
+
+fn collect_uninterpreted_message(
+    sets: &mut HashMap<String, FeatureSet>,
+    message: &DescriptorProto,
+    scope: &str,
+) {
+    let fq_name = format!("{scope}.{}", message.name());
+    let options = message.options.as_ref().map(|o| &o.uninterpreted_option);
+    insert_uninterpreted(sets, fq_name.clone(), options);
+
+    for field in message.field.iter().chain(&message.extension) {
+        let options = field.options.as_ref().map(|o| &o.uninterpreted_option);
+        insert_uninterpreted(sets, format!("{fq_name}.{}", field.name()), options);
+    }
+    for oneof in &message.oneof_decl {
+        let options = oneof.options.as_ref().map(|o| &o.uninterpreted_option);
+        insert_uninterpreted(sets, format!("{fq_name}.{}", oneof.name()), options);
+    }
+    for nested in &message.nested_type {
+        collect_uninterpreted_message(sets, nested, &fq_name);
+    }
+    for enum_type in &message.enum_type {
+        collect_uninterpreted_enum(sets, enum_type, &fq_name);
+    }
+}
+
+fn collect_uninterpreted_enum(
+    sets: &mut HashMap<String, FeatureSet>,
+    enum_type: &EnumDescriptorProto,
+    scope: &str,
+) {
+    let options = enum_type.options.as_ref().map(|o| &o.uninterpreted_option);
+    insert_uninterpreted(sets, format!("{scope}.{}", enum_type.name()), options);
+}
+
+fn collect_raw_files(files: Vec<RawFile>) -> HashMap<String, FileFeatures> {
+    files
+        .into_iter()
+        .filter_map(|file| {
+            let name = file.name.clone()?;
+            let features = file.into_file_features();
+            (!features.is_empty()).then_some((name, features))
+        })
+        .collect()
+}
+
+// ----- wire structs: only the fields prost-types drops, plus names -----
+
+#[derive(Clone, PartialEq, Message)]
+struct RawFileSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<RawFile>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawGeneratorRequest {
+    #[prost(message, repeated, tag = "15")]
+    proto_file: Vec<RawFile>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawFile {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    message_type: Vec<RawMessage>,
+    #[prost(message, repeated, tag = "5")]
+    enum_type: Vec<RawEnum>,
+    #[prost(message, repeated, tag = "7")]
+    extension: Vec<RawField>,
+    #[prost(message, optional, tag = "8")]
+    options: Option<RawFileOptions>,
+    #[prost(int32, optional, tag = "14")]
+    edition: Option<i32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawMessage {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    field: Vec<RawField>,
+    #[prost(message, repeated, tag = "3")]
+    nested_type: Vec<RawMessage>,
+    #[prost(message, repeated, tag = "4")]
+    enum_type: Vec<RawEnum>,
+    #[prost(message, repeated, tag = "6")]
+    extension: Vec<RawField>,
+    #[prost(message, optional, tag = "7")]
+    options: Option<RawMessageOptions>,
+    #[prost(message, repeated, tag = "8")]
+    oneof_decl: Vec<RawOneof>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawField {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "8")]
+    options: Option<RawFieldOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawOneof {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "2")]
+    options: Option<RawOneofOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawEnum {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, optional, tag = "3")]
+    options: Option<RawEnumOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawFileOptions {
+    #[prost(message, optional, tag = "50")]
+    features: Option<FeatureSet>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawMessageOptions {
+    #[prost(message, optional, tag = "12")]
+    features: Option<FeatureSet>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawFieldOptions {
+    #[prost(message, optional, tag = "21")]
+    features: Option<FeatureSet>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawOneofOptions {
+    #[prost(message, optional, tag = "1")]
+    features: Option<FeatureSet>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawEnumOptions {
+    #[prost(message, optional, tag = "7")]
+    features: Option<FeatureSet>,
+}
+
+impl RawFile {
+    fn into_file_features(self) -> FileFeatures {
+        let mut sets = HashMap::new();
+        let scope = match self.package.as_deref() {
+            None | Some("") => String::new(),
+            Some(package) => format!(".{package}"),
+        };
+
+        insert_set(
+            &mut sets,
+            String::new(),
+            self.options.and_then(|o| o.features),
+        );
+        for message in self.message_type {
+            message.collect(&mut sets, &scope);
+        }
+        for enum_type in self.enum_type {
+            enum_type.collect(&mut sets, &scope);
+        }
+        for extension in self.extension {
+            extension.collect(&mut sets, &scope);
+        }
+
+        FileFeatures {
+            edition: self.edition,
+            sets,
+        }
+    }
+}
+
+impl RawMessage {
+    fn collect(self, sets: &mut HashMap<String, FeatureSet>, scope: &str) {
+        let fq_name = format!("{scope}.{}", self.name.unwrap_or_default());
+        insert_set(sets, fq_name.clone(), self.options.and_then(|o| o.features));
+        for field in self.field.into_iter().chain(self.extension) {
+            field.collect(sets, &fq_name);
+        }
+        for oneof in self.oneof_decl {
+            let key = format!("{fq_name}.{}", oneof.name.unwrap_or_default());
+            insert_set(sets, key, oneof.options.and_then(|o| o.features));
+        }
+        for nested in self.nested_type {
+            nested.collect(sets, &fq_name);
+        }
+        for enum_type in self.enum_type {
+            enum_type.collect(sets, &fq_name);
+        }
+    }
+}
+
+impl RawField {
+    fn collect(self, sets: &mut HashMap<String, FeatureSet>, scope: &str) {
+        let key = format!("{scope}.{}", self.name.unwrap_or_default());
+        insert_set(sets, key, self.options.and_then(|o| o.features));
+    }
+}
+
+impl RawEnum {
+    fn collect(self, sets: &mut HashMap<String, FeatureSet>, scope: &str) {
+        let key = format!("{scope}.{}", self.name.unwrap_or_default());
+        insert_set(sets, key, self.options.and_then(|o| o.features));
+    }
+}
+
+fn insert_set(sets: &mut HashMap<String, FeatureSet>, key: String, set: Option<FeatureSet>) {
+    if let Some(set) = set.filter(|s| !s.is_empty()) {
+        sets.insert(key, set);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::encoding::{WireType, encode_key, encode_varint};
+    use prost_types::{FieldOptions, FileDescriptorSet, OneofDescriptorProto};
+
+    use super::*;
+
+    fn field(name: &str, label: Label, field_type: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(1),
+            label: Some(label as i32),
+            r#type: Some(field_type as i32),
+            ..Default::default()
+        }
+    }
+
+    fn file(syntax: Option<&str>, fields: Vec<FieldDescriptorProto>) -> FileDescriptorProto {
+        FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("test".to_string()),
+            syntax: syntax.map(str::to_string),
+            message_type: vec![DescriptorProto {
+                name: Some("Msg".to_string()),
+                field: fields,
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Kind".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn resolve(
+        file: &FileDescriptorProto,
+        features: Option<&FileFeatures>,
+    ) -> Vec<(Option<FieldPresence>, Option<FieldEncoding>)> {
+        let resolver = Resolver::new(file, features);
+        let message = &file.message_type[0];
+        message
+            .field
+            .iter()
+            .map(|f| resolver.field(".test.Msg", message, f))
+            .collect()
+    }
+
+    #[test]
+    fn test_proto2_and_proto3_semantics() {
+        let fields = vec![
+            field("a", Label::Optional, Type::Int32),
+            field("b", Label::Required, Type::Int32),
+            field("c", Label::Repeated, Type::Int32),
+            field("d", Label::Optional, Type::Group),
+        ];
+
+        let proto2 = file(None, fields.clone());
+        assert_eq!(
+            resolve(&proto2, None),
+            vec![
+                (Some(FieldPresence::Explicit), None),
+                (Some(FieldPresence::Required), None),
+                (None, Some(FieldEncoding::Expanded)),
+                (
+                    Some(FieldPresence::Explicit),
+                    Some(FieldEncoding::Delimited)
+                ),
+            ]
+        );
+        assert!(Resolver::new(&proto2, None).enum_closed(".test.Kind"));
+
+        let mut proto3 = file(Some("proto3"), fields[..1].to_vec());
+        proto3.message_type[0]
+            .field
+            .push(field("c", Label::Repeated, Type::Int32));
+        let mut unpacked = field("e", Label::Repeated, Type::Int32);
+        unpacked.options = Some(FieldOptions {
+            packed: Some(false),
+            ..Default::default()
+        });
+        proto3.message_type[0].field.push(unpacked);
+        assert_eq!(
+            resolve(&proto3, None),
+            vec![
+                (Some(FieldPresence::Implicit), None),
+                (None, Some(FieldEncoding::Packed)),
+                (None, Some(FieldEncoding::Expanded)),
+            ]
+        );
+        assert!(!Resolver::new(&proto3, None).enum_closed(".test.Kind"));
+    }
+
+    #[test]
+    fn test_editions_feature_precedence() {
+        let mut edition = file(
+            Some("editions"),
+            vec![
+                field("a", Label::Optional, Type::Int32),
+                field("b", Label::Optional, Type::Int32),
+                field("c", Label::Optional, Type::Message),
+                field("d", Label::Repeated, Type::Int32),
+                field("e", Label::Optional, Type::Int32),
+            ],
+        );
+        edition.message_type[0]
+            .oneof_decl
+            .push(OneofDescriptorProto {
+                name: Some("choice".to_string()),
+                options: None,
+            });
+        edition.message_type[0].field[4].oneof_index = Some(0);
+
+        let features = FileFeatures {
+            edition: Some(EDITION_2023),
+            sets: HashMap::from([
+                (
+                    String::new(),
+                    FeatureSet {
+                        field_presence: Some(2),
+                        enum_type: Some(2),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    ".test.Msg.b".to_string(),
+                    FeatureSet {
+                        field_presence: Some(3),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    ".test.Msg.c".to_string(),
+                    FeatureSet {
+                        message_encoding: Some(2),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    ".test.Msg".to_string(),
+                    FeatureSet {
+                        repeated_field_encoding: Some(2),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+
+        assert_eq!(
+            resolve(&edition, Some(&features)),
+            vec![
+                (Some(FieldPresence::Implicit), None),
+                (Some(FieldPresence::Required), None),
+                (
+                    Some(FieldPresence::Explicit),
+                    Some(FieldEncoding::Delimited)
+                ),
+                (None, Some(FieldEncoding::Expanded)),
+                (Some(FieldPresence::Explicit), None),
+            ]
+        );
+        assert!(Resolver::new(&edition, Some(&features)).enum_closed(".test.Kind"));
+    }
+
+    #[test]
+    fn test_decode_descriptor_set() {
+        // file { name: "e.proto" package: "e" edition: EDITION_2023
+        //        message_type { name: "M" field { name: "f" options { features { field_presence: IMPLICIT } } } } }
+        let field_features = FeatureSet {
+            field_presence: Some(2),
+            ..Default::default()
+        };
+        let raw = RawFileSet {
+            file: vec![RawFile {
+                name: Some("e.proto".to_string()),
+                package: Some("e".to_string()),
+                message_type: vec![RawMessage {
+                    name: Some("M".to_string()),
+                    field: vec![RawField {
+                        name: Some("f".to_string()),
+                        options: Some(RawFieldOptions {
+                            features: Some(field_features),
+                        }),
+                    }],
+                    ..Default::default()
+                }],
+                edition: Some(EDITION_2023),
+                ..Default::default()
+            }],
+        };
+        let mut bytes = raw.encode_to_vec();
+        // A proto3 file without features is omitted
+        let plain = FileDescriptorProto {
+            name: Some("p.proto".to_string()),
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        encode_key(1, WireType::LengthDelimited, &mut bytes);
+        encode_varint(plain.len() as u64, &mut bytes);
+        bytes.extend(plain);
+
+        // prost-types still decodes the set, dropping what we read here
+        let fds = FileDescriptorSet::decode(bytes.as_slice()).expect("fds");
+        assert_eq!(fds.file.len(), 2);
+
+        let features = decode_descriptor_set(&bytes).expect("features");
+        assert_eq!(features.len(), 1);
+        let file = &features["e.proto"];
+        assert_eq!(file.edition, Some(EDITION_2023));
+        assert_eq!(file.sets[".e.M.f"], field_features);
+    }
+
+    #[test]
+    fn test_from_uninterpreted() {
+        let source = r#"
+            edition = "2023";
+            package e;
+            option features.field_presence = IMPLICIT;
+            message M {
+              int32 a = 1 [features.field_presence = EXPLICIT];
+            }
+            enum E {
+              option features.enum_type = CLOSED;
+              E_UNSPECIFIED = 0;
+            }
+        "#;
+        let file = crate::parser::parse_file("e.proto", source).expect("parse");
+        let features = FileFeatures::from_uninterpreted(&file);
+
+        assert_eq!(features.sets[""].field_presence, Some(2));
+        assert_eq!(features.sets[".e.M.a"].field_presence, Some(1));
+        assert_eq!(features.sets[".e.E"].enum_type, Some(2));
+    }
+}
@@ -0,0 +1,364 @@
+//! File-level import analysis.
+//!
+//! Checks every file's `import` statements against the types it actually
+//! references: imports that provide none of them are unused, types reached
+//! only through another file's `import public` are indirect, and types whose
+//! file is not imported at all are missing.
+
+use std::collections::{HashMap, HashSet};
+
+use prost_types::{FileDescriptorProto, FileDescriptorSet};
+use serde::{Deserialize, Serialize};
+
+use crate::merge::defined_types;
+use crate::validate::type_references;
+
+/// An `import` that no type reference in `file` needs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnusedImport {
+    pub file: String,
+    pub import: String,
+}
+
+/// Types defined in `import` that `file` only sees because its direct
+/// import `via` re-exports them with `import public`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndirectImport {
+    pub file: String,
+    pub import: String,
+    pub via: String,
+    pub types: Vec<String>,
+}
+
+/// Types defined in `import` that `file` uses without importing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingImport {
+    pub file: String,
+    pub import: String,
+    pub types: Vec<String>,
+}
+
+/// Import problems across a descriptor set, in file order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub unused: Vec<UnusedImport>,
+    pub indirect: Vec<IndirectImport>,
+    pub missing: Vec<MissingImport>,
+}
+
+impl ImportReport {
+    /// Check the files for which `checked` returns true; the rest of `fds`
+    /// is only used to resolve types and public imports.
+    ///
+    /// Custom options are not part of decoded descriptors, so imports whose
+    /// only content is option extensions (e.g. `google/api/annotations.proto`)
+    /// are assumed to be used.
+    #[must_use]
+    pub fn compute(
+        fds: &FileDescriptorSet,
+        checked: impl Fn(&FileDescriptorProto) -> bool,
+    ) -> Self {
+        let files: HashMap<&str, &FileDescriptorProto> =
+            fds.file.iter().map(|f| (f.name(), f)).collect();
+        let type_files: HashMap<String, &str> = fds
+            .file
+            .iter()
+            .flat_map(|f| defined_types(f).into_iter().map(move |t| (t, f.name())))
+            .collect();
+
+        let mut report = Self::default();
+        for file in fds.file.iter().filter(|f| checked(f)) {
+            let exports: Vec<(&str, HashSet<&str>)> = file
+                .dependency
+                .iter()
+                .map(|d| (d.as_str(), public_closure(d, &files)))
+                .collect();
+
+            let mut used: HashSet<&str> = HashSet::new();
+            let mut indirect: Vec<IndirectImport> = Vec::new();
+            let mut missing: Vec<MissingImport> = Vec::new();
+            for (_, type_name) in type_references(file) {
+                let Some(&defined_in) = type_files.get(&type_name) else {
+                    continue; // dangling; reported by validation
+                };
+                if defined_in == file.name() {
+                    continue;
+                }
+                let type_name = type_name.trim_start_matches('.').to_string();
+                if file.dependency.iter().any(|d| d == defined_in) {
+                    used.insert(defined_in);
+                } else if let Some((via, _)) = exports.iter().find(|(_, e)| e.contains(defined_in))
+                {
+                    used.insert(via);
+                    match indirect
+                        .iter_mut()
+                        .find(|i| i.import == defined_in && i.via == *via)
+                    {
+                        Some(entry) => push_unique(&mut entry.types, type_name),
+                        None => indirect.push(IndirectImport {
+                            file: file.name().to_string(),
+                            import: defined_in.to_string(),
+                            via: via.to_string(),
+                            types: vec![type_name],
+                        }),
+                    }
+                } else {
+                    match missing.iter_mut().find(|m| m.import == defined_in) {
+                        Some(entry) => push_unique(&mut entry.types, type_name),
+                        None => missing.push(MissingImport {
+                            file: file.name().to_string(),
+                            import: defined_in.to_string(),
+                            types: vec![type_name],
+                        }),
+                    }
+                }
+            }
+
+            let public: HashSet<&str> = indices(&file.public_dependency, &file.dependency);
+            for dependency in &file.dependency {
+                let dependency = dependency.as_str();
+                // Public imports are re-exports for dependents, not for this file
+                if used.contains(dependency) || public.contains(dependency) {
+                    continue;
+                }
+                if files
+                    .get(dependency)
+                    .is_some_and(|d| only_option_extensions(d))
+                {
+                    continue;
+                }
+                report.unused.push(UnusedImport {
+                    file: file.name().to_string(),
+                    import: dependency.to_string(),
+                });
+            }
+            report.indirect.extend(indirect);
+            report.missing.extend(missing);
+        }
+        report
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.unused.is_empty() && self.indirect.is_empty() && self.missing.is_empty()
+    }
+
+    /// Generate Markdown representation of the report.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::from("### Import Report\n\n");
+        if self.is_empty() {
+            output.push_str("No import problems found.\n");
+            return output;
+        }
+
+        if !self.unused.is_empty() {
+            output.push_str(&format!("#### 🗑️ Unused imports ({})\n", self.unused.len()));
+            output.push_str("| File | Import |\n");
+            output.push_str("|------|--------|\n");
+            for unused in &self.unused {
+                output.push_str(&format!("| {} | {} |\n", unused.file, unused.import));
+            }
+            output.push('\n');
+        }
+
+        if !self.indirect.is_empty() {
+            output.push_str(&format!(
+                "#### 🔀 Provided by public imports ({})\n",
+                self.indirect.len()
+            ));
+            output.push_str("| File | Needs | Via | Types |\n");
+            output.push_str("|------|-------|-----|-------|\n");
+            for indirect in &self.indirect {
+                output.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    indirect.file,
+                    indirect.import,
+                    indirect.via,
+                    indirect.types.join(", ")
+                ));
+            }
+            output.push('\n');
+        }
+
+        if !self.missing.is_empty() {
+            output.push_str(&format!(
+                "#### ❌ Missing imports ({})\n",
+                self.missing.len()
+            ));
+            output.push_str("| File | Needs | Types |\n");
+            output.push_str("|------|-------|-------|\n");
+            for missing in &self.missing {
+                output.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    missing.file,
+                    missing.import,
+                    missing.types.join(", ")
+                ));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+fn push_unique(types: &mut Vec<String>, type_name: String) {
+    if !types.contains(&type_name) {
+        types.push(type_name);
+    }
+}
+
+/// `file` plus everything it re-exports through chains of `import public`.
+fn public_closure<'a>(
+    file: &'a str,
+    files: &HashMap<&str, &'a FileDescriptorProto>,
+) -> HashSet<&'a str> {
+    let mut closure = HashSet::from([file]);
+    let mut pending = vec![file];
+    while let Some(name) = pending.pop() {
+        let Some(descriptor) = files.get(name) else {
+            continue;
+        };
+        for public in indices(&descriptor.public_dependency, &descriptor.dependency) {
+            if closure.insert(public) {
+                pending.push(public);
+            }
+        }
+    }
+    closure
+}
+
+/// Dependency names selected by `public_dependency` / `weak_dependency` indices.
+pub(crate) fn indices<'a>(selected: &[i32], dependencies: &'a [String]) -> HashSet<&'a str> {
+    selected
+        .iter()
+        .filter_map(|&i| usize::try_from(i).ok())
+        .filter_map(|i| dependencies.get(i))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Files that only declare extensions of descriptor options.
+fn only_option_extensions(file: &FileDescriptorProto) -> bool {
+    let is_option =
+        |extendee: &str| extendee.starts_with(".google.protobuf.") && extendee.ends_with("Options");
+    !file.extension.is_empty()
+        && file.extension.iter().all(|e| is_option(e.extendee()))
+        && file.message_type.is_empty()
+        && file.enum_type.is_empty()
+        && file.service.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn compile(sources: &[(&str, &str)]) -> FileDescriptorSet {
+        let sources: HashMap<&str, &str> = sources.iter().copied().collect();
+        crate::parser::compile_with(&["app.proto".to_string()], |name| {
+            Ok(sources.get(name).map(|s| s.to_string()))
+        })
+        .expect("compile")
+    }
+
+    #[test]
+    fn test_unused_and_indirect_imports() {
+        let fds = compile(&[
+            (
+                "app.proto",
+                r#"
+                syntax = "proto3";
+                package app;
+                import "facade.proto";
+                import "unused.proto";
+                message Order { money.Money total = 1; }
+                "#,
+            ),
+            (
+                "facade.proto",
+                r#"
+                syntax = "proto3";
+                import public "money.proto";
+                "#,
+            ),
+            (
+                "money.proto",
+                r#"
+                syntax = "proto3";
+                package money;
+                message Money { string currency = 1; }
+                "#,
+            ),
+            (
+                "unused.proto",
+                r#"
+                syntax = "proto3";
+                package unused;
+                message Nothing {}
+                "#,
+            ),
+        ]);
+
+        let report = ImportReport::compute(&fds, |f| f.name() == "app.proto");
+        assert_eq!(
+            report.unused,
+            vec![UnusedImport {
+                file: "app.proto".to_string(),
+                import: "unused.proto".to_string(),
+            }]
+        );
+        assert_eq!(
+            report.indirect,
+            vec![IndirectImport {
+                file: "app.proto".to_string(),
+                import: "money.proto".to_string(),
+                via: "facade.proto".to_string(),
+                types: vec!["money.Money".to_string()],
+            }]
+        );
+        assert!(report.missing.is_empty());
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| app.proto | unused.proto |"));
+        assert!(markdown.contains("| app.proto | money.proto | facade.proto | money.Money |"));
+    }
+
+    #[test]
+    fn test_missing_import() {
+        let mut fds = compile(&[
+            (
+                "app.proto",
+                r#"
+                syntax = "proto3";
+                package app;
+                import "money.proto";
+                message Order { money.Money total = 1; }
+                "#,
+            ),
+            (
+                "money.proto",
+                r#"
+                syntax = "proto3";
+                package money;
+                message Money { string currency = 1; }
+                "#,
+            ),
+        ]);
+        // e.g. a merged set where the import was dropped from the importer
+        fds.file[1].dependency.clear();
+
+        let report = ImportReport::compute(&fds, |_| true);
+        assert_eq!(
+            report.missing,
+            vec![MissingImport {
+                file: "app.proto".to_string(),
+                import: "money.proto".to_string(),
+                types: vec!["money.Money".to_string()],
+            }]
+        );
+        assert!(report.unused.is_empty());
+    }
+}
@@ -1,12 +1,22 @@
 //! Coral - Proto dependency visualizer for gRPC/Connect projects.
 
 pub mod analyzer;
+pub mod classify;
 pub mod decoder;
 pub mod diff;
 pub mod domain;
 pub mod error;
+pub mod features;
+pub mod imports;
+pub mod merge;
+pub mod parser;
+pub mod plugin;
+pub mod reflection;
 pub mod reporter;
 pub mod server;
+pub mod source_info;
+pub mod validate;
+pub mod well_known;
 
 pub use analyzer::Analyzer;
 pub use diff::DiffReport;
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
@@ -15,6 +15,74 @@ struct Cli {
 
     #[arg(long, short, value_enum, default_value_t = OutputMode::Json, global = true)]
     output: OutputMode,
+
+    /// Descriptor set or buf image file to read instead of stdin (repeatable; inputs are merged)
+    #[arg(long = "input", short = 'i', global = true)]
+    inputs: Vec<PathBuf>,
+
+    /// Parse .proto files under this directory instead of reading stdin
+    #[arg(long, global = true)]
+    proto_path: Option<PathBuf>,
+
+    /// Additional import roots for --proto-path
+    #[arg(long = "include", short = 'I', global = true)]
+    includes: Vec<PathBuf>,
+
+    /// Fetch descriptors from a running server via gRPC reflection (e.g. http://localhost:8080)
+    #[arg(long = "reflect", value_name = "URL", global = true)]
+    reflect: Vec<String>,
+
+    /// Fail instead of warning when the descriptor set has missing imports,
+    /// unresolved type references or files out of dependency order
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Fold nested messages and enums into their top-level message
+    #[arg(long, global = true)]
+    collapse_nested: bool,
+
+    /// Emit a node per RPC method, linked to its service and its messages
+    #[arg(long, global = true)]
+    method_nodes: bool,
+
+    /// One node per definition, or one per .proto file with import edges
+    /// (Markdown output then reports unused and missing imports)
+    #[arg(long, value_enum, default_value_t = Granularity::Definition, global = true)]
+    granularity: Granularity,
+
+    /// Link definition names in Markdown output to their source, e.g.
+    /// "https://github.com/acme/api/blob/{sha}/proto/{path}#L{line}-L{end_line}"
+    #[arg(long, value_name = "TEMPLATE", requires = "commit_sha", global = true)]
+    permalink_template: Option<String>,
+
+    /// Commit substituted for {sha} in --permalink-template
+    #[arg(long, value_name = "SHA", global = true)]
+    commit_sha: Option<String>,
+
+    /// Name fields in Markdown output by their proto3 JSON name (json_name)
+    #[arg(long, global = true)]
+    json_names: bool,
+
+    /// Show singular wrapper-typed fields (google.protobuf.StringValue, ...)
+    /// as nullable scalars instead of linking them to the wrapper type
+    #[arg(long, global = true)]
+    inline_wrappers: bool,
+
+    /// JSON file with externalPaths, externalPackages and foreignPackages
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Treat files matching this path glob as external (e.g. "third_party/**")
+    #[arg(long = "external-path", value_name = "GLOB", global = true)]
+    external_paths: Vec<String>,
+
+    /// Treat this package and its subpackages as external
+    #[arg(long = "external-package", value_name = "PACKAGE", global = true)]
+    external_packages: Vec<String>,
+
+    /// Show this package and its subpackages in full, but marked as foreign
+    #[arg(long = "foreign-package", value_name = "PACKAGE", global = true)]
+    foreign_packages: Vec<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -34,6 +102,12 @@ enum Command {
     },
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Granularity {
+    Definition,
+    File,
+}
+
 #[derive(Clone, Debug, ValueEnum)]
 enum OutputMode {
     Json,
@@ -42,22 +116,87 @@ enum OutputMode {
     Markdown,
 }
 
+/// Load descriptors from `--input` files, `--proto-path` sources and
+/// `--reflect` servers, merging them when there is more than one; falls
+/// back to stdin. The result is validated according to `--strict`.
+async fn load_image(cli: &Cli) -> Result<coral::decoder::Image> {
+    let mut sources = Vec::new();
+    for path in &cli.inputs {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("failed to read input {}", path.display()))?;
+        let image = coral::decoder::decode_image(&bytes)
+            .with_context(|| format!("failed to decode input {}", path.display()))?;
+        sources.push(coral::merge::Source::new(path.display().to_string(), image));
+    }
+    if let Some(dir) = &cli.proto_path {
+        let fds = coral::parser::compile(dir, &cli.includes)?;
+        sources.push(coral::merge::Source::new(
+            dir.display().to_string(),
+            fds.into(),
+        ));
+    }
+    for endpoint in &cli.reflect {
+        let image = coral::reflection::fetch(endpoint).await?;
+        sources.push(coral::merge::Source::new(endpoint.clone(), image));
+    }
+
+    let image = match sources.len() {
+        0 => coral::decoder::decode_image(&coral::read_stdin()?)?,
+        1 => sources.remove(0).image,
+        _ => coral::merge::merge(sources)?,
+    };
+
+    if cli.strict {
+        coral::validate::check(&image.descriptors)?;
+    } else {
+        for diagnostic in coral::validate::validate(&image.descriptors) {
+            eprintln!("warning: {diagnostic}");
+        }
+    }
+    Ok(image)
+}
+
+/// Analyzer configured from the command line and `--config`.
+fn analyzer(cli: &Cli) -> Result<coral::Analyzer> {
+    let mut classification = match &cli.config {
+        Some(path) => coral::classify::Classification::load(path)?,
+        None => coral::classify::Classification::default(),
+    };
+    classification.extend(coral::classify::Classification {
+        external_paths: cli.external_paths.clone(),
+        external_packages: cli.external_packages.clone(),
+        foreign_packages: cli.foreign_packages.clone(),
+    });
+    Ok(coral::Analyzer::default()
+        .with_nested_collapsed(cli.collapse_nested)
+        .with_method_nodes(cli.method_nodes)
+        .with_wrappers_inlined(cli.inline_wrappers)
+        .with_classification(classification))
+}
+
+/// Graph at the granularity selected on the command line.
+fn analyze(cli: &Cli, image: &coral::decoder::Image) -> Result<coral::GraphModel> {
+    let mut analyzer = analyzer(cli)?;
+    Ok(match cli.granularity {
+        Granularity::Definition => analyzer.analyze_image(image),
+        Granularity::File => analyzer.analyze_files(image),
+    })
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
     let cli = Cli::parse();
 
-    match cli.command {
+    match &cli.command {
         Some(Command::Serve { port, static_dir }) => {
-            let bytes = coral::read_stdin()?;
-            let fds = coral::decoder::decode(&bytes)?;
-            let mut analyzer = coral::Analyzer::default();
-            let model = analyzer.analyze(&fds);
-            coral::server::serve_with_static(model, port, static_dir).await?;
+            let image = load_image(&cli).await?;
+            let model = analyze(&cli, &image)?;
+            coral::server::serve_with_static(model, *port, static_dir.clone()).await?;
         }
         Some(Command::Diff { base, head }) => {
-            let base_json = std::fs::read_to_string(&base)?;
-            let head_json = std::fs::read_to_string(&head)?;
+            let base_json = std::fs::read_to_string(base)?;
+            let head_json = std::fs::read_to_string(head)?;
 
             let base_model: coral::GraphModel = serde_json::from_str(&base_json)?;
             let head_model: coral::GraphModel = serde_json::from_str(&head_json)?;
@@ -66,17 +205,16 @@ async fn main() -> Result<()> {
             println!("{}", diff.to_markdown());
         }
         None => {
-            let bytes = coral::read_stdin()?;
-            let fds = coral::decoder::decode(&bytes)?;
+            let image = load_image(&cli).await?;
+            let fds = &image.descriptors;
 
             match cli.output {
                 OutputMode::Json => {
-                    let mut analyzer = coral::Analyzer::default();
-                    let model = analyzer.analyze(&fds);
+                    let model = analyze(&cli, &image)?;
                     println!("{}", serde_json::to_string_pretty(&model)?);
                 }
                 OutputMode::Debug => {
-                    coral::debug_output(&fds);
+                    coral::debug_output(fds);
                 }
                 OutputMode::Summary => {
                     println!("Files: {}", fds.file.len());
@@ -87,11 +225,24 @@ async fn main() -> Result<()> {
                     println!("Messages: {messages}");
                     println!("Enums: {enums}");
                 }
-                OutputMode::Markdown => {
-                    let mut analyzer = coral::Analyzer::default();
-                    let model = analyzer.analyze(&fds);
-                    println!("{}", coral::MarkdownReporter::generate(&model));
-                }
+                OutputMode::Markdown => match cli.granularity {
+                    Granularity::Definition => {
+                        let model = analyze(&cli, &image)?;
+                        let permalinks = cli
+                            .permalink_template
+                            .as_ref()
+                            .zip(cli.commit_sha.as_ref())
+                            .map(|(template, sha)| coral::reporter::Permalinks::new(template, sha));
+                        let reporter = coral::MarkdownReporter::default()
+                            .with_permalinks(permalinks)
+                            .with_json_names(cli.json_names);
+                        println!("{}", reporter.render(&model));
+                    }
+                    Granularity::File => {
+                        let report = analyzer(&cli)?.import_report(&image);
+                        println!("{}", report.to_markdown());
+                    }
+                },
             }
         }
     }
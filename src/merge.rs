@@ -0,0 +1,333 @@
+//! Merging descriptor inputs from several sources into one image.
+//!
+//! Files that appear in more than one input with identical contents are
+//! kept once. A file name or fully-qualified type that is defined
+//! differently by two inputs is reported as a [`CoralError::MergeConflict`].
+//! A file counts as first-party if any buf image owns it, i.e. does not mark
+//! it as an import; plain descriptor sets carry no such metadata and leave
+//! the decision to the other inputs.
+
+use std::collections::HashMap;
+
+use prost_types::{DescriptorProto, FileDescriptorProto};
+
+use crate::decoder::{Image, ImageFileInfo};
+use crate::error::{CoralError, Result};
+
+/// A decoded input together with a human-readable name for error messages
+/// (usually the path it was read from).
+#[derive(Debug, Clone)]
+pub struct Source {
+    pub name: String,
+    pub image: Image,
+}
+
+impl Source {
+    #[must_use]
+    pub fn new(name: impl Into<String>, image: Image) -> Self {
+        Self {
+            name: name.into(),
+            image,
+        }
+    }
+}
+
+/// Merge sources in order. Image metadata of a file found in several
+/// sources is combined: it is an import only if every image that has
+/// metadata for it says so, and its module comes from the first source that
+/// names one.
+pub fn merge(sources: Vec<Source>) -> Result<Image> {
+    let mut merged = Image::default();
+    let mut file_owner: HashMap<String, (String, usize)> = HashMap::new();
+    let mut type_owner: HashMap<String, (String, String)> = HashMap::new();
+
+    for source in sources {
+        let Image {
+            descriptors,
+            files,
+            mut features,
+        } = source.image;
+
+        for file in descriptors.file {
+            let file_name = file.name().to_string();
+
+            if let Some((first_source, index)) = file_owner.get(&file_name) {
+                if !same_definition(&merged.descriptors.file[*index], &file) {
+                    return Err(CoralError::MergeConflict {
+                        item: format!("file \"{file_name}\""),
+                        first: first_source.clone(),
+                        second: source.name.clone(),
+                    });
+                }
+                merge_file_info(&mut merged, &file_name, files.get(&file_name));
+                continue;
+            }
+
+            for fq_type in defined_types(&file) {
+                if let Some((first_source, first_file)) = type_owner.get(&fq_type) {
+                    return Err(CoralError::MergeConflict {
+                        item: format!("type \"{fq_type}\" (in {first_file} and {file_name})"),
+                        first: first_source.clone(),
+                        second: source.name.clone(),
+                    });
+                }
+                type_owner.insert(fq_type, (source.name.clone(), file_name.clone()));
+            }
+
+            file_owner.insert(
+                file_name.clone(),
+                (source.name.clone(), merged.descriptors.file.len()),
+            );
+            if let Some(info) = files.get(&file_name) {
+                merged.files.insert(file_name.clone(), info.clone());
+            }
+            if let Some(file_features) = features.remove(&file_name) {
+                merged.features.insert(file_name, file_features);
+            }
+            merged.descriptors.file.push(file);
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Combine a repeated file's `info` into what earlier sources recorded.
+/// Sources without metadata (plain descriptor sets, `--proto-path`
+/// compiles) leave the entry untouched, so bundled well-known types keep
+/// falling back to path-based classification.
+fn merge_file_info(merged: &mut Image, file_name: &str, info: Option<&ImageFileInfo>) {
+    let Some(info) = info else {
+        return;
+    };
+    let Some(existing) = merged.files.get_mut(file_name) else {
+        merged.files.insert(file_name.to_string(), info.clone());
+        return;
+    };
+    existing.is_import &= info.is_import;
+    if existing.module.is_none() {
+        existing.module.clone_from(&info.module);
+        existing.commit.clone_from(&info.commit);
+    }
+}
+
+/// Compare two files ignoring `source_code_info`, which depends on how the
+/// input was built rather than on the schema itself.
+fn same_definition(a: &FileDescriptorProto, b: &FileDescriptorProto) -> bool {
+    let strip = |file: &FileDescriptorProto| FileDescriptorProto {
+        source_code_info: None,
+        ..file.clone()
+    };
+    strip(a) == strip(b)
+}
+
+/// Fully-qualified names (`.pkg.Type`) of every message, enum and service in `file`.
+pub(crate) fn defined_types(file: &FileDescriptorProto) -> Vec<String> {
+    let scope = match file.package() {
+        "" => String::new(),
+        package => format!(".{package}"),
+    };
+
+    let mut types = Vec::new();
+    for message in &file.message_type {
+        collect_message_types(message, &scope, &mut types);
+    }
+    for enum_type in &file.enum_type {
+        types.push(format!("{scope}.{}", enum_type.name()));
+    }
+    for service in &file.service {
+        types.push(format!("{scope}.{}", service.name()));
+    }
+    types
+}
+
+fn collect_message_types(message: &DescriptorProto, scope: &str, out: &mut Vec<String>) {
+    let fq_name = format!("{scope}.{}", message.name());
+    for nested in &message.nested_type {
+        collect_message_types(nested, &fq_name, out);
+    }
+    for enum_type in &message.enum_type {
+        out.push(format!("{fq_name}.{}", enum_type.name()));
+    }
+    out.push(fq_name);
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::{FieldDescriptorProto, FileDescriptorSet};
+
+    use super::*;
+
+    fn file(name: &str, package: &str, messages: &[&str]) -> FileDescriptorProto {
+        FileDescriptorProto {
+            name: Some(name.to_string()),
+            package: Some(package.to_string()),
+            message_type: messages
+                .iter()
+                .map(|m| DescriptorProto {
+                    name: Some(m.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn source(name: &str, files: Vec<FileDescriptorProto>) -> Source {
+        Source::new(name, FileDescriptorSet { file: files }.into())
+    }
+
+    #[test]
+    fn test_merge_deduplicates_identical_files() {
+        let common = file("common/v1/money.proto", "common.v1", &["Money"]);
+        let merged = merge(vec![
+            source(
+                "a.binpb",
+                vec![common.clone(), file("a/v1/a.proto", "a.v1", &["A"])],
+            ),
+            source(
+                "b.binpb",
+                vec![common, file("b/v1/b.proto", "b.v1", &["B"])],
+            ),
+        ])
+        .expect("merge");
+
+        let names: Vec<&str> = merged.descriptors.file.iter().map(|f| f.name()).collect();
+        assert_eq!(
+            names,
+            vec!["common/v1/money.proto", "a/v1/a.proto", "b/v1/b.proto"]
+        );
+    }
+
+    #[test]
+    fn test_merge_file_conflict() {
+        let mut changed = file("common/v1/money.proto", "common.v1", &["Money"]);
+        changed.message_type[0].field.push(FieldDescriptorProto {
+            name: Some("units".to_string()),
+            number: Some(1),
+            ..Default::default()
+        });
+
+        let result = merge(vec![
+            source(
+                "a.binpb",
+                vec![file("common/v1/money.proto", "common.v1", &["Money"])],
+            ),
+            source("b.binpb", vec![changed]),
+        ]);
+
+        let err = result.expect_err("conflict");
+        assert!(matches!(err, CoralError::MergeConflict { .. }));
+        let message = err.to_string();
+        assert!(message.contains("common/v1/money.proto"));
+        assert!(message.contains("a.binpb"));
+        assert!(message.contains("b.binpb"));
+    }
+
+    #[test]
+    fn test_merge_type_conflict() {
+        let result = merge(vec![
+            source(
+                "a.binpb",
+                vec![file("common/money.proto", "common.v1", &["Money"])],
+            ),
+            source(
+                "b.binpb",
+                vec![file("common/v1/money.proto", "common.v1", &["Money"])],
+            ),
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(CoralError::MergeConflict { item, first, second })
+                if item.contains(".common.v1.Money") && first == "a.binpb" && second == "b.binpb"
+        ));
+    }
+
+    #[test]
+    fn test_merge_combines_is_import() {
+        let image = |files: Vec<FileDescriptorProto>, imports: &[&str]| {
+            let mut image: Image = FileDescriptorSet { file: files }.into();
+            for file in &image.descriptors.file {
+                let info = ImageFileInfo {
+                    is_import: imports.contains(&file.name()),
+                    module: Some("buf.build/acme/common".to_string()),
+                    ..Default::default()
+                };
+                image.files.insert(file.name().to_string(), info);
+            }
+            image
+        };
+        let common = file("common/v1/money.proto", "common.v1", &["Money"]);
+        let order = file("order/v1/order.proto", "order.v1", &["Order"]);
+
+        // Imported by the order image, a generation target of the common image
+        let merged = merge(vec![
+            Source::new(
+                "order.binpb",
+                image(vec![common.clone(), order], &["common/v1/money.proto"]),
+            ),
+            Source::new("common.binpb", image(vec![common.clone()], &[])),
+        ])
+        .expect("merge");
+        assert!(!merged.files["common/v1/money.proto"].is_import);
+        assert_eq!(
+            merged.files["common/v1/money.proto"].module.as_deref(),
+            Some("buf.build/acme/common")
+        );
+
+        // Imported everywhere stays an import
+        let merged = merge(vec![
+            Source::new(
+                "a.binpb",
+                image(vec![common.clone()], &["common/v1/money.proto"]),
+            ),
+            Source::new("b.binpb", image(vec![common], &["common/v1/money.proto"])),
+        ])
+        .expect("merge");
+        assert!(merged.files["common/v1/money.proto"].is_import);
+    }
+
+    #[test]
+    fn test_merge_plain_set_keeps_imports_external() {
+        let timestamp = file(
+            "google/protobuf/timestamp.proto",
+            "google.protobuf",
+            &["Timestamp"],
+        );
+        let mut image: Image = FileDescriptorSet {
+            file: vec![timestamp.clone()],
+        }
+        .into();
+        image.files.insert(
+            timestamp.name().to_string(),
+            ImageFileInfo {
+                is_import: true,
+                ..Default::default()
+            },
+        );
+        let plain = || source("proto-path", vec![timestamp.clone()]);
+
+        // Either order, the plain compile does not claim the image's import
+        for sources in [
+            vec![Source::new("api.binpb", image.clone()), plain()],
+            vec![plain(), Source::new("api.binpb", image.clone())],
+        ] {
+            let merged = merge(sources).expect("merge");
+            assert!(merged.files["google/protobuf/timestamp.proto"].is_import);
+        }
+    }
+
+    #[test]
+    fn test_merge_ignores_source_code_info() {
+        let plain = file("a.proto", "a", &["A"]);
+        let mut with_info = plain.clone();
+        with_info.source_code_info = Some(Default::default());
+
+        let merged = merge(vec![
+            source("a.binpb", vec![plain]),
+            source("b.binpb", vec![with_info]),
+        ])
+        .expect("merge");
+        assert_eq!(merged.descriptors.file.len(), 1);
+    }
+}
@@ -0,0 +1,1150 @@
+//! Recursive-descent parser producing descriptors from `.proto` tokens.
+//!
+//! Type references are left exactly as written in the source; the
+//! resolver rewrites them into fully-qualified names once every file
+//! of the set has been parsed.
+
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{
+    DescriptorProto, EnumDescriptorProto, EnumOptions, EnumValueDescriptorProto, EnumValueOptions,
+    FieldDescriptorProto, FieldOptions, FileDescriptorProto, FileOptions, MessageOptions,
+    MethodDescriptorProto, MethodOptions, OneofDescriptorProto, OneofOptions,
+    ServiceDescriptorProto, ServiceOptions, UninterpretedOption, descriptor_proto,
+    enum_descriptor_proto, uninterpreted_option,
+};
+
+use super::lexer::{Token, TokenKind, tokenize};
+use super::locations::{Located, source_code_info};
+use crate::error::{CoralError, Result};
+
+/// Highest valid field number (2^29 - 1).
+const MAX_FIELD_NUMBER: i32 = 536_870_911;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Proto2,
+    Proto3,
+    Editions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum OptionValue {
+    Ident(String),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Aggregate,
+}
+
+impl OptionValue {
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Ident(s) if s == "true" => Some(true),
+            Self::Ident(s) if s == "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// `features.<name> = VALUE` as protoc's parser records it before option
+    /// interpretation; other options yield `None`.
+    fn feature_option(name: &str, value: &Self) -> Option<UninterpretedOption> {
+        let feature = name.strip_prefix("features.")?;
+        let Self::Ident(identifier) = value else {
+            return None;
+        };
+        let part = |name: &str| uninterpreted_option::NamePart {
+            name_part: name.to_string(),
+            is_extension: false,
+        };
+        Some(UninterpretedOption {
+            name: vec![part("features"), part(feature)],
+            identifier_value: Some(identifier.clone()),
+            ..Default::default()
+        })
+    }
+
+    /// Text form used by `FieldDescriptorProto.default_value`.
+    fn to_default_string(&self) -> String {
+        match self {
+            Self::Ident(s) | Self::Str(s) => s.clone(),
+            Self::Int(n) => n.to_string(),
+            Self::Float(f) if f.is_infinite() && *f > 0.0 => "inf".to_string(),
+            Self::Float(f) if f.is_infinite() => "-inf".to_string(),
+            Self::Float(f) if f.is_nan() => "nan".to_string(),
+            Self::Float(f) => f.to_string(),
+            Self::Aggregate => String::new(),
+        }
+    }
+}
+
+/// Parse a single `.proto` source into a descriptor with unresolved type
+/// names. `source_code_info` holds the span and comments of every message,
+/// field, oneof, enum, enum value, service, method and extension.
+pub fn parse_file(name: &str, source: &str) -> Result<FileDescriptorProto> {
+    let (tokens, comments) = tokenize(name, source)?;
+    let mut parser = Parser {
+        file: name,
+        tokens,
+        pos: 0,
+        syntax: Syntax::Proto2,
+        path: Vec::new(),
+        located: Vec::new(),
+    };
+    let mut file = parser.file(name)?;
+    file.source_code_info = Some(source_code_info(&parser.located, &parser.tokens, &comments));
+    Ok(file)
+}
+
+struct Parser<'a> {
+    file: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    syntax: Syntax,
+    /// `SourceCodeInfo` path of the definition being parsed.
+    path: Vec<i32>,
+    located: Vec<Located>,
+}
+
+impl Parser<'_> {
+    // ----- token helpers -----
+
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.pos + offset).map(|t| &t.kind)
+    }
+
+    /// Advances even at end of input so callers can always step back by one.
+    fn next(&mut self) -> Option<TokenKind> {
+        let token = self.tokens.get(self.pos).map(|t| t.kind.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn error(&self, message: impl Into<String>) -> CoralError {
+        let (line, column) = self
+            .tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or((0, 0), |t| (t.line, t.column));
+        CoralError::ProtoSyntax {
+            file: self.file.to_string(),
+            line: line + 1,
+            column: column + 1,
+            message: message.into(),
+        }
+    }
+
+    fn is_symbol(&self, c: char) -> bool {
+        self.peek() == Some(&TokenKind::Symbol(c))
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(TokenKind::Ident(s)) if s == keyword)
+    }
+
+    fn eat_symbol(&mut self, c: char) -> bool {
+        if self.is_symbol(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, c: char) -> Result<()> {
+        if self.eat_symbol(c) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{c}`")))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{keyword}`")))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(TokenKind::Ident(s)) => {
+                let s = s.clone();
+                self.pos += 1;
+                Ok(s)
+            }
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    /// Dotted identifier, optionally with a leading dot (`.pkg.Type`).
+    fn full_ident(&mut self) -> Result<String> {
+        let mut out = String::new();
+        if self.eat_symbol('.') {
+            out.push('.');
+        }
+        out.push_str(&self.ident()?);
+        while self.eat_symbol('.') {
+            out.push('.');
+            out.push_str(&self.ident()?);
+        }
+        Ok(out)
+    }
+
+    /// One or more adjacent string literals, concatenated.
+    fn string(&mut self) -> Result<String> {
+        let mut out = match self.next() {
+            Some(TokenKind::Str(s)) => s,
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected string literal"));
+            }
+        };
+        while let Some(TokenKind::Str(s)) = self.peek() {
+            out.push_str(s);
+            self.pos += 1;
+        }
+        Ok(out)
+    }
+
+    fn int(&mut self) -> Result<i128> {
+        let negative = self.eat_symbol('-');
+        match self.peek() {
+            Some(TokenKind::Int(n)) => {
+                let n = i128::from(*n);
+                self.pos += 1;
+                Ok(if negative { -n } else { n })
+            }
+            _ => Err(self.error("expected integer")),
+        }
+    }
+
+    fn int32(&mut self) -> Result<i32> {
+        let value = self.int()?;
+        i32::try_from(value).map_err(|_| self.error(format!("integer {value} is out of range")))
+    }
+
+    fn end_statement(&mut self) -> Result<()> {
+        self.expect_symbol(';')
+    }
+
+    // ----- source locations -----
+
+    /// Record element `index` of the descriptor field numbered `field` (a
+    /// child of the current path) as spanning token `start` up to the
+    /// last consumed token.
+    fn record(&mut self, field: i32, index: usize, start: usize) {
+        let mut path = self.path.clone();
+        path.extend([field, index as i32]);
+        self.located.push(Located {
+            path,
+            start,
+            end: self.pos - 1,
+        });
+    }
+
+    /// Run `parse` with the path descended into element `index` of `field`,
+    /// then [`Self::record`] the element.
+    fn located<T>(
+        &mut self,
+        field: i32,
+        index: usize,
+        start: usize,
+        parse: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.path.extend([field, index as i32]);
+        let parsed = parse(self)?;
+        self.path.truncate(self.path.len() - 2);
+        self.record(field, index, start);
+        Ok(parsed)
+    }
+
+    // ----- file -----
+
+    fn file(&mut self, name: &str) -> Result<FileDescriptorProto> {
+        let mut file = FileDescriptorProto {
+            name: Some(name.to_string()),
+            ..Default::default()
+        };
+
+        if self.eat_keyword("syntax") {
+            self.expect_symbol('=')?;
+            let syntax = self.string()?;
+            self.syntax = match syntax.as_str() {
+                "proto2" => Syntax::Proto2,
+                "proto3" => Syntax::Proto3,
+                _ => return Err(self.error(format!("unknown syntax `{syntax}`"))),
+            };
+            self.end_statement()?;
+            if self.syntax == Syntax::Proto3 {
+                file.syntax = Some(syntax);
+            }
+        } else if self.eat_keyword("edition") {
+            self.expect_symbol('=')?;
+            self.string()?;
+            self.end_statement()?;
+            self.syntax = Syntax::Editions;
+            file.syntax = Some("editions".to_string());
+        }
+
+        while self.peek().is_some() {
+            if self.eat_symbol(';') {
+                continue;
+            }
+            match self.ident()?.as_str() {
+                "package" => {
+                    if file.package.is_some() {
+                        return Err(self.error("multiple package declarations"));
+                    }
+                    file.package = Some(self.full_ident()?);
+                    self.end_statement()?;
+                }
+                "import" => {
+                    let index = file.dependency.len() as i32;
+                    if self.eat_keyword("public") {
+                        file.public_dependency.push(index);
+                    } else if self.eat_keyword("weak") {
+                        file.weak_dependency.push(index);
+                    }
+                    file.dependency.push(self.string()?);
+                    self.end_statement()?;
+                }
+                "option" => {
+                    let (name, value) = self.option_assignment()?;
+                    self.end_statement()?;
+                    Self::apply_file_option(
+                        file.options.get_or_insert_with(Default::default),
+                        &name,
+                        &value,
+                    );
+                }
+                "message" => {
+                    let start = self.pos - 1;
+                    let message = self.located(4, file.message_type.len(), start, Self::message)?;
+                    file.message_type.push(message);
+                }
+                "enum" => {
+                    let start = self.pos - 1;
+                    let enumeration =
+                        self.located(5, file.enum_type.len(), start, Self::enumeration)?;
+                    file.enum_type.push(enumeration);
+                }
+                "service" => {
+                    let start = self.pos - 1;
+                    let service = self.located(6, file.service.len(), start, Self::service)?;
+                    file.service.push(service);
+                }
+                "extend" => {
+                    let mut nested = Vec::new();
+                    let extensions = self.extend(&mut nested, 7, file.extension.len())?;
+                    file.extension.extend(extensions);
+                    file.message_type.extend(nested);
+                }
+                other => return Err(self.error(format!("unexpected `{other}`"))),
+            }
+        }
+
+        Ok(file)
+    }
+
+    // ----- options -----
+
+    /// `name = value` where name may contain parenthesized extension names.
+    fn option_assignment(&mut self) -> Result<(String, OptionValue)> {
+        let name = self.option_name()?;
+        self.expect_symbol('=')?;
+        let value = self.option_value()?;
+        Ok((name, value))
+    }
+
+    fn option_name(&mut self) -> Result<String> {
+        let mut name = String::new();
+        loop {
+            if self.eat_symbol('(') {
+                name.push('(');
+                name.push_str(&self.full_ident()?);
+                self.expect_symbol(')')?;
+                name.push(')');
+            } else {
+                name.push_str(&self.ident()?);
+            }
+            if !self.eat_symbol('.') {
+                return Ok(name);
+            }
+            name.push('.');
+        }
+    }
+
+    fn option_value(&mut self) -> Result<OptionValue> {
+        if self.is_symbol('{') {
+            self.skip_aggregate()?;
+            return Ok(OptionValue::Aggregate);
+        }
+
+        let negative = self.eat_symbol('-');
+        let value = match self.next() {
+            Some(TokenKind::Int(n)) => {
+                let n = i128::from(n);
+                OptionValue::Int(if negative { -n } else { n })
+            }
+            Some(TokenKind::Float(f)) => OptionValue::Float(if negative { -f } else { f }),
+            Some(TokenKind::Ident(s)) if negative && (s == "inf" || s == "nan") => {
+                OptionValue::Float(if s == "inf" {
+                    f64::NEG_INFINITY
+                } else {
+                    f64::NAN
+                })
+            }
+            Some(TokenKind::Ident(s)) if !negative => {
+                let mut ident = s;
+                while self.eat_symbol('.') {
+                    ident.push('.');
+                    ident.push_str(&self.ident()?);
+                }
+                OptionValue::Ident(ident)
+            }
+            Some(TokenKind::Str(s)) if !negative => {
+                let mut s = s;
+                while let Some(TokenKind::Str(next)) = self.peek() {
+                    s.push_str(next);
+                    self.pos += 1;
+                }
+                OptionValue::Str(s)
+            }
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected option value"));
+            }
+        };
+        Ok(value)
+    }
+
+    /// Skip a text-format aggregate value (`{ ... }`), honoring nesting.
+    fn skip_aggregate(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            match self.next() {
+                Some(TokenKind::Symbol('{')) => depth += 1,
+                Some(TokenKind::Symbol('}')) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(_) => {}
+                None => return Err(self.error("unterminated aggregate option value")),
+            }
+        }
+    }
+
+    /// `[name = value, ...]` compact options; returns them in source order.
+    fn compact_options(&mut self) -> Result<Vec<(String, OptionValue)>> {
+        let mut options = Vec::new();
+        if !self.eat_symbol('[') {
+            return Ok(options);
+        }
+        loop {
+            options.push(self.option_assignment()?);
+            if self.eat_symbol(']') {
+                return Ok(options);
+            }
+            self.expect_symbol(',')?;
+        }
+    }
+
+    fn apply_file_option(options: &mut FileOptions, name: &str, value: &OptionValue) {
+        let text = value.as_str().map(str::to_string);
+        match name {
+            "java_package" => options.java_package = text,
+            "java_outer_classname" => options.java_outer_classname = text,
+            "java_multiple_files" => options.java_multiple_files = value.as_bool(),
+            "go_package" => options.go_package = text,
+            "csharp_namespace" => options.csharp_namespace = text,
+            "objc_class_prefix" => options.objc_class_prefix = text,
+            "php_namespace" => options.php_namespace = text,
+            "ruby_package" => options.ruby_package = text,
+            "swift_prefix" => options.swift_prefix = text,
+            "cc_enable_arenas" => options.cc_enable_arenas = value.as_bool(),
+            "deprecated" => options.deprecated = value.as_bool(),
+            _ => options
+                .uninterpreted_option
+                .extend(OptionValue::feature_option(name, value)),
+        }
+    }
+
+    // ----- messages -----
+
+    fn message(&mut self) -> Result<DescriptorProto> {
+        let name = self.ident()?;
+        self.message_body(name)
+    }
+
+    fn message_body(&mut self, name: String) -> Result<DescriptorProto> {
+        let mut message = DescriptorProto {
+            name: Some(name),
+            ..Default::default()
+        };
+        let mut synthetic_oneofs = Vec::new();
+
+        self.expect_symbol('{')?;
+        while !self.eat_symbol('}') {
+            if self.peek().is_none() {
+                return Err(self.error("unexpected end of file in message"));
+            }
+            if self.eat_symbol(';') {
+                continue;
+            }
+
+            let start = self.pos;
+            if self.is_definition("message") {
+                self.pos += 1;
+                let nested = self.located(3, message.nested_type.len(), start, Self::message)?;
+                message.nested_type.push(nested);
+            } else if self.is_definition("enum") {
+                self.pos += 1;
+                let enumeration =
+                    self.located(4, message.enum_type.len(), start, Self::enumeration)?;
+                message.enum_type.push(enumeration);
+            } else if self.is_definition("oneof") {
+                self.pos += 1;
+                self.oneof(&mut message)?;
+            } else if self.is_keyword("extend") {
+                self.pos += 1;
+                let mut nested = Vec::new();
+                let extensions = self.extend(&mut nested, 6, message.extension.len())?;
+                message.extension.extend(extensions);
+                message.nested_type.extend(nested);
+            } else if self.is_keyword("option") {
+                self.pos += 1;
+                let (name, value) = self.option_assignment()?;
+                self.end_statement()?;
+                let options = message.options.get_or_insert_with(MessageOptions::default);
+                match name.as_str() {
+                    "deprecated" => options.deprecated = value.as_bool(),
+                    "message_set_wire_format" => {
+                        options.message_set_wire_format = value.as_bool();
+                    }
+                    "no_standard_descriptor_accessor" => {
+                        options.no_standard_descriptor_accessor = value.as_bool();
+                    }
+                    _ => options
+                        .uninterpreted_option
+                        .extend(OptionValue::feature_option(&name, &value)),
+                }
+            } else if self.is_keyword("reserved") {
+                self.pos += 1;
+                self.message_reserved(&mut message)?;
+            } else if self.is_keyword("extensions") {
+                self.pos += 1;
+                self.extension_ranges(&mut message)?;
+            } else {
+                let (field, nested) =
+                    self.located(2, message.field.len(), start, |p| p.field(None))?;
+                if field.proto3_optional == Some(true) {
+                    synthetic_oneofs.push(message.field.len());
+                }
+                message.field.push(field);
+                message.nested_type.extend(nested);
+            }
+        }
+
+        // Synthetic oneofs for proto3 `optional` come after all real oneofs.
+        for field_index in synthetic_oneofs {
+            let field = &mut message.field[field_index];
+            field.oneof_index = Some(message.oneof_decl.len() as i32);
+            message.oneof_decl.push(OneofDescriptorProto {
+                name: Some(format!("_{}", field.name())),
+                options: None,
+            });
+        }
+
+        Ok(message)
+    }
+
+    /// `keyword Ident {` — distinguishes definitions from fields whose type
+    /// happens to be named like a keyword.
+    fn is_definition(&self, keyword: &str) -> bool {
+        self.is_keyword(keyword)
+            && matches!(self.peek_at(1), Some(TokenKind::Ident(_)))
+            && self.peek_at(2) == Some(&TokenKind::Symbol('{'))
+    }
+
+    fn oneof(&mut self, message: &mut DescriptorProto) -> Result<()> {
+        let start = self.pos - 1;
+        let index = message.oneof_decl.len() as i32;
+        message.oneof_decl.push(OneofDescriptorProto {
+            name: Some(self.ident()?),
+            options: None,
+        });
+
+        self.expect_symbol('{')?;
+        while !self.eat_symbol('}') {
+            if self.peek().is_none() {
+                return Err(self.error("unexpected end of file in oneof"));
+            }
+            if self.eat_symbol(';') {
+                continue;
+            }
+            if self.eat_keyword("option") {
+                let (name, value) = self.option_assignment()?;
+                self.end_statement()?;
+                if let Some(feature) = OptionValue::feature_option(&name, &value) {
+                    message.oneof_decl[index as usize]
+                        .options
+                        .get_or_insert_with(OneofOptions::default)
+                        .uninterpreted_option
+                        .push(feature);
+                }
+                continue;
+            }
+            let (mut field, nested) = self.located(2, message.field.len(), self.pos, |p| {
+                p.field(Some(Label::Optional))
+            })?;
+            field.oneof_index = Some(index);
+            message.field.push(field);
+            message.nested_type.extend(nested);
+        }
+        self.record(8, index as usize, start);
+        Ok(())
+    }
+
+    /// Parses a field, map field or group. Returns the field together with any
+    /// synthesized nested message (map entry or group body).
+    fn field(
+        &mut self,
+        forced_label: Option<Label>,
+    ) -> Result<(FieldDescriptorProto, Vec<DescriptorProto>)> {
+        if self.is_keyword("map") && self.peek_at(1) == Some(&TokenKind::Symbol('<')) {
+            self.pos += 1;
+            return self.map_field();
+        }
+
+        let mut proto3_optional = false;
+        let label = if forced_label.is_some() {
+            forced_label
+        } else if self.eat_keyword("repeated") {
+            Some(Label::Repeated)
+        } else if self.eat_keyword("required") {
+            Some(Label::Required)
+        } else if self.eat_keyword("optional") {
+            proto3_optional = self.syntax == Syntax::Proto3;
+            Some(Label::Optional)
+        } else if self.syntax == Syntax::Proto2 {
+            return Err(self.error("expected field label (optional, required or repeated)"));
+        } else {
+            Some(Label::Optional)
+        };
+
+        if self.is_keyword("group")
+            && matches!(self.peek_at(1), Some(TokenKind::Ident(_)))
+            && self.peek_at(2) == Some(&TokenKind::Symbol('='))
+        {
+            self.pos += 1;
+            return self.group(label);
+        }
+
+        let (r#type, type_name) = self.field_type()?;
+        let name = self.ident()?;
+        self.expect_symbol('=')?;
+        let number = self.int32()?;
+
+        let mut field = FieldDescriptorProto {
+            json_name: Some(to_json_name(&name)),
+            name: Some(name),
+            number: Some(number),
+            label: label.map(|l| l as i32),
+            r#type: r#type.map(|t| t as i32),
+            type_name,
+            proto3_optional: proto3_optional.then_some(true),
+            ..Default::default()
+        };
+        let options = self.compact_options()?;
+        self.apply_field_options(&mut field, &options);
+        self.end_statement()?;
+
+        Ok((field, Vec::new()))
+    }
+
+    /// Returns the scalar type, or the raw type reference for messages and enums.
+    fn field_type(&mut self) -> Result<(Option<Type>, Option<String>)> {
+        let type_name = self.full_ident()?;
+        Ok(match scalar_type(&type_name) {
+            Some(scalar) => (Some(scalar), None),
+            None => (None, Some(type_name)),
+        })
+    }
+
+    fn map_field(&mut self) -> Result<(FieldDescriptorProto, Vec<DescriptorProto>)> {
+        self.expect_symbol('<')?;
+        let (key_type, key_type_name) = self.field_type()?;
+        self.expect_symbol(',')?;
+        let (value_type, value_type_name) = self.field_type()?;
+        self.expect_symbol('>')?;
+        let name = self.ident()?;
+        self.expect_symbol('=')?;
+        let number = self.int32()?;
+
+        let entry_name = format!("{}Entry", to_camel_case(&name));
+        let entry = DescriptorProto {
+            name: Some(entry_name.clone()),
+            field: vec![
+                FieldDescriptorProto {
+                    name: Some("key".to_string()),
+                    json_name: Some("key".to_string()),
+                    number: Some(1),
+                    label: Some(Label::Optional as i32),
+                    r#type: key_type.map(|t| t as i32),
+                    type_name: key_type_name,
+                    ..Default::default()
+                },
+                FieldDescriptorProto {
+                    name: Some("value".to_string()),
+                    json_name: Some("value".to_string()),
+                    number: Some(2),
+                    label: Some(Label::Optional as i32),
+                    r#type: value_type.map(|t| t as i32),
+                    type_name: value_type_name,
+                    ..Default::default()
+                },
+            ],
+            options: Some(MessageOptions {
+                map_entry: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut field = FieldDescriptorProto {
+            json_name: Some(to_json_name(&name)),
+            name: Some(name),
+            number: Some(number),
+            label: Some(Label::Repeated as i32),
+            r#type: Some(Type::Message as i32),
+            type_name: Some(entry_name),
+            ..Default::default()
+        };
+        let options = self.compact_options()?;
+        self.apply_field_options(&mut field, &options);
+        self.end_statement()?;
+
+        Ok((field, vec![entry]))
+    }
+
+    fn group(
+        &mut self,
+        label: Option<Label>,
+    ) -> Result<(FieldDescriptorProto, Vec<DescriptorProto>)> {
+        let group_name = self.ident()?;
+        self.expect_symbol('=')?;
+        let number = self.int32()?;
+        let options = self.compact_options()?;
+        // Group bodies are not located: their path depends on the enclosing message
+        let recorded = self.located.len();
+        let body = self.message_body(group_name.clone())?;
+        self.located.truncate(recorded);
+
+        let name = group_name.to_ascii_lowercase();
+        let mut field = FieldDescriptorProto {
+            json_name: Some(to_json_name(&name)),
+            name: Some(name),
+            number: Some(number),
+            label: label.map(|l| l as i32),
+            r#type: Some(Type::Group as i32),
+            type_name: Some(group_name),
+            ..Default::default()
+        };
+        self.apply_field_options(&mut field, &options);
+
+        Ok((field, vec![body]))
+    }
+
+    fn apply_field_options(
+        &self,
+        field: &mut FieldDescriptorProto,
+        options: &[(String, OptionValue)],
+    ) {
+        for (name, value) in options {
+            match name.as_str() {
+                "default" => field.default_value = Some(value.to_default_string()),
+                "json_name" => field.json_name = value.as_str().map(str::to_string),
+                "deprecated" => {
+                    field
+                        .options
+                        .get_or_insert_with(FieldOptions::default)
+                        .deprecated = value.as_bool();
+                }
+                "packed" => {
+                    field
+                        .options
+                        .get_or_insert_with(FieldOptions::default)
+                        .packed = value.as_bool();
+                }
+                "lazy" => {
+                    field.options.get_or_insert_with(FieldOptions::default).lazy = value.as_bool();
+                }
+                "weak" => {
+                    field.options.get_or_insert_with(FieldOptions::default).weak = value.as_bool();
+                }
+                _ => {
+                    if let Some(feature) = OptionValue::feature_option(name, value) {
+                        field
+                            .options
+                            .get_or_insert_with(FieldOptions::default)
+                            .uninterpreted_option
+                            .push(feature);
+                    }
+                }
+            }
+        }
+    }
+
+    fn message_reserved(&mut self, message: &mut DescriptorProto) -> Result<()> {
+        if matches!(self.peek(), Some(TokenKind::Str(_) | TokenKind::Ident(_))) {
+            loop {
+                let name = match self.next() {
+                    Some(TokenKind::Str(s) | TokenKind::Ident(s)) => s,
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.error("expected reserved name"));
+                    }
+                };
+                message.reserved_name.push(name);
+                if !self.eat_symbol(',') {
+                    break;
+                }
+            }
+        } else {
+            for (start, end) in self.ranges(MAX_FIELD_NUMBER)? {
+                message
+                    .reserved_range
+                    .push(descriptor_proto::ReservedRange {
+                        start: Some(start),
+                        end: Some(end + 1),
+                    });
+            }
+        }
+        self.end_statement()
+    }
+
+    fn extension_ranges(&mut self, message: &mut DescriptorProto) -> Result<()> {
+        let ranges = self.ranges(MAX_FIELD_NUMBER)?;
+        self.compact_options()?;
+        self.end_statement()?;
+        for (start, end) in ranges {
+            message
+                .extension_range
+                .push(descriptor_proto::ExtensionRange {
+                    start: Some(start),
+                    end: Some(end + 1),
+                    options: None,
+                });
+        }
+        Ok(())
+    }
+
+    /// Comma-separated `N` / `N to M` / `N to max` ranges with inclusive ends.
+    fn ranges(&mut self, max: i32) -> Result<Vec<(i32, i32)>> {
+        let mut ranges = Vec::new();
+        loop {
+            let start = self.int32()?;
+            let end = if self.eat_keyword("to") {
+                if self.eat_keyword("max") {
+                    max
+                } else {
+                    self.int32()?
+                }
+            } else {
+                start
+            };
+            ranges.push((start, end));
+            if !self.eat_symbol(',') {
+                return Ok(ranges);
+            }
+        }
+    }
+
+    // ----- extend -----
+
+    /// Extensions are located as elements `first_index..` of the enclosing
+    /// file's or message's descriptor field `extension_field`.
+    fn extend(
+        &mut self,
+        nested: &mut Vec<DescriptorProto>,
+        extension_field: i32,
+        first_index: usize,
+    ) -> Result<Vec<FieldDescriptorProto>> {
+        let extendee = self.full_ident()?;
+        let mut fields = Vec::new();
+
+        self.expect_symbol('{')?;
+        while !self.eat_symbol('}') {
+            if self.peek().is_none() {
+                return Err(self.error("unexpected end of file in extend block"));
+            }
+            if self.eat_symbol(';') {
+                continue;
+            }
+            let index = first_index + fields.len();
+            let (mut field, groups) =
+                self.located(extension_field, index, self.pos, |p| p.field(None))?;
+            field.extendee = Some(extendee.clone());
+            // Extensions are never members of a synthetic oneof.
+            field.proto3_optional = None;
+            fields.push(field);
+            nested.extend(groups);
+        }
+        Ok(fields)
+    }
+
+    // ----- enums -----
+
+    fn enumeration(&mut self) -> Result<EnumDescriptorProto> {
+        let mut enumeration = EnumDescriptorProto {
+            name: Some(self.ident()?),
+            ..Default::default()
+        };
+
+        self.expect_symbol('{')?;
+        while !self.eat_symbol('}') {
+            if self.peek().is_none() {
+                return Err(self.error("unexpected end of file in enum"));
+            }
+            if self.eat_symbol(';') {
+                continue;
+            }
+            if self.eat_keyword("option") {
+                let (name, value) = self.option_assignment()?;
+                self.end_statement()?;
+                let options = enumeration.options.get_or_insert_with(EnumOptions::default);
+                match name.as_str() {
+                    "allow_alias" => options.allow_alias = value.as_bool(),
+                    "deprecated" => options.deprecated = value.as_bool(),
+                    _ => options
+                        .uninterpreted_option
+                        .extend(OptionValue::feature_option(&name, &value)),
+                }
+            } else if self.is_keyword("reserved")
+                && !matches!(self.peek_at(1), Some(TokenKind::Symbol('=')))
+            {
+                self.pos += 1;
+                self.enum_reserved(&mut enumeration)?;
+            } else {
+                let start = self.pos;
+                let name = self.ident()?;
+                self.expect_symbol('=')?;
+                let number = self.int32()?;
+                let mut value = EnumValueDescriptorProto {
+                    name: Some(name),
+                    number: Some(number),
+                    options: None,
+                };
+                for (name, option) in self.compact_options()? {
+                    if name == "deprecated" {
+                        value
+                            .options
+                            .get_or_insert_with(EnumValueOptions::default)
+                            .deprecated = option.as_bool();
+                    }
+                }
+                self.end_statement()?;
+                self.record(2, enumeration.value.len(), start);
+                enumeration.value.push(value);
+            }
+        }
+        Ok(enumeration)
+    }
+
+    fn enum_reserved(&mut self, enumeration: &mut EnumDescriptorProto) -> Result<()> {
+        if matches!(self.peek(), Some(TokenKind::Str(_) | TokenKind::Ident(_))) {
+            loop {
+                match self.next() {
+                    Some(TokenKind::Str(s) | TokenKind::Ident(s)) => {
+                        enumeration.reserved_name.push(s);
+                    }
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.error("expected reserved name"));
+                    }
+                }
+                if !self.eat_symbol(',') {
+                    break;
+                }
+            }
+        } else {
+            for (start, end) in self.ranges(i32::MAX)? {
+                enumeration
+                    .reserved_range
+                    .push(enum_descriptor_proto::EnumReservedRange {
+                        start: Some(start),
+                        end: Some(end),
+                    });
+            }
+        }
+        self.end_statement()
+    }
+
+    // ----- services -----
+
+    fn service(&mut self) -> Result<ServiceDescriptorProto> {
+        let mut service = ServiceDescriptorProto {
+            name: Some(self.ident()?),
+            ..Default::default()
+        };
+
+        self.expect_symbol('{')?;
+        while !self.eat_symbol('}') {
+            if self.peek().is_none() {
+                return Err(self.error("unexpected end of file in service"));
+            }
+            if self.eat_symbol(';') {
+                continue;
+            }
+            if self.eat_keyword("option") {
+                let (name, value) = self.option_assignment()?;
+                self.end_statement()?;
+                if name == "deprecated" {
+                    service
+                        .options
+                        .get_or_insert_with(ServiceOptions::default)
+                        .deprecated = value.as_bool();
+                }
+            } else {
+                let start = self.pos;
+                self.expect_keyword("rpc")?;
+                let method = self.located(2, service.method.len(), start, Self::method)?;
+                service.method.push(method);
+            }
+        }
+        Ok(service)
+    }
+
+    fn method(&mut self) -> Result<MethodDescriptorProto> {
+        let name = self.ident()?;
+        let (client_streaming, input_type) = self.method_type()?;
+        self.expect_keyword("returns")?;
+        let (server_streaming, output_type) = self.method_type()?;
+
+        let mut method = MethodDescriptorProto {
+            name: Some(name),
+            input_type: Some(input_type),
+            output_type: Some(output_type),
+            options: None,
+            client_streaming: client_streaming.then_some(true),
+            server_streaming: server_streaming.then_some(true),
+        };
+
+        if self.eat_symbol('{') {
+            while !self.eat_symbol('}') {
+                if self.peek().is_none() {
+                    return Err(self.error("unexpected end of file in rpc"));
+                }
+                if self.eat_symbol(';') {
+                    continue;
+                }
+                self.expect_keyword("option")?;
+                let (name, value) = self.option_assignment()?;
+                self.end_statement()?;
+                if name == "deprecated" {
+                    method
+                        .options
+                        .get_or_insert_with(MethodOptions::default)
+                        .deprecated = value.as_bool();
+                }
+            }
+        } else {
+            self.end_statement()?;
+        }
+        Ok(method)
+    }
+
+    /// `( [stream] Type )`
+    fn method_type(&mut self) -> Result<(bool, String)> {
+        self.expect_symbol('(')?;
+        let streaming = self.is_keyword("stream")
+            && matches!(
+                self.peek_at(1),
+                Some(TokenKind::Ident(_) | TokenKind::Symbol('.'))
+            );
+        if streaming {
+            self.pos += 1;
+        }
+        let type_name = self.full_ident()?;
+        self.expect_symbol(')')?;
+        Ok((streaming, type_name))
+    }
+}
+
+fn scalar_type(name: &str) -> Option<Type> {
+    Some(match name {
+        "double" => Type::Double,
+        "float" => Type::Float,
+        "int64" => Type::Int64,
+        "uint64" => Type::Uint64,
+        "int32" => Type::Int32,
+        "fixed64" => Type::Fixed64,
+        "fixed32" => Type::Fixed32,
+        "bool" => Type::Bool,
+        "string" => Type::String,
+        "bytes" => Type::Bytes,
+        "uint32" => Type::Uint32,
+        "sfixed32" => Type::Sfixed32,
+        "sfixed64" => Type::Sfixed64,
+        "sint32" => Type::Sint32,
+        "sint64" => Type::Sint64,
+        _ => return None,
+    })
+}
+
+/// `user_id` → `userId`, matching protoc's default `json_name`.
+fn to_json_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            out.push(c.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `shipping_rates` → `ShippingRates`, used for map entry names.
+fn to_camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper_next = true;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            out.push(c.to_ascii_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,346 @@
+//! Tokenizer for `.proto` source files.
+
+use crate::error::{CoralError, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Int(u64),
+    Float(f64),
+    Str(String),
+    Symbol(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// 0-based line of the first character.
+    pub line: usize,
+    /// 0-based column of the first character.
+    pub column: usize,
+}
+
+/// A `//` line or `/* */` block comment, without its delimiters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// Line comments keep their trailing newline, as in `SourceCodeInfo`.
+    pub text: String,
+    /// 0-based line of the opening delimiter.
+    pub line: usize,
+    /// 0-based column of the opening delimiter.
+    pub column: usize,
+    /// 0-based line the comment ends on.
+    pub end_line: usize,
+}
+
+/// Splits proto source into tokens, setting comments aside and skipping
+/// whitespace.
+pub fn tokenize(file: &str, source: &str) -> Result<(Vec<Token>, Vec<Comment>)> {
+    Lexer {
+        file,
+        chars: source.chars().collect(),
+        pos: 0,
+        line: 0,
+        column: 0,
+    }
+    .run()
+}
+
+struct Lexer<'a> {
+    file: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer<'_> {
+    fn run(mut self) -> Result<(Vec<Token>, Vec<Comment>)> {
+        let mut tokens = Vec::new();
+        let mut comments = Vec::new();
+
+        while let Some(c) = self.peek(0) {
+            if c.is_whitespace() {
+                self.bump();
+                continue;
+            }
+            if c == '/' && self.peek(1) == Some('/') {
+                let (line, column) = (self.line, self.column);
+                self.bump();
+                self.bump();
+                let mut text = self.take_while(|c| c != '\n');
+                text.push('\n');
+                comments.push(Comment {
+                    text,
+                    line,
+                    column,
+                    end_line: line,
+                });
+                continue;
+            }
+            if c == '/' && self.peek(1) == Some('*') {
+                comments.push(self.block_comment()?);
+                continue;
+            }
+
+            let (line, column) = (self.line, self.column);
+            let kind = if c.is_ascii_alphabetic() || c == '_' {
+                TokenKind::Ident(self.take_while(|c| c.is_ascii_alphanumeric() || c == '_'))
+            } else if c.is_ascii_digit()
+                || (c == '.' && self.peek(1).is_some_and(|c| c.is_ascii_digit()))
+            {
+                self.number()?
+            } else if c == '"' || c == '\'' {
+                TokenKind::Str(self.string()?)
+            } else {
+                self.bump();
+                TokenKind::Symbol(c)
+            };
+            tokens.push(Token { kind, line, column });
+        }
+
+        Ok((tokens, comments))
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.peek(0).filter(|c| pred(*c)) {
+            out.push(c);
+            self.bump();
+        }
+        out
+    }
+
+    fn error(&self, message: impl Into<String>) -> CoralError {
+        CoralError::ProtoSyntax {
+            file: self.file.to_string(),
+            line: self.line + 1,
+            column: self.column + 1,
+            message: message.into(),
+        }
+    }
+
+    /// Block comment text with the ` * ` prefix of continuation lines removed.
+    fn block_comment(&mut self) -> Result<Comment> {
+        let (line, column) = (self.line, self.column);
+        self.bump();
+        self.bump();
+        let mut raw = String::new();
+        loop {
+            match self.bump() {
+                Some('*') if self.peek(0) == Some('/') => {
+                    self.bump();
+                    break;
+                }
+                Some(c) => raw.push(c),
+                None => return Err(self.error("unterminated block comment")),
+            }
+        }
+
+        let text = raw
+            .split('\n')
+            .enumerate()
+            .map(|(i, l)| match l.trim_start().strip_prefix('*') {
+                Some(rest) if i > 0 => rest,
+                _ => l,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(Comment {
+            text,
+            line,
+            column,
+            end_line: self.line,
+        })
+    }
+
+    fn number(&mut self) -> Result<TokenKind> {
+        let text = self.take_while(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_');
+        // Exponent sign, e.g. `1e-5`
+        let text = if text.ends_with(['e', 'E'])
+            && !text.starts_with("0x")
+            && matches!(self.peek(0), Some('+' | '-'))
+        {
+            let sign = self.bump().unwrap_or('+');
+            format!("{text}{sign}{}", self.take_while(|c| c.is_ascii_digit()))
+        } else {
+            text
+        };
+
+        let lower = text.to_ascii_lowercase();
+        let parsed = if let Some(hex) = lower.strip_prefix("0x") {
+            u64::from_str_radix(hex, 16).ok().map(TokenKind::Int)
+        } else if lower.contains(['.', 'e']) || lower.ends_with('f') {
+            lower
+                .trim_end_matches('f')
+                .parse::<f64>()
+                .ok()
+                .map(TokenKind::Float)
+        } else if lower.len() > 1 && lower.starts_with('0') {
+            u64::from_str_radix(&lower[1..], 8).ok().map(TokenKind::Int)
+        } else {
+            lower.parse::<u64>().ok().map(TokenKind::Int)
+        };
+
+        parsed.ok_or_else(|| self.error(format!("invalid number literal `{text}`")))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = self.bump().unwrap_or('"');
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                Some(c) if c == quote => return Ok(out),
+                Some('\\') => out.push(self.escape()?),
+                Some('\n') | None => return Err(self.error("unterminated string literal")),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char> {
+        let c = self
+            .bump()
+            .ok_or_else(|| self.error("unterminated string literal"))?;
+        let escaped = match c {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0c',
+            'v' => '\x0b',
+            'x' | 'X' => {
+                let hex = self.take_while_max(2, |c| c.is_ascii_hexdigit());
+                Self::code_point(u32::from_str_radix(&hex, 16).ok())
+                    .ok_or_else(|| self.error("invalid hex escape"))?
+            }
+            'u' | 'U' => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let hex = self.take_while_max(len, |c| c.is_ascii_hexdigit());
+                Self::code_point(u32::from_str_radix(&hex, 16).ok())
+                    .ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            '0'..='7' => {
+                let mut oct = c.to_string();
+                oct.push_str(&self.take_while_max(2, |c| ('0'..='7').contains(&c)));
+                Self::code_point(u32::from_str_radix(&oct, 8).ok())
+                    .ok_or_else(|| self.error("invalid octal escape"))?
+            }
+            other => other,
+        };
+        Ok(escaped)
+    }
+
+    fn take_while_max(&mut self, max: usize, pred: impl Fn(char) -> bool) -> String {
+        let mut out = String::new();
+        while out.len() < max {
+            match self.peek(0).filter(|c| pred(*c)) {
+                Some(c) => {
+                    out.push(c);
+                    self.bump();
+                }
+                None => break,
+            }
+        }
+        out
+    }
+
+    fn code_point(value: Option<u32>) -> Option<char> {
+        value.and_then(char::from_u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        tokenize("test.proto", source)
+            .expect("tokenize")
+            .0
+            .into_iter()
+            .map(|t| t.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_skips_comments() {
+        let tokens = kinds("// line\nmessage /* block */ Foo {}");
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Ident("message".to_string()),
+                TokenKind::Ident("Foo".to_string()),
+                TokenKind::Symbol('{'),
+                TokenKind::Symbol('}'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_collects_comments() {
+        let (_, comments) =
+            tokenize("test.proto", "// line\nmessage /* one\n * two */ Foo {}").expect("tokenize");
+        let texts: Vec<(&str, usize, usize)> = comments
+            .iter()
+            .map(|c| (c.text.as_str(), c.line, c.end_line))
+            .collect();
+        assert_eq!(texts, vec![(" line\n", 0, 0), (" one\n two ", 1, 2)]);
+    }
+
+    #[test]
+    fn test_tokenize_numbers() {
+        let tokens = kinds("42 0x1F 017 1.5 1e-3");
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Int(42),
+                TokenKind::Int(31),
+                TokenKind::Int(15),
+                TokenKind::Float(1.5),
+                TokenKind::Float(0.001),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_string_escapes() {
+        let tokens = kinds(r#""a\"b\n" 'c'"#);
+        assert_eq!(
+            tokens,
+            vec![
+                TokenKind::Str("a\"b\n".to_string()),
+                TokenKind::Str("c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_positions() {
+        let (tokens, _) = tokenize("test.proto", "syntax\n  = ").expect("tokenize");
+        assert_eq!((tokens[1].line, tokens[1].column), (1, 2));
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        let result = tokenize("test.proto", "\"abc");
+        assert!(matches!(result, Err(CoralError::ProtoSyntax { .. })));
+    }
+}
@@ -0,0 +1,89 @@
+//! `SourceCodeInfo` for parsed files: spans and comments of definitions.
+//!
+//! Comments are attached the way protoc does in the common cases: the
+//! comments directly above a definition (no blank line in between) are its
+//! leading comments, and a comment starting on the line of its final `;`
+//! (or opening `{` for blocks) is its trailing comment.
+
+use prost_types::SourceCodeInfo;
+use prost_types::source_code_info::Location;
+
+use super::lexer::{Comment, Token, TokenKind};
+
+/// A definition at `path` spanning tokens `start..=end`; `end` is always
+/// the `;` or `}` closing it.
+pub(super) struct Located {
+    pub path: Vec<i32>,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub(super) fn source_code_info(
+    located: &[Located],
+    tokens: &[Token],
+    comments: &[Comment],
+) -> SourceCodeInfo {
+    let location = located
+        .iter()
+        .filter_map(|l| {
+            let (first, last) = (tokens.get(l.start)?, tokens.get(l.end)?);
+            let span = if first.line == last.line {
+                vec![first.line, first.column, last.column + 1]
+            } else {
+                vec![first.line, first.column, last.line, last.column + 1]
+            };
+            Some(Location {
+                path: l.path.clone(),
+                span: span.into_iter().map(|n| n as i32).collect(),
+                leading_comments: leading(l.start, tokens, comments),
+                trailing_comments: trailing(l, tokens, comments),
+                leading_detached_comments: Vec::new(),
+            })
+        })
+        .collect();
+    SourceCodeInfo { location }
+}
+
+fn is_before(comment: &Comment, token: &Token) -> bool {
+    (comment.line, comment.column) < (token.line, token.column)
+}
+
+/// Consecutive comments ending right above (or on the line of) token `start`.
+fn leading(start: usize, tokens: &[Token], comments: &[Comment]) -> Option<String> {
+    let token = &tokens[start];
+    let previous = start.checked_sub(1).map(|i| &tokens[i]);
+
+    let mut block: Vec<&Comment> = Vec::new();
+    let mut next_line = token.line;
+    for comment in comments
+        .iter()
+        .rev()
+        .filter(|c| is_before(c, token) && previous.is_none_or(|p| !is_before(c, p)))
+    {
+        // A comment on the previous token's line is that token's trailing comment
+        let trails_previous = previous.is_some_and(|p| p.line == comment.line);
+        if trails_previous || comment.end_line + 1 < next_line {
+            break;
+        }
+        block.push(comment);
+        next_line = comment.line;
+    }
+    (!block.is_empty()).then(|| block.iter().rev().map(|c| c.text.as_str()).collect())
+}
+
+/// Comment following the definition's `;`, or its `{` for blocks, on the same line.
+fn trailing(located: &Located, tokens: &[Token], comments: &[Comment]) -> Option<String> {
+    let anchor = if tokens[located.end].kind == TokenKind::Symbol('}') {
+        (located.start..located.end).find(|&i| tokens[i].kind == TokenKind::Symbol('{'))?
+    } else {
+        located.end
+    };
+    let token = &tokens[anchor];
+    let next = tokens.get(anchor + 1);
+    comments
+        .iter()
+        .find(|c| {
+            c.line == token.line && !is_before(c, token) && next.is_none_or(|n| is_before(c, n))
+        })
+        .map(|c| c.text.clone())
+}
@@ -0,0 +1,487 @@
+//! Native `.proto` source parser.
+//!
+//! Builds the same `FileDescriptorSet` that `buf build -o -` would produce,
+//! so Coral can visualize a proto tree without `buf` or `protoc` installed.
+//! Imports are looked up in the proto path, then in each include root, and
+//! finally in the bundled well-known types (`google/protobuf/*.proto`).
+
+mod grammar;
+mod lexer;
+mod locations;
+mod resolve;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use prost_types::{FileDescriptorProto, FileDescriptorSet};
+
+use crate::error::{CoralError, Result};
+
+pub use grammar::parse_file;
+
+/// Well-known type sources bundled into the binary.
+const WELL_KNOWN_TYPES: &[(&str, &str)] = &[
+    (
+        "google/protobuf/any.proto",
+        include_str!("include/google/protobuf/any.proto"),
+    ),
+    (
+        "google/protobuf/api.proto",
+        include_str!("include/google/protobuf/api.proto"),
+    ),
+    (
+        "google/protobuf/descriptor.proto",
+        include_str!("include/google/protobuf/descriptor.proto"),
+    ),
+    (
+        "google/protobuf/duration.proto",
+        include_str!("include/google/protobuf/duration.proto"),
+    ),
+    (
+        "google/protobuf/empty.proto",
+        include_str!("include/google/protobuf/empty.proto"),
+    ),
+    (
+        "google/protobuf/field_mask.proto",
+        include_str!("include/google/protobuf/field_mask.proto"),
+    ),
+    (
+        "google/protobuf/source_context.proto",
+        include_str!("include/google/protobuf/source_context.proto"),
+    ),
+    (
+        "google/protobuf/struct.proto",
+        include_str!("include/google/protobuf/struct.proto"),
+    ),
+    (
+        "google/protobuf/timestamp.proto",
+        include_str!("include/google/protobuf/timestamp.proto"),
+    ),
+    (
+        "google/protobuf/type.proto",
+        include_str!("include/google/protobuf/type.proto"),
+    ),
+    (
+        "google/protobuf/wrappers.proto",
+        include_str!("include/google/protobuf/wrappers.proto"),
+    ),
+];
+
+/// Parse every `.proto` file under `proto_path`, resolving imports against
+/// `proto_path` and then `includes`.
+///
+/// Files are returned in dependency order (imports before importers),
+/// including any imported files found outside `proto_path`.
+pub fn compile(proto_path: &Path, includes: &[PathBuf]) -> Result<FileDescriptorSet> {
+    let mut roots = Vec::new();
+    collect_proto_files(proto_path, proto_path, &mut roots)?;
+    roots.sort();
+
+    if roots.is_empty() {
+        return Err(CoralError::NoProtoFiles);
+    }
+
+    let search_paths: Vec<&Path> = std::iter::once(proto_path)
+        .chain(includes.iter().map(PathBuf::as_path))
+        .collect();
+
+    compile_with(&roots, |name| {
+        for dir in &search_paths {
+            let path = dir.join(name);
+            if path.is_file() {
+                return std::fs::read_to_string(path).map(Some);
+            }
+        }
+        Ok(None)
+    })
+}
+
+/// Compile `roots` using `load` to fetch source text by import name.
+///
+/// `load` returns `Ok(None)` when a file does not exist, in which case the
+/// bundled well-known types are consulted.
+pub fn compile_with<F>(roots: &[String], load: F) -> Result<FileDescriptorSet>
+where
+    F: Fn(&str) -> std::io::Result<Option<String>>,
+{
+    let mut loader = Loader {
+        load,
+        files: Vec::new(),
+        loaded: HashSet::new(),
+        in_progress: Vec::new(),
+    };
+
+    for root in roots {
+        loader.load_file(root, None)?;
+    }
+
+    let mut files = loader.files;
+    resolve::resolve_types(&mut files)?;
+    Ok(FileDescriptorSet { file: files })
+}
+
+struct Loader<F> {
+    load: F,
+    files: Vec<FileDescriptorProto>,
+    loaded: HashSet<String>,
+    in_progress: Vec<String>,
+}
+
+impl<F> Loader<F>
+where
+    F: Fn(&str) -> std::io::Result<Option<String>>,
+{
+    fn load_file(&mut self, name: &str, importer: Option<&str>) -> Result<()> {
+        if self.loaded.contains(name) {
+            return Ok(());
+        }
+        if let Some(start) = self.in_progress.iter().position(|f| f == name) {
+            let mut cycle = self.in_progress[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(CoralError::ImportCycle {
+                cycle: cycle.join(" -> "),
+            });
+        }
+
+        let source = match (self.load)(name)? {
+            Some(source) => source,
+            None => well_known_source(name).map(str::to_string).ok_or_else(|| {
+                CoralError::ImportNotFound {
+                    import: name.to_string(),
+                    importer: importer.unwrap_or("<command line>").to_string(),
+                }
+            })?,
+        };
+
+        let file = parse_file(name, &source)?;
+
+        self.in_progress.push(name.to_string());
+        for dependency in &file.dependency {
+            self.load_file(dependency, Some(name))?;
+        }
+        self.in_progress.pop();
+
+        self.loaded.insert(name.to_string());
+        self.files.push(file);
+        Ok(())
+    }
+}
+
+fn well_known_source(name: &str) -> Option<&'static str> {
+    WELL_KNOWN_TYPES
+        .iter()
+        .find(|(path, _)| *path == name)
+        .map(|(_, source)| *source)
+}
+
+/// Recursively collect `.proto` files as `/`-separated paths relative to `root`.
+/// Symlinked directories are not followed, so links back into the tree cannot
+/// recurse forever.
+fn collect_proto_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_proto_files(root, &path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "proto")
+            && let Ok(relative) = path.strip_prefix(root)
+        {
+            let name: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            out.push(name.join("/"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use prost_types::field_descriptor_proto::{Label, Type};
+
+    use super::*;
+
+    fn compile_sources(sources: &[(&str, &str)]) -> Result<FileDescriptorSet> {
+        let map: HashMap<String, String> = sources
+            .iter()
+            .map(|(name, source)| (name.to_string(), source.to_string()))
+            .collect();
+        let roots: Vec<String> = sources.iter().map(|(name, _)| name.to_string()).collect();
+        compile_with(&roots, |name| Ok(map.get(name).cloned()))
+    }
+
+    #[test]
+    fn test_compile_resolves_cross_file_types() {
+        let fds = compile_sources(&[
+            (
+                "order/v1/order.proto",
+                r#"
+                syntax = "proto3";
+                package order.v1;
+                import "common/v1/money.proto";
+                import "google/protobuf/timestamp.proto";
+
+                service OrderService {
+                  rpc GetOrder(GetOrderRequest) returns (Order);
+                }
+                message GetOrderRequest { string id = 1; }
+                message Order {
+                  common.v1.Money total = 1;
+                  google.protobuf.Timestamp created_at = 2;
+                  Status status = 3;
+                  enum Status { STATUS_UNSPECIFIED = 0; }
+                }
+                "#,
+            ),
+            (
+                "common/v1/money.proto",
+                r#"
+                syntax = "proto3";
+                package common.v1;
+                message Money { string currency_code = 1; int64 units = 2; }
+                "#,
+            ),
+        ])
+        .expect("compile");
+
+        // Dependencies come before the files that import them
+        let names: Vec<&str> = fds.file.iter().map(|f| f.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "common/v1/money.proto",
+                "google/protobuf/timestamp.proto",
+                "order/v1/order.proto",
+            ]
+        );
+
+        let order_file = &fds.file[2];
+        let order = &order_file.message_type[1];
+        assert_eq!(order.field[0].type_name(), ".common.v1.Money");
+        assert_eq!(order.field[0].r#type(), Type::Message);
+        assert_eq!(order.field[1].type_name(), ".google.protobuf.Timestamp");
+        assert_eq!(order.field[1].json_name(), "createdAt");
+        assert_eq!(order.field[2].type_name(), ".order.v1.Order.Status");
+        assert_eq!(order.field[2].r#type(), Type::Enum);
+
+        let method = &order_file.service[0].method[0];
+        assert_eq!(method.input_type(), ".order.v1.GetOrderRequest");
+        assert_eq!(method.output_type(), ".order.v1.Order");
+    }
+
+    #[test]
+    fn test_compile_map_oneof_and_optional() {
+        let fds = compile_sources(&[(
+            "shop.proto",
+            r#"
+            syntax = "proto3";
+            package shop;
+            message Cart {
+              map<string, Item> items = 1;
+              optional string coupon = 2;
+              oneof payment {
+                string card = 3;
+                string wallet = 4;
+              }
+            }
+            message Item {}
+            "#,
+        )])
+        .expect("compile");
+
+        let cart = &fds.file[0].message_type[0];
+        let entry = &cart.nested_type[0];
+        assert_eq!(entry.name(), "ItemsEntry");
+        assert_eq!(entry.options.as_ref().and_then(|o| o.map_entry), Some(true));
+        assert_eq!(entry.field[1].type_name(), ".shop.Item");
+        assert_eq!(cart.field[0].type_name(), ".shop.Cart.ItemsEntry");
+        assert_eq!(cart.field[0].label(), Label::Repeated);
+
+        // Real oneof first, synthetic oneof for `optional` last
+        assert_eq!(cart.oneof_decl[0].name(), "payment");
+        assert_eq!(cart.oneof_decl[1].name(), "_coupon");
+        assert_eq!(cart.field[1].proto3_optional, Some(true));
+        assert_eq!(cart.field[1].oneof_index, Some(1));
+        assert_eq!(cart.field[2].oneof_index, Some(0));
+    }
+
+    #[test]
+    fn test_compile_streaming_methods() {
+        let fds = compile_sources(&[(
+            "chat.proto",
+            r#"
+            syntax = "proto3";
+            package chat;
+            service Chat {
+              rpc Talk(stream Msg) returns (stream Msg) {}
+              rpc Watch(Msg) returns (stream Msg);
+            }
+            message Msg {}
+            "#,
+        )])
+        .expect("compile");
+
+        let methods = &fds.file[0].service[0].method;
+        assert_eq!(methods[0].client_streaming, Some(true));
+        assert_eq!(methods[0].server_streaming, Some(true));
+        assert_eq!(methods[1].client_streaming, None);
+        assert_eq!(methods[1].server_streaming, Some(true));
+    }
+
+    #[test]
+    fn test_compile_records_comments_and_spans() {
+        let fds = compile_sources(&[(
+            "doc.proto",
+            r#"syntax = "proto3";
+package doc;
+
+// Detached, separated by a blank line.
+
+// A customer order.
+// Second line.
+message Order {
+  string id = 1; // Opaque ID.
+
+  /* Current status. */
+  Status status = 2;
+}
+
+enum Status {
+  // Not set.
+  STATUS_UNSPECIFIED = 0;
+}
+
+service Orders {
+  // Fetch one order.
+  rpc Get(Order) returns (Order);
+}
+"#,
+        )])
+        .expect("compile");
+
+        let info = fds.file[0].source_code_info.as_ref().expect("source info");
+        let location = |path: &[i32]| {
+            info.location
+                .iter()
+                .find(|l| l.path == path)
+                .unwrap_or_else(|| panic!("no location for {path:?}"))
+        };
+
+        let order = location(&[4, 0]);
+        assert_eq!(
+            order.leading_comments(),
+            " A customer order.\n Second line.\n"
+        );
+        assert_eq!(order.span, vec![7, 0, 12, 1]);
+        assert_eq!(location(&[4, 0, 2, 0]).trailing_comments(), " Opaque ID.\n");
+        assert_eq!(location(&[4, 0, 2, 0]).span, vec![8, 2, 16]);
+        assert_eq!(
+            location(&[4, 0, 2, 1]).leading_comments(),
+            " Current status. "
+        );
+        assert_eq!(location(&[5, 0, 2, 0]).leading_comments(), " Not set.\n");
+        assert_eq!(
+            location(&[6, 0, 2, 0]).leading_comments(),
+            " Fetch one order.\n"
+        );
+        assert_eq!(location(&[6, 0]).leading_comments, None);
+    }
+
+    #[test]
+    fn test_compile_missing_import() {
+        let result =
+            compile_sources(&[("a.proto", r#"syntax = "proto3"; import "missing.proto";"#)]);
+        assert!(matches!(
+            result,
+            Err(CoralError::ImportNotFound { import, importer })
+                if import == "missing.proto" && importer == "a.proto"
+        ));
+    }
+
+    #[test]
+    fn test_compile_import_cycle() {
+        let result = compile_sources(&[
+            ("a.proto", r#"syntax = "proto3"; import "b.proto";"#),
+            ("b.proto", r#"syntax = "proto3"; import "a.proto";"#),
+        ]);
+        assert!(matches!(result, Err(CoralError::ImportCycle { .. })));
+    }
+
+    #[test]
+    fn test_compile_unresolved_type() {
+        let result = compile_sources(&[(
+            "a.proto",
+            r#"syntax = "proto3"; package a; message A { Missing m = 1; }"#,
+        )]);
+        assert!(matches!(
+            result,
+            Err(CoralError::UnresolvedType { type_name, .. }) if type_name == "Missing"
+        ));
+    }
+
+    #[test]
+    fn test_compile_inner_scope_shadows_compound_name() {
+        // `pkg` binds to the nested message first, which has no `Inner`, and
+        // protoc does not fall back to the package of the same name
+        let result = compile_sources(&[(
+            "a.proto",
+            r#"syntax = "proto3";
+            package pkg;
+            message Inner {}
+            message Outer {
+              message pkg {}
+              pkg.Inner m = 1;
+            }"#,
+        )]);
+        assert!(matches!(
+            result,
+            Err(CoralError::UnresolvedType { type_name, .. }) if type_name == "pkg.Inner"
+        ));
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let result = compile_sources(&[("a.proto", "syntax = \"proto3\";\nmessage {}")]);
+        assert!(matches!(
+            result,
+            Err(CoralError::ProtoSyntax {
+                line: 2,
+                column: 9,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_compile_sandbox() {
+        let proto_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("sandbox/proto");
+        let fds = compile(&proto_path, &[]).expect("compile sandbox");
+
+        assert!(fds.file.iter().any(|f| f.name() == "order/v1/order.proto"));
+        assert!(
+            fds.file
+                .iter()
+                .any(|f| f.name() == "google/protobuf/timestamp.proto")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_skips_symlinked_dirs() {
+        let root = std::env::temp_dir().join(format!("coral-symlink-{}", std::process::id()));
+        let dir = root.join("a");
+        std::fs::create_dir_all(&dir).expect("create dir");
+        std::fs::write(dir.join("a.proto"), "syntax = \"proto3\";").expect("write proto");
+        std::os::unix::fs::symlink(&root, dir.join("loop")).expect("symlink");
+
+        let mut files = Vec::new();
+        let result = collect_proto_files(&root, &root, &mut files);
+        std::fs::remove_dir_all(&root).expect("cleanup");
+
+        result.expect("collect");
+        assert_eq!(files, vec!["a/a.proto"]);
+    }
+}
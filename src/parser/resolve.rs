@@ -0,0 +1,184 @@
+//! Resolves type references in parsed files to fully-qualified names.
+
+use std::collections::HashMap;
+
+use prost_types::field_descriptor_proto::Type;
+use prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorProto};
+
+use crate::error::{CoralError, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Symbol {
+    Package,
+    Message,
+    Enum,
+    Service,
+}
+
+/// Rewrite every `type_name`, `extendee`, `input_type` and `output_type`
+/// to its fully-qualified form (`.pkg.Type`), filling in field types.
+pub fn resolve_types(files: &mut [FileDescriptorProto]) -> Result<()> {
+    let symbols = SymbolTable::build(files);
+
+    for file in files.iter_mut() {
+        let file_name = file.name.clone().unwrap_or_default();
+        let scope = file
+            .package
+            .as_deref()
+            .map(|p| format!(".{p}"))
+            .unwrap_or_default();
+
+        for message in &mut file.message_type {
+            symbols.resolve_message(message, &scope, &file_name)?;
+        }
+        for extension in &mut file.extension {
+            symbols.resolve_field(extension, &scope, &file_name)?;
+        }
+        for service in &mut file.service {
+            for method in &mut service.method {
+                for type_name in [&mut method.input_type, &mut method.output_type]
+                    .into_iter()
+                    .flatten()
+                {
+                    *type_name = symbols.lookup_type(type_name, &scope, &file_name, false)?.0;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct SymbolTable {
+    symbols: HashMap<String, Symbol>,
+}
+
+impl SymbolTable {
+    fn build(files: &[FileDescriptorProto]) -> Self {
+        let mut symbols = HashMap::new();
+
+        for file in files {
+            let package = file.package.as_deref().unwrap_or("");
+            let mut scope = String::new();
+            for segment in package.split('.').filter(|s| !s.is_empty()) {
+                scope = format!("{scope}.{segment}");
+                symbols.insert(scope.clone(), Symbol::Package);
+            }
+
+            for message in &file.message_type {
+                Self::register_message(&mut symbols, message, &scope);
+            }
+            for enum_type in &file.enum_type {
+                symbols.insert(format!("{scope}.{}", enum_type.name()), Symbol::Enum);
+            }
+            for service in &file.service {
+                symbols.insert(format!("{scope}.{}", service.name()), Symbol::Service);
+            }
+        }
+
+        Self { symbols }
+    }
+
+    fn register_message(
+        symbols: &mut HashMap<String, Symbol>,
+        message: &DescriptorProto,
+        scope: &str,
+    ) {
+        let fq_name = format!("{scope}.{}", message.name());
+        for nested in &message.nested_type {
+            Self::register_message(symbols, nested, &fq_name);
+        }
+        for enum_type in &message.enum_type {
+            symbols.insert(format!("{fq_name}.{}", enum_type.name()), Symbol::Enum);
+        }
+        symbols.insert(fq_name, Symbol::Message);
+    }
+
+    fn resolve_message(
+        &self,
+        message: &mut DescriptorProto,
+        scope: &str,
+        file_name: &str,
+    ) -> Result<()> {
+        let fq_name = format!("{scope}.{}", message.name());
+        for field in message.field.iter_mut().chain(message.extension.iter_mut()) {
+            self.resolve_field(field, &fq_name, file_name)?;
+        }
+        for nested in &mut message.nested_type {
+            self.resolve_message(nested, &fq_name, file_name)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_field(
+        &self,
+        field: &mut FieldDescriptorProto,
+        scope: &str,
+        file_name: &str,
+    ) -> Result<()> {
+        if let Some(extendee) = &mut field.extendee {
+            *extendee = self.lookup_type(extendee, scope, file_name, false)?.0;
+        }
+
+        if let Some(type_name) = &mut field.type_name {
+            let (resolved, symbol) = self.lookup_type(type_name, scope, file_name, true)?;
+            *type_name = resolved;
+            if field.r#type.is_none() {
+                field.r#type = Some(match symbol {
+                    Symbol::Enum => Type::Enum as i32,
+                    _ => Type::Message as i32,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// C++-style scoping: the first name component is searched from the
+    /// innermost scope outward, and the full name is only tried under the
+    /// first match; like protoc, a compound name that is missing there is not
+    /// looked up in outer scopes.
+    fn lookup_type(
+        &self,
+        name: &str,
+        scope: &str,
+        file_name: &str,
+        allow_enum: bool,
+    ) -> Result<(String, Symbol)> {
+        let is_type =
+            |symbol: Symbol| symbol == Symbol::Message || (allow_enum && symbol == Symbol::Enum);
+        let unresolved = || CoralError::UnresolvedType {
+            file: file_name.to_string(),
+            type_name: name.to_string(),
+        };
+
+        if name.starts_with('.') {
+            return match self.symbols.get(name) {
+                Some(symbol) if is_type(*symbol) => Ok((name.to_string(), *symbol)),
+                _ => Err(unresolved()),
+            };
+        }
+
+        let first = name.split('.').next().unwrap_or(name);
+        let mut current = scope;
+        loop {
+            if let Some(found) = self.symbols.get(&format!("{current}.{first}")) {
+                if first == name {
+                    // A package or service of the same name does not shadow outer types
+                    if is_type(*found) {
+                        return Ok((format!("{current}.{name}"), *found));
+                    }
+                } else {
+                    let candidate = format!("{current}.{name}");
+                    return match self.symbols.get(&candidate) {
+                        Some(symbol) if is_type(*symbol) => Ok((candidate, *symbol)),
+                        _ => Err(unresolved()),
+                    };
+                }
+            }
+            if current.is_empty() {
+                return Err(unresolved());
+            }
+            current = &current[..current.rfind('.').unwrap_or(0)];
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! protoc / buf plugin mode (`protoc-gen-coral`).
+//!
+//! Reads a `CodeGeneratorRequest`, analyzes the files listed in
+//! `file_to_generate` (everything else in `proto_file` is treated as an
+//! import), and answers with a `CodeGeneratorResponse` holding the
+//! requested outputs.
+//!
+//! The plugin parameter is a comma-separated list of outputs, each
+//! optionally renamed with `=<file name>`:
+//!
+//! ```text
+//! buf.gen.yaml:  opt: json=deps/graph.json,markdown
+//! protoc:        --coral_out=markdown=README.deps.md:gen
+//! ```
+//!
+//! Without a parameter both `graph.json` and `coral.md` are generated.
+
+use std::collections::{HashMap, HashSet};
+
+use prost::Message;
+use prost_types::FileDescriptorSet;
+use prost_types::compiler::code_generator_response::{Feature, File};
+use prost_types::compiler::{CodeGeneratorRequest, CodeGeneratorResponse};
+
+use crate::analyzer::Analyzer;
+use crate::decoder::{Image, ImageFileInfo};
+use crate::domain::GraphModel;
+use crate::error::Result;
+use crate::features::{self, EDITION_2024, EDITION_PROTO2, FileFeatures};
+use crate::reporter::MarkdownReporter;
+
+/// `CodeGeneratorResponse.Feature.FEATURE_SUPPORTS_EDITIONS`, which
+/// prost-types does not define.
+const FEATURE_SUPPORTS_EDITIONS: u64 = 2;
+
+/// Editions range accepted from protoc / buf; sent in
+/// `CodeGeneratorResponse.minimum_edition` / `maximum_edition`.
+const MINIMUM_EDITION: i32 = EDITION_PROTO2;
+const MAXIMUM_EDITION: i32 = EDITION_2024;
+
+/// A generated artifact that can be selected by the plugin parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Output {
+    /// Pretty-printed `GraphModel` JSON.
+    Json,
+    /// Report from [`MarkdownReporter`].
+    Markdown,
+}
+
+impl Output {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "markdown" | "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    fn default_file_name(self) -> &'static str {
+        match self {
+            Self::Json => "graph.json",
+            Self::Markdown => "coral.md",
+        }
+    }
+
+    fn render(self, model: &GraphModel) -> serde_json::Result<String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(model),
+            Self::Markdown => Ok(MarkdownReporter::generate(model)),
+        }
+    }
+}
+
+/// Parse the plugin parameter into `(output, file name)` pairs.
+pub fn parse_parameter(parameter: &str) -> std::result::Result<Vec<(Output, String)>, String> {
+    let mut outputs = Vec::new();
+    for option in parameter
+        .split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+    {
+        let (name, file_name) = match option.split_once('=') {
+            Some((name, file_name)) => (name, Some(file_name)),
+            None => (option, None),
+        };
+        let output = Output::parse(name).ok_or_else(|| {
+            format!("unknown coral plugin option \"{name}\" (expected json or markdown)")
+        })?;
+        let file_name = file_name.unwrap_or(output.default_file_name());
+        if file_name.is_empty() {
+            return Err(format!(
+                "empty file name for coral plugin option \"{name}\""
+            ));
+        }
+        outputs.push((output, file_name.to_string()));
+    }
+
+    if outputs.is_empty() {
+        outputs = [Output::Json, Output::Markdown]
+            .into_iter()
+            .map(|o| (o, o.default_file_name().to_string()))
+            .collect();
+    }
+    Ok(outputs)
+}
+
+/// Handle one plugin invocation. Failures are reported through
+/// `CodeGeneratorResponse.error`, as the plugin protocol requires.
+///
+/// Editions features are not part of a decoded `CodeGeneratorRequest`;
+/// use [`run`] on the raw request to take them into account.
+#[must_use]
+pub fn generate(request: CodeGeneratorRequest) -> CodeGeneratorResponse {
+    respond(request, HashMap::new())
+}
+
+/// Decode a serialized `CodeGeneratorRequest` and encode the response.
+pub fn run(input: &[u8]) -> Result<Vec<u8>> {
+    let request = CodeGeneratorRequest::decode(input)?;
+    let features = features::decode_generator_request(input)?;
+    let mut output = respond(request, features).encode_to_vec();
+    encode_edition_range(&mut output);
+    Ok(output)
+}
+
+/// Append `minimum_edition` (3) and `maximum_edition` (4), which
+/// prost-types' `CodeGeneratorResponse` has no fields for.
+fn encode_edition_range(output: &mut Vec<u8>) {
+    prost::encoding::int32::encode(3, &MINIMUM_EDITION, output);
+    prost::encoding::int32::encode(4, &MAXIMUM_EDITION, output);
+}
+
+fn respond(
+    request: CodeGeneratorRequest,
+    features: HashMap<String, FileFeatures>,
+) -> CodeGeneratorResponse {
+    let mut response = CodeGeneratorResponse {
+        supported_features: Some(Feature::Proto3Optional as u64 | FEATURE_SUPPORTS_EDITIONS),
+        ..Default::default()
+    };
+
+    let outputs = match parse_parameter(request.parameter()) {
+        Ok(outputs) => outputs,
+        Err(error) => {
+            response.error = Some(error);
+            return response;
+        }
+    };
+
+    let model = Analyzer::default().analyze_image(&request_image(request, features));
+    for (output, file_name) in outputs {
+        match output.render(&model) {
+            Ok(content) => response.file.push(File {
+                name: Some(file_name),
+                content: Some(content),
+                ..Default::default()
+            }),
+            Err(error) => {
+                response.error = Some(format!("failed to render {file_name}: {error}"));
+                return response;
+            }
+        }
+    }
+    response
+}
+
+/// Files outside `file_to_generate` become imports, so only the requested
+/// files produce nodes while their dependencies show up as external.
+fn request_image(request: CodeGeneratorRequest, features: HashMap<String, FileFeatures>) -> Image {
+    let to_generate: HashSet<&str> = request
+        .file_to_generate
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let files = request
+        .proto_file
+        .iter()
+        .map(|file| {
+            let info = ImageFileInfo {
+                is_import: !to_generate.contains(file.name()),
+                ..Default::default()
+            };
+            (file.name().to_string(), info)
+        })
+        .collect();
+
+    Image {
+        descriptors: FileDescriptorSet {
+            file: request.proto_file,
+        },
+        files,
+        features,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, MethodDescriptorProto,
+        ServiceDescriptorProto,
+    };
+
+    use super::*;
+    use crate::domain::NodeType;
+
+    fn request(parameter: Option<&str>) -> CodeGeneratorRequest {
+        let common = FileDescriptorProto {
+            name: Some("common/v1/money.proto".to_string()),
+            package: Some("common.v1".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Money".to_string()),
+                ..Default::default()
+            }],
+            service: vec![ServiceDescriptorProto {
+                name: Some("CurrencyService".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let order = FileDescriptorProto {
+            name: Some("order/v1/order.proto".to_string()),
+            package: Some("order.v1".to_string()),
+            dependency: vec!["common/v1/money.proto".to_string()],
+            message_type: vec![DescriptorProto {
+                name: Some("Order".to_string()),
+                field: vec![FieldDescriptorProto {
+                    name: Some("total".to_string()),
+                    number: Some(1),
+                    r#type: Some(11),
+                    type_name: Some(".common.v1.Money".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            service: vec![ServiceDescriptorProto {
+                name: Some("OrderService".to_string()),
+                method: vec![MethodDescriptorProto {
+                    name: Some("GetOrder".to_string()),
+                    input_type: Some(".order.v1.Order".to_string()),
+                    output_type: Some(".order.v1.Order".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        CodeGeneratorRequest {
+            file_to_generate: vec!["order/v1/order.proto".to_string()],
+            parameter: parameter.map(str::to_string),
+            proto_file: vec![common, order],
+            ..Default::default()
+        }
+    }
+
+    fn file_names(response: &CodeGeneratorResponse) -> Vec<&str> {
+        response.file.iter().map(|f| f.name()).collect()
+    }
+
+    #[test]
+    fn test_default_outputs() {
+        let response = generate(request(None));
+        assert_eq!(response.error, None);
+        assert_eq!(file_names(&response), vec!["graph.json", "coral.md"]);
+        let features = response.supported_features();
+        assert_ne!(features & Feature::Proto3Optional as u64, 0);
+        assert_ne!(features & FEATURE_SUPPORTS_EDITIONS, 0);
+    }
+
+    #[test]
+    fn test_restricted_to_file_to_generate() {
+        let response = generate(request(Some("json")));
+        let model: GraphModel =
+            serde_json::from_str(response.file[0].content()).expect("graph json");
+
+        let ids: Vec<(&str, &NodeType)> = model
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), &n.node_type))
+            .collect();
+        assert!(ids.contains(&("order.v1.OrderService", &NodeType::Service)));
+        assert!(ids.contains(&("order.v1.Order", &NodeType::Message)));
+        assert!(ids.contains(&("common.v1.Money", &NodeType::External)));
+        assert!(!ids.iter().any(|(id, _)| *id == "common.v1.CurrencyService"));
+    }
+
+    #[test]
+    fn test_parameter_selects_and_renames_outputs() {
+        let response = generate(request(Some("markdown=docs/deps.md")));
+        assert_eq!(file_names(&response), vec!["docs/deps.md"]);
+        assert!(response.file[0].content().contains("OrderService"));
+    }
+
+    #[test]
+    fn test_unknown_parameter_is_reported() {
+        let response = generate(request(Some("json,svg")));
+        assert!(response.file.is_empty());
+        assert!(response.error().contains("\"svg\""));
+    }
+
+    #[test]
+    fn test_run_round_trip() {
+        let bytes = run(&request(Some("json")).encode_to_vec()).expect("run");
+        let response = CodeGeneratorResponse::decode(bytes.as_slice()).expect("decode");
+        assert_eq!(file_names(&response), vec!["graph.json"]);
+
+        // minimum_edition / maximum_edition, unknown to prost-types
+        let mut tail = Vec::new();
+        encode_edition_range(&mut tail);
+        assert!(bytes.ends_with(&tail));
+        let mut buf = &tail[..];
+        let mut range = Vec::new();
+        while !buf.is_empty() {
+            let (tag, _) = prost::encoding::decode_key(&mut buf).expect("key");
+            let value = prost::encoding::decode_varint(&mut buf).expect("value");
+            range.push((tag, value as i32));
+        }
+        assert_eq!(range, vec![(3, EDITION_PROTO2), (4, EDITION_2024)]);
+    }
+}
@@ -0,0 +1,377 @@
+//! Fetching descriptors from a running server via gRPC server reflection.
+//!
+//! Both `grpc.reflection.v1` and the older `grpc.reflection.v1alpha`
+//! protocols are supported; their messages are identical on the wire, so
+//! v1alpha is only a different method path that is tried when the server
+//! does not implement v1.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use prost::Message;
+use prost_types::{FileDescriptorProto, FileDescriptorSet};
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::codec::Streaming;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Status};
+use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::v1::{ServerReflectionRequest, ServerReflectionResponse};
+
+use crate::decoder::Image;
+use crate::error::{CoralError, Result};
+use crate::features::{self, FileFeatures};
+
+const V1_PATH: &str = "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo";
+const V1ALPHA_PATH: &str = "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo";
+
+/// Services that only describe the reflection protocol itself.
+const REFLECTION_SERVICE_PREFIX: &str = "grpc.reflection.";
+
+/// Connect to `endpoint` (e.g. `http://localhost:8080`) and download every
+/// file that defines a listed service, plus all of its transitive imports.
+///
+/// Files are returned in dependency order (imports before importers).
+pub async fn fetch(endpoint: &str) -> Result<Image> {
+    let error = |message: String| CoralError::Reflection {
+        endpoint: endpoint.to_string(),
+        message,
+    };
+
+    let channel = Endpoint::from_shared(endpoint.to_string())
+        .map_err(|e| error(e.to_string()))?
+        .connect()
+        .await
+        .map_err(|e| error(format!("connection failed: {e}")))?;
+
+    let (mut session, services) = match Session::open(channel.clone(), V1_PATH).await {
+        Err(status) if status.code() == Code::Unimplemented => {
+            log::debug!("{endpoint} does not implement reflection v1, trying v1alpha");
+            Session::open(channel, V1ALPHA_PATH).await
+        }
+        result => result,
+    }
+    .map_err(|status| error(status.message().to_string()))?;
+
+    let mut files: BTreeMap<String, FileDescriptorProto> = BTreeMap::new();
+    let mut features: HashMap<String, FileFeatures> = HashMap::new();
+    for service in services
+        .iter()
+        .filter(|s| !s.starts_with(REFLECTION_SERVICE_PREFIX))
+    {
+        let found = session
+            .files(MessageRequest::FileContainingSymbol(service.clone()))
+            .await
+            .map_err(|e| error(format!("service {service}: {e}")))?;
+        for (file, file_features) in found {
+            add_file(&mut files, &mut features, file, file_features);
+        }
+    }
+
+    // Servers may answer with only the requested file, so walk imports
+    // until every dependency has been fetched.
+    loop {
+        let missing: Vec<String> = files
+            .values()
+            .flat_map(|f| &f.dependency)
+            .filter(|d| !files.contains_key(*d))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        if missing.is_empty() {
+            break;
+        }
+        for name in missing {
+            let found = session
+                .files(MessageRequest::FileByFilename(name.clone()))
+                .await
+                .map_err(|e| error(format!("file {name}: {e}")))?;
+            if !found.iter().any(|(f, _)| f.name() == name) {
+                return Err(error(format!("server did not return file {name}")));
+            }
+            for (file, file_features) in found {
+                add_file(&mut files, &mut features, file, file_features);
+            }
+        }
+    }
+
+    if files.is_empty() {
+        return Err(CoralError::NoProtoFiles);
+    }
+    Ok(Image {
+        descriptors: FileDescriptorSet {
+            file: dependency_order(files),
+        },
+        features,
+        ..Default::default()
+    })
+}
+
+fn add_file(
+    files: &mut BTreeMap<String, FileDescriptorProto>,
+    features: &mut HashMap<String, FileFeatures>,
+    file: FileDescriptorProto,
+    file_features: FileFeatures,
+) {
+    let name = file.name().to_string();
+    if files.contains_key(&name) {
+        return;
+    }
+    if file_features != FileFeatures::default() {
+        features.insert(name.clone(), file_features);
+    }
+    files.insert(name, file);
+}
+
+/// One bidirectional `ServerReflectionInfo` stream.
+struct Session {
+    requests: mpsc::Sender<ServerReflectionRequest>,
+    responses: Streaming<ServerReflectionResponse>,
+}
+
+impl Session {
+    /// Open the stream at `path` and list the server's services. Listing
+    /// first is what surfaces `Unimplemented` for an unsupported version.
+    async fn open(
+        channel: Channel,
+        path: &'static str,
+    ) -> std::result::Result<(Self, Vec<String>), Status> {
+        let (requests, rx) = mpsc::channel(4);
+        let mut client = tonic::client::Grpc::new(channel);
+        client
+            .ready()
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))?;
+        requests
+            .send(request(MessageRequest::ListServices(String::new())))
+            .await
+            .map_err(|_| closed())?;
+        let responses = client
+            .streaming(
+                tonic::Request::new(ReceiverStream::new(rx)),
+                PathAndQuery::from_static(path),
+                tonic_prost::ProstCodec::default(),
+            )
+            .await?
+            .into_inner();
+
+        let mut session = Self {
+            requests,
+            responses,
+        };
+        let services = match session.receive().await? {
+            MessageResponse::ListServicesResponse(list) => {
+                list.service.into_iter().map(|s| s.name).collect()
+            }
+            other => return Err(unexpected(&other)),
+        };
+        Ok((session, services))
+    }
+
+    /// Ask for a file by name or symbol and decode every file in the answer,
+    /// together with its editions features.
+    async fn files(
+        &mut self,
+        message: MessageRequest,
+    ) -> std::result::Result<Vec<(FileDescriptorProto, FileFeatures)>, Status> {
+        self.send(message).await?;
+        match self.receive().await? {
+            MessageResponse::FileDescriptorResponse(response) => response
+                .file_descriptor_proto
+                .iter()
+                .map(|bytes| {
+                    let file = FileDescriptorProto::decode(bytes.as_slice())
+                        .map_err(|e| Status::data_loss(e.to_string()))?;
+                    let features = features::decode_file(bytes)
+                        .map_err(|e| Status::data_loss(e.to_string()))?;
+                    Ok((file, features))
+                })
+                .collect(),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    async fn send(&self, message: MessageRequest) -> std::result::Result<(), Status> {
+        self.requests
+            .send(request(message))
+            .await
+            .map_err(|_| closed())
+    }
+
+    async fn receive(&mut self) -> std::result::Result<MessageResponse, Status> {
+        let response = self.responses.next().await.ok_or_else(closed)??;
+        match response.message_response {
+            Some(MessageResponse::ErrorResponse(error)) => Err(Status::new(
+                Code::from(error.error_code),
+                error.error_message,
+            )),
+            Some(message) => Ok(message),
+            None => Err(Status::internal("empty reflection response")),
+        }
+    }
+}
+
+fn request(message: MessageRequest) -> ServerReflectionRequest {
+    ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(message),
+    }
+}
+
+fn closed() -> Status {
+    Status::aborted("reflection stream closed")
+}
+
+fn unexpected(response: &MessageResponse) -> Status {
+    Status::internal(format!("unexpected reflection response: {response:?}"))
+}
+
+/// Order files so every import precedes its importers; ties keep name order.
+fn dependency_order(mut files: BTreeMap<String, FileDescriptorProto>) -> Vec<FileDescriptorProto> {
+    fn visit(
+        name: &str,
+        files: &mut BTreeMap<String, FileDescriptorProto>,
+        seen: &mut HashSet<String>,
+        out: &mut Vec<FileDescriptorProto>,
+    ) {
+        if !seen.insert(name.to_string()) {
+            return;
+        }
+        let Some(file) = files.remove(name) else {
+            return;
+        };
+        for dependency in &file.dependency {
+            visit(dependency, files, seen, out);
+        }
+        out.push(file);
+    }
+
+    let names: Vec<String> = files.keys().cloned().collect();
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(names.len());
+    for name in names {
+        visit(&name, &mut files, &mut seen, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::net::SocketAddr;
+
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::Server;
+    use tonic_reflection::server::Builder;
+
+    use super::*;
+
+    fn descriptors() -> FileDescriptorSet {
+        let sources: HashMap<&str, &str> = HashMap::from([
+            (
+                "order/v1/order.proto",
+                r#"
+                syntax = "proto3";
+                package order.v1;
+                import "common/v1/money.proto";
+                service OrderService {
+                  rpc GetOrder(GetOrderRequest) returns (Order);
+                }
+                message GetOrderRequest { string id = 1; }
+                message Order { common.v1.Money total = 1; }
+                "#,
+            ),
+            (
+                "common/v1/money.proto",
+                r#"
+                syntax = "proto3";
+                package common.v1;
+                import "google/protobuf/timestamp.proto";
+                message Money { string currency_code = 1; google.protobuf.Timestamp at = 2; }
+                "#,
+            ),
+        ]);
+        crate::parser::compile_with(&["order/v1/order.proto".to_string()], |name| {
+            Ok(sources.get(name).map(|s| s.to_string()))
+        })
+        .expect("compile")
+    }
+
+    /// Serve `descriptors` over reflection on an ephemeral port.
+    async fn spawn_server(v1alpha_only: bool) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let builder = Builder::configure().register_file_descriptor_set(descriptors());
+        let router = if v1alpha_only {
+            Server::builder().add_service(builder.build_v1alpha().expect("v1alpha"))
+        } else {
+            Server::builder().add_service(builder.build_v1().expect("v1"))
+        };
+        tokio::spawn(router.serve_with_incoming(TcpListenerStream::new(listener)));
+        addr
+    }
+
+    fn file_names(fds: &FileDescriptorSet) -> Vec<&str> {
+        fds.file.iter().map(|f| f.name()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_v1_walks_transitive_imports() {
+        let addr = spawn_server(false).await;
+        let fds = fetch(&format!("http://{addr}"))
+            .await
+            .expect("fetch")
+            .descriptors;
+
+        assert_eq!(
+            file_names(&fds),
+            vec![
+                "google/protobuf/timestamp.proto",
+                "common/v1/money.proto",
+                "order/v1/order.proto",
+            ]
+        );
+        // The reflection service's own descriptor is not part of the result
+        assert!(fds.file.iter().all(|f| !f.package().starts_with("grpc.")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_falls_back_to_v1alpha() {
+        let addr = spawn_server(true).await;
+        let image = fetch(&format!("http://{addr}")).await.expect("fetch");
+        assert_eq!(image.descriptors.file.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_connection_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        drop(listener);
+
+        let result = fetch(&format!("http://{addr}")).await;
+        assert!(matches!(result, Err(CoralError::Reflection { .. })));
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let file = |name: &str, deps: &[&str]| FileDescriptorProto {
+            name: Some(name.to_string()),
+            dependency: deps.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let files = BTreeMap::from([
+            ("a.proto".to_string(), file("a.proto", &["c.proto"])),
+            ("b.proto".to_string(), file("b.proto", &[])),
+            ("c.proto".to_string(), file("c.proto", &["b.proto"])),
+        ]);
+        let ordered: Vec<String> = dependency_order(files)
+            .into_iter()
+            .map(|f| f.name().to_string())
+            .collect();
+        assert_eq!(ordered, vec!["b.proto", "c.proto", "a.proto"]);
+    }
+}
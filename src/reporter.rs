@@ -2,25 +2,106 @@
 //!
 //! Generates detailed Markdown output from GraphModel for PR comments.
 
-use crate::domain::{GraphModel, Node, NodeDetails, NodeType};
+use crate::domain::{
+    Comments, EdgeKind, FieldInfo, FieldPresence, GraphModel, Node, NodeDetails, NodeType,
+    Reserved, Span,
+};
+
+/// Source links for definitions, built from a URL template such as
+/// `https://github.com/acme/api/blob/{sha}/proto/{path}#L{line}-L{end_line}`.
+///
+/// `{path}` is the file name as imported (relative to the proto root), and
+/// `{line}` / `{end_line}` are the 1-based first and last lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permalinks {
+    template: String,
+    sha: String,
+}
+
+impl Permalinks {
+    #[must_use]
+    pub fn new(template: impl Into<String>, sha: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            sha: sha.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn url(&self, file: &str, span: &Span) -> String {
+        self.template
+            .replace("{sha}", &self.sha)
+            .replace("{path}", file)
+            .replace("{end_line}", &span.end_line.to_string())
+            .replace("{line}", &span.start_line.to_string())
+    }
+}
 
 /// Generates Markdown reports from proto dependency graphs.
-pub struct MarkdownReporter;
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownReporter {
+    /// Link definition names to their source; names without a recorded
+    /// span stay plain text.
+    permalinks: Option<Permalinks>,
+    /// Show fields by their proto3 JSON name instead of their proto name.
+    json_names: bool,
+}
 
 impl MarkdownReporter {
     /// Generate complete Markdown report from GraphModel.
     #[must_use]
     pub fn generate(model: &GraphModel) -> String {
+        Self::default().render(model)
+    }
+
+    #[must_use]
+    pub fn with_permalinks(mut self, permalinks: Option<Permalinks>) -> Self {
+        self.permalinks = permalinks;
+        self
+    }
+
+    #[must_use]
+    pub fn with_json_names(mut self, json_names: bool) -> Self {
+        self.json_names = json_names;
+        self
+    }
+
+    /// Generate complete Markdown report with this reporter's options.
+    #[must_use]
+    pub fn render(&self, model: &GraphModel) -> String {
         let mut output = String::new();
         output.push_str(&Self::render_header());
         output.push_str(&Self::render_overview(model));
-        output.push_str(&Self::render_services_section(model));
-        output.push_str(&Self::render_messages_section(model));
-        output.push_str(&Self::render_enums_section(model));
+        output.push_str(&Self::render_packages_section(model));
+        output.push_str(&self.render_services_section(model));
+        output.push_str(&self.render_messages_section(model));
+        output.push_str(&self.render_enums_section(model));
+        output.push_str(&self.render_extensions_section(model));
+        output.push_str(&self.render_foreign_section(model));
+        output.push_str(&self.render_deprecated_section(model));
+        output.push_str(&Self::render_dependencies_section(model));
         output.push_str(&Self::render_footer());
         output
     }
 
+    /// `name` as a link to `span` in `file` when permalinks are enabled.
+    fn link(&self, name: &str, file: &str, span: Option<&Span>) -> String {
+        match (&self.permalinks, span) {
+            (Some(permalinks), Some(span)) => format!("[{name}]({})", permalinks.url(file, span)),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Linked `name`, struck through when the definition is deprecated.
+    fn name(&self, name: &str, file: &str, span: Option<&Span>, deprecated: bool) -> String {
+        let name = self.link(name, file, span);
+        if deprecated {
+            format!("~~{name}~~")
+        } else {
+            name
+        }
+    }
+
     fn render_header() -> String {
         "## 🪸 Coral Proto Dependency Analysis\n\n".to_string()
     }
@@ -29,17 +110,26 @@ impl MarkdownReporter {
         let mut services = 0;
         let mut messages = 0;
         let mut enums = 0;
+        let mut extensions = 0;
         let mut externals = 0;
+        let mut foreign = 0;
         let mut files = std::collections::HashSet::new();
 
         for node in &model.nodes {
+            files.insert(&node.file);
+            if node.foreign {
+                foreign += 1;
+                continue;
+            }
             match node.node_type {
                 NodeType::Service => services += 1,
                 NodeType::Message => messages += 1,
                 NodeType::Enum => enums += 1,
+                NodeType::Extension => extensions += 1,
                 NodeType::External => externals += 1,
+                // Listed with their service / only in file-level graphs
+                NodeType::Method | NodeType::File => {}
             }
-            files.insert(&node.file);
         }
 
         format!(
@@ -50,22 +140,69 @@ impl MarkdownReporter {
              | Services | {} |\n\
              | Messages | {} |\n\
              | Enums | {} |\n\
+             | Extensions | {} |\n\
              | External | {} |\n\
+             | Foreign | {} |\n\
              | Dependencies | {} |\n\n",
             files.len(),
             services,
             messages,
             enums,
+            extensions,
             externals,
+            foreign,
             model.edges.len()
         )
     }
 
-    fn render_services_section(model: &GraphModel) -> String {
+    /// The package tree, one row per package indented below its parent,
+    /// with counts that include subpackages. Packages holding only external
+    /// types are left out.
+    fn render_packages_section(model: &GraphModel) -> String {
+        let packages: Vec<_> = model
+            .packages
+            .iter()
+            .filter(|p| {
+                let c = &p.counts;
+                c.services + c.messages + c.enums + c.extensions > 0
+            })
+            .collect();
+
+        if packages.is_empty() {
+            return String::new();
+        }
+
+        let mut output = format!(
+            "<details>\n<summary>🗂️ Packages ({})</summary>\n\n\
+             | Package | Services | Messages | Enums | Extensions |\n\
+             |---------|----------|----------|-------|------------|\n",
+            packages.len()
+        );
+        for package in packages {
+            let name = if package.id.is_empty() {
+                "(default)"
+            } else {
+                &package.id
+            };
+            output.push_str(&format!(
+                "| {}`{}` | {} | {} | {} | {} |\n",
+                "&emsp;".repeat(package.depth()),
+                name,
+                package.counts.services,
+                package.counts.messages,
+                package.counts.enums,
+                package.counts.extensions
+            ));
+        }
+        output.push_str("\n</details>\n\n");
+        output
+    }
+
+    fn render_services_section(&self, model: &GraphModel) -> String {
         let services: Vec<_> = model
             .nodes
             .iter()
-            .filter(|n| n.node_type == NodeType::Service)
+            .filter(|n| n.node_type == NodeType::Service && !n.foreign)
             .collect();
 
         if services.is_empty() {
@@ -78,28 +215,39 @@ impl MarkdownReporter {
         );
 
         for service in services {
-            output.push_str(&Self::render_service(service));
+            output.push_str(&self.render_service(service));
         }
 
         output.push_str("</details>\n\n");
         output
     }
 
-    fn render_service(node: &Node) -> String {
-        let mut output = format!(
-            "#### {}\n**Package**: `{}` | **File**: `{}`\n\n",
-            node.label, node.package, node.file
-        );
+    fn render_service(&self, node: &Node) -> String {
+        let mut output = self.render_heading(node);
 
         if let NodeDetails::Service { methods, .. } = &node.details
             && !methods.is_empty()
         {
-            output.push_str("| Method | Input | Output |\n");
-            output.push_str("|--------|-------|--------|\n");
+            let described = methods.iter().any(|m| !m.comments.is_empty());
+            output.push_str(&Self::table_header(
+                &["Method", "Input", "Output"],
+                described,
+            ));
             for method in methods {
+                let stream = |streams: bool| if streams { "stream " } else { "" };
                 output.push_str(&format!(
-                    "| {} | {} | {} |\n",
-                    method.name, method.input_type, method.output_type
+                    "| {} | {}{} | {}{} |{}\n",
+                    self.name(
+                        &method.name,
+                        &node.file,
+                        method.span.as_ref(),
+                        method.deprecated
+                    ),
+                    stream(method.streaming.client_streams()),
+                    method.input_type,
+                    stream(method.streaming.server_streams()),
+                    method.output_type,
+                    Self::description_cell(described, &method.comments)
                 ));
             }
             output.push('\n');
@@ -108,11 +256,11 @@ impl MarkdownReporter {
         output
     }
 
-    fn render_messages_section(model: &GraphModel) -> String {
+    fn render_messages_section(&self, model: &GraphModel) -> String {
         let messages: Vec<_> = model
             .nodes
             .iter()
-            .filter(|n| n.node_type == NodeType::Message)
+            .filter(|n| n.node_type == NodeType::Message && !n.foreign)
             .collect();
 
         if messages.is_empty() {
@@ -125,41 +273,152 @@ impl MarkdownReporter {
         );
 
         for message in messages {
-            output.push_str(&Self::render_message(message));
+            output.push_str(&self.render_message(message));
         }
 
         output.push_str("</details>\n\n");
         output
     }
 
-    fn render_message(node: &Node) -> String {
-        let mut output = format!(
-            "#### {}\n**Package**: `{}` | **File**: `{}`\n\n",
-            node.label, node.package, node.file
-        );
+    fn render_message(&self, node: &Node) -> String {
+        let mut output = self.render_heading(node);
 
-        if let NodeDetails::Message { fields } = &node.details
+        if let NodeDetails::Message { fields, .. } = &node.details
             && !fields.is_empty()
         {
-            output.push_str("| # | Field | Type | Label |\n");
-            output.push_str("|---|-------|------|-------|\n");
+            let described = fields.iter().any(|f| !f.comments.is_empty());
+            output.push_str(&Self::table_header(
+                &["#", "Field", "Type", "Label"],
+                described,
+            ));
             for field in fields {
                 output.push_str(&format!(
-                    "| {} | {} | {} | {} |\n",
-                    field.number, field.name, field.type_name, field.label
+                    "| {} | {} | {} | {} |{}\n",
+                    field.number,
+                    self.name(
+                        self.field_name(field),
+                        &node.file,
+                        field.span.as_ref(),
+                        field.deprecated
+                    ),
+                    Self::field_type(field),
+                    Self::field_label(field),
+                    Self::description_cell(described, &field.comments)
                 ));
             }
             output.push('\n');
         }
+        if let NodeDetails::Message {
+            reserved,
+            extension_ranges,
+            ..
+        } = &node.details
+        {
+            output.push_str(&Self::render_reserved(reserved));
+            if !extension_ranges.is_empty() {
+                let ranges: Vec<String> =
+                    extension_ranges.iter().map(ToString::to_string).collect();
+                output.push_str(&format!("**Extensions**: {}\n\n", ranges.join(", ")));
+            }
+        }
+
+        output
+    }
+
+    /// Reserved numbers, then reserved names, on one line.
+    fn render_reserved(reserved: &Reserved) -> String {
+        if reserved.is_empty() {
+            return String::new();
+        }
+        let items: Vec<String> = reserved
+            .ranges
+            .iter()
+            .map(ToString::to_string)
+            .chain(reserved.names.iter().map(|n| format!("`{n}`")))
+            .collect();
+        format!("**Reserved**: {}\n\n", items.join(", "))
+    }
 
+    /// Definition heading, followed by its doc comment when there is one.
+    fn render_heading(&self, node: &Node) -> String {
+        let mut output = format!(
+            "#### {}{}\n**Package**: `{}` | **File**: `{}`\n\n",
+            self.name(&node.label, &node.file, node.span.as_ref(), node.deprecated),
+            if node.deprecated {
+                " ⚠️ *deprecated*"
+            } else {
+                ""
+            },
+            node.package,
+            node.file
+        );
+        if let Some(description) = node.comments.description() {
+            output.push_str(description);
+            output.push_str("\n\n");
+        }
         output
     }
 
-    fn render_enums_section(model: &GraphModel) -> String {
+    /// Header and separator rows, with a trailing Description column when
+    /// any row is documented.
+    fn table_header(columns: &[&str], described: bool) -> String {
+        let mut columns = columns.to_vec();
+        if described {
+            columns.push("Description");
+        }
+        let separators: Vec<String> = columns.iter().map(|c| "-".repeat(c.len() + 2)).collect();
+        format!("| {} |\n|{}|\n", columns.join(" | "), separators.join("|"))
+    }
+
+    /// Description cell (with its closing `|`) for tables that have the column.
+    fn description_cell(described: bool, comments: &Comments) -> String {
+        if !described {
+            return String::new();
+        }
+        let text = comments
+            .description()
+            .unwrap_or_default()
+            .replace('|', "\\|")
+            .replace('\n', "<br>");
+        format!(" {text} |")
+    }
+
+    /// Type column; inlined wrapper types read as their scalar plus `?`.
+    fn field_type(field: &FieldInfo) -> String {
+        if field.nullable {
+            format!("{}?", field.type_name)
+        } else {
+            field.type_name.clone()
+        }
+    }
+
+    /// Label column: oneof members name their group, and fields without
+    /// presence tracking have no `optional` label.
+    fn field_label(field: &FieldInfo) -> String {
+        let label = match &field.oneof {
+            Some(oneof) => format!("oneof {oneof}"),
+            None if field.presence == Some(FieldPresence::Implicit) => String::new(),
+            None => field.label.clone(),
+        };
+        match &field.default_value {
+            Some(default) => format!("{label} [default = {}]", default.replace('|', "\\|")),
+            None => label,
+        }
+    }
+
+    /// JSON name when enabled and known, proto name otherwise.
+    fn field_name<'a>(&self, field: &'a FieldInfo) -> &'a str {
+        match &field.json_name {
+            Some(json_name) if self.json_names => json_name,
+            _ => &field.name,
+        }
+    }
+
+    fn render_enums_section(&self, model: &GraphModel) -> String {
         let enums: Vec<_> = model
             .nodes
             .iter()
-            .filter(|n| n.node_type == NodeType::Enum)
+            .filter(|n| n.node_type == NodeType::Enum && !n.foreign)
             .collect();
 
         if enums.is_empty() {
@@ -172,30 +431,257 @@ impl MarkdownReporter {
         );
 
         for enum_node in enums {
-            output.push_str(&Self::render_enum(enum_node));
+            output.push_str(&self.render_enum(enum_node));
         }
 
         output.push_str("</details>\n\n");
         output
     }
 
-    fn render_enum(node: &Node) -> String {
-        let mut output = format!(
-            "#### {}\n**Package**: `{}` | **File**: `{}`\n\n",
-            node.label, node.package, node.file
-        );
+    fn render_enum(&self, node: &Node) -> String {
+        let mut output = self.render_heading(node);
 
-        if let NodeDetails::Enum { values } = &node.details
+        if let NodeDetails::Enum { values, .. } = &node.details
             && !values.is_empty()
         {
-            output.push_str("| Value | Number |\n");
-            output.push_str("|-------|--------|\n");
+            let described = values.iter().any(|v| !v.comments.is_empty());
+            output.push_str(&Self::table_header(&["Value", "Number"], described));
             for value in values {
-                output.push_str(&format!("| {} | {} |\n", value.name, value.number));
+                output.push_str(&format!(
+                    "| {} | {} |{}\n",
+                    self.name(
+                        &value.name,
+                        &node.file,
+                        value.span.as_ref(),
+                        value.deprecated
+                    ),
+                    match &value.alias_of {
+                        Some(alias_of) => format!("{} (alias of {alias_of})", value.number),
+                        None => value.number.to_string(),
+                    },
+                    Self::description_cell(described, &value.comments)
+                ));
             }
             output.push('\n');
         }
+        if let NodeDetails::Enum { reserved, .. } = &node.details {
+            output.push_str(&Self::render_reserved(reserved));
+        }
+
+        output
+    }
+
+    /// One table for all extensions, since each is a single field.
+    fn render_extensions_section(&self, model: &GraphModel) -> String {
+        let extensions: Vec<_> = model
+            .nodes
+            .iter()
+            .filter(|n| n.node_type == NodeType::Extension && !n.foreign)
+            .collect();
+
+        if extensions.is_empty() {
+            return String::new();
+        }
 
+        let mut output = format!(
+            "<details>\n<summary>🧩 Extensions ({})</summary>\n\n",
+            extensions.len()
+        );
+        output.push_str(&self.render_extension_table(&extensions));
+        output.push_str("\n</details>\n\n");
+        output
+    }
+
+    /// Extension rows, shared by our own and the foreign section.
+    fn render_extension_table(&self, extensions: &[&Node]) -> String {
+        let described = extensions.iter().any(|n| !n.comments.is_empty());
+        let mut output = Self::table_header(
+            &["Extension", "Extends", "#", "Type", "Label", "File"],
+            described,
+        );
+        for node in extensions {
+            if let NodeDetails::Extension { extendee, field } = &node.details {
+                output.push_str(&format!(
+                    "| {} | {} | {} | {} | {} | {} |{}\n",
+                    self.name(&node.label, &node.file, node.span.as_ref(), node.deprecated),
+                    extendee,
+                    field.number,
+                    Self::field_type(field),
+                    Self::field_label(field),
+                    node.file,
+                    Self::description_cell(described, &node.comments)
+                ));
+            }
+        }
+        output
+    }
+
+    /// Definitions from foreign packages, kept apart from our own.
+    fn render_foreign_section(&self, model: &GraphModel) -> String {
+        let foreign: Vec<_> = model
+            .nodes
+            .iter()
+            .filter(|n| n.foreign && n.node_type != NodeType::Method)
+            .collect();
+
+        if foreign.is_empty() {
+            return String::new();
+        }
+
+        let mut output = format!(
+            "<details>\n<summary>🌐 Foreign ({})</summary>\n\n",
+            foreign.len()
+        );
+        let (extensions, others): (Vec<&Node>, Vec<&Node>) = foreign
+            .into_iter()
+            .partition(|n| n.node_type == NodeType::Extension);
+        for node in others {
+            output.push_str(&match node.node_type {
+                NodeType::Service => self.render_service(node),
+                NodeType::Message => self.render_message(node),
+                NodeType::Enum => self.render_enum(node),
+                _ => self.render_heading(node),
+            });
+        }
+        if !extensions.is_empty() {
+            output.push_str("#### Extensions\n\n");
+            output.push_str(&self.render_extension_table(&extensions));
+            output.push('\n');
+        }
+        output.push_str("</details>\n\n");
+        output
+    }
+
+    /// Deprecated definitions and members, with the nodes that still
+    /// reference each definition.
+    fn render_deprecated_section(&self, model: &GraphModel) -> String {
+        let referencers = |id: &str| {
+            let mut sources: Vec<&str> = model
+                .edges
+                .iter()
+                .filter(|e| {
+                    e.target == id
+                        && e.labels
+                            .iter()
+                            .any(|l| !matches!(l.kind, EdgeKind::Nesting | EdgeKind::Method))
+                })
+                .map(|e| {
+                    model
+                        .find_node(&e.source)
+                        .map_or(e.source.as_str(), |n| n.label.as_str())
+                })
+                .collect();
+            sources.sort_unstable();
+            sources.dedup();
+            sources.join(", ")
+        };
+
+        let mut rows = Vec::new();
+        for node in &model.nodes {
+            // Method nodes repeat their service's methods
+            if node.deprecated && node.node_type != NodeType::Method {
+                let kind = match node.node_type {
+                    NodeType::Service => "Service",
+                    NodeType::Message => "Message",
+                    NodeType::Enum => "Enum",
+                    NodeType::Extension => "Extension",
+                    NodeType::Method | NodeType::External | NodeType::File => "",
+                };
+                rows.push((
+                    self.link(&node.label, &node.file, node.span.as_ref()),
+                    kind,
+                    node,
+                    referencers(&node.id),
+                ));
+            }
+            let member = |name: &str, span: Option<&Span>| {
+                self.link(&format!("{}.{name}", node.label), &node.file, span)
+            };
+            // Members are reached through the definition that owns them
+            let owner_referencers = || referencers(&node.id);
+            match &node.details {
+                NodeDetails::Service { methods, .. } => {
+                    rows.extend(methods.iter().filter(|m| m.deprecated).map(|m| {
+                        (
+                            member(&m.name, m.span.as_ref()),
+                            "Method",
+                            node,
+                            owner_referencers(),
+                        )
+                    }))
+                }
+                NodeDetails::Message { fields, .. } => {
+                    rows.extend(fields.iter().filter(|f| f.deprecated).map(|f| {
+                        (
+                            member(self.field_name(f), f.span.as_ref()),
+                            "Field",
+                            node,
+                            owner_referencers(),
+                        )
+                    }))
+                }
+                NodeDetails::Enum { values, .. } => {
+                    rows.extend(values.iter().filter(|v| v.deprecated).map(|v| {
+                        (
+                            member(&v.name, v.span.as_ref()),
+                            "Enum value",
+                            node,
+                            owner_referencers(),
+                        )
+                    }))
+                }
+                _ => {}
+            }
+        }
+
+        if rows.is_empty() {
+            return String::new();
+        }
+
+        let mut output = format!(
+            "<details>\n<summary>🚧 Deprecated ({})</summary>\n\n",
+            rows.len()
+        );
+        output.push_str("| Item | Kind | File | Referenced by |\n");
+        output.push_str("|------|------|------|---------------|\n");
+        for (item, kind, node, referenced_by) in rows {
+            output.push_str(&format!(
+                "| {item} | {kind} | {} | {referenced_by} |\n",
+                node.file
+            ));
+        }
+        output.push_str("\n</details>\n\n");
+        output
+    }
+
+    /// Edge list explaining which fields, methods or nestings connect nodes.
+    fn render_dependencies_section(model: &GraphModel) -> String {
+        if model.edges.is_empty() {
+            return String::new();
+        }
+
+        let label = |id: &str| {
+            model
+                .find_node(id)
+                .map_or(id, |n| n.label.as_str())
+                .to_string()
+        };
+        let mut output = format!(
+            "<details>\n<summary>🔗 Dependencies ({})</summary>\n\n",
+            model.edges.len()
+        );
+        output.push_str("| From | To | Via |\n");
+        output.push_str("|------|----|-----|\n");
+        for edge in &model.edges {
+            let via: Vec<String> = edge.labels.iter().map(ToString::to_string).collect();
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                label(&edge.source),
+                label(&edge.target),
+                via.join(", ")
+            ));
+        }
+        output.push_str("\n</details>\n\n");
         output
     }
 
@@ -207,8 +693,10 @@ impl MarkdownReporter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::Edge;
-    use crate::domain::node::{EnumValue, FieldInfo, MethodSignature, NodeDetails};
+    use crate::domain::node::{
+        EnumValue, FieldInfo, MethodSignature, NodeDetails, NumberRange, Reserved, StreamingMode,
+    };
+    use crate::domain::{Edge, EdgeKind, EdgeLabel, Package};
 
     fn create_test_model() -> GraphModel {
         GraphModel {
@@ -225,11 +713,13 @@ mod tests {
                                 name: "GetUser".to_string(),
                                 input_type: "GetUserRequest".to_string(),
                                 output_type: "User".to_string(),
+                                ..Default::default()
                             },
                             MethodSignature {
                                 name: "CreateUser".to_string(),
                                 input_type: "CreateUserRequest".to_string(),
                                 output_type: "User".to_string(),
+                                ..Default::default()
                             },
                         ],
                         messages: vec![],
@@ -242,18 +732,23 @@ mod tests {
                     "User".to_string(),
                     "user/v1/user.proto".to_string(),
                     NodeDetails::Message {
+                        reserved: Reserved::default(),
+                        extension_ranges: vec![],
+                        oneofs: vec![],
                         fields: vec![
                             FieldInfo {
                                 name: "id".to_string(),
                                 number: 1,
                                 type_name: "string".to_string(),
                                 label: "optional".to_string(),
+                                ..Default::default()
                             },
                             FieldInfo {
                                 name: "name".to_string(),
                                 number: 2,
                                 type_name: "string".to_string(),
                                 label: "optional".to_string(),
+                                ..Default::default()
                             },
                         ],
                     },
@@ -265,23 +760,32 @@ mod tests {
                     "Status".to_string(),
                     "user/v1/user.proto".to_string(),
                     NodeDetails::Enum {
+                        reserved: Reserved::default(),
+                        allow_alias: false,
                         values: vec![
                             EnumValue {
                                 name: "UNKNOWN".to_string(),
                                 number: 0,
+                                ..Default::default()
                             },
                             EnumValue {
                                 name: "ACTIVE".to_string(),
                                 number: 1,
+                                ..Default::default()
                             },
                         ],
+                        closed: false,
                     },
                 ),
             ],
-            edges: vec![Edge::new(
-                "user.v1.UserService".to_string(),
-                "user.v1.User".to_string(),
-            )],
+            edges: vec![
+                Edge::new(
+                    "user.v1.UserService".to_string(),
+                    "user.v1.User".to_string(),
+                )
+                .with_label(EdgeLabel::new(EdgeKind::RpcOutput).with_name("GetUser"))
+                .with_label(EdgeLabel::new(EdgeKind::RpcOutput).with_name("CreateUser")),
+            ],
             packages: vec![],
         }
     }
@@ -312,6 +816,37 @@ mod tests {
         assert!(report.contains("| GetUser | GetUserRequest | User |"));
     }
 
+    #[test]
+    fn test_render_service_streaming_methods() {
+        let method = |name: &str, streaming: StreamingMode| MethodSignature {
+            name: name.to_string(),
+            input_type: "Req".to_string(),
+            output_type: "Res".to_string(),
+            streaming,
+            ..Default::default()
+        };
+        let node = Node::new(
+            "chat.v1.ChatService".to_string(),
+            NodeType::Service,
+            "chat.v1".to_string(),
+            "ChatService".to_string(),
+            "chat/v1/chat.proto".to_string(),
+            NodeDetails::Service {
+                methods: vec![
+                    method("Send", StreamingMode::Unary),
+                    method("Watch", StreamingMode::ServerStreaming),
+                    method("Chat", StreamingMode::BidiStreaming),
+                ],
+                messages: vec![],
+            },
+        );
+
+        let rendered = MarkdownReporter::default().render_service(&node);
+        assert!(rendered.contains("| Send | Req | Res |"));
+        assert!(rendered.contains("| Watch | Req | stream Res |"));
+        assert!(rendered.contains("| Chat | stream Req | stream Res |"));
+    }
+
     #[test]
     fn test_generate_contains_messages() {
         let model = create_test_model();
@@ -321,6 +856,286 @@ mod tests {
         assert!(report.contains("| 1 | id | string | optional |"));
     }
 
+    #[test]
+    fn test_render_message_oneof_and_optional() {
+        let source = r#"
+            syntax = "proto3";
+            package pay.v1;
+            message Money { int64 units = 1; }
+            message Payment {
+              string id = 1;
+              Money total = 2;
+              optional string note = 3;
+              oneof method { string card = 4; }
+              repeated string tags = 5;
+            }
+        "#;
+        let fds = crate::parser::compile_with(&["pay/v1/pay.proto".to_string()], |name| {
+            Ok((name == "pay/v1/pay.proto").then(|| source.to_string()))
+        })
+        .expect("compile");
+        let model = crate::analyzer::Analyzer::new().analyze(&fds);
+        let node = model.find_node("pay.v1.Payment").expect("Payment");
+
+        // Singular scalars have no presence; message fields always track it
+        let rendered = MarkdownReporter::default().render_message(node);
+        assert!(rendered.contains("| 1 | id | string |  |"));
+        assert!(rendered.contains("| 2 | total | Money | optional |"));
+        assert!(rendered.contains("| 3 | note | string | optional |"));
+        assert!(rendered.contains("| 4 | card | string | oneof method |"));
+        assert!(rendered.contains("| 5 | tags | string | repeated |"));
+    }
+
+    #[test]
+    fn test_render_message_descriptions() {
+        let node = Node::new(
+            "shop.v1.Order".to_string(),
+            NodeType::Message,
+            "shop.v1".to_string(),
+            "Order".to_string(),
+            "shop/v1/order.proto".to_string(),
+            NodeDetails::Message {
+                reserved: Reserved::default(),
+                extension_ranges: vec![],
+                oneofs: vec![],
+                fields: vec![
+                    FieldInfo {
+                        name: "id".to_string(),
+                        number: 1,
+                        type_name: "string".to_string(),
+                        label: "optional".to_string(),
+                        comments: Comments::new(None, Some(" Opaque | stable ID.\n")),
+                        ..Default::default()
+                    },
+                    FieldInfo {
+                        name: "note".to_string(),
+                        number: 2,
+                        type_name: "string".to_string(),
+                        label: "optional".to_string(),
+                        ..Default::default()
+                    },
+                ],
+            },
+        )
+        .with_comments(Comments::new(
+            Some(" A customer order.\n Immutable.\n"),
+            None,
+        ));
+
+        let rendered = MarkdownReporter::default().render_message(&node);
+        assert!(rendered.contains("`\n\nA customer order.\nImmutable.\n\n"));
+        assert!(rendered.contains("| # | Field | Type | Label | Description |"));
+        assert!(rendered.contains("| 1 | id | string | optional | Opaque \\| stable ID. |"));
+        assert!(rendered.contains("| 2 | note | string | optional |  |"));
+    }
+
+    #[test]
+    fn test_render_permalinks() {
+        let span = |line| Span {
+            start_line: line,
+            start_column: 1,
+            end_line: line + 2,
+            end_column: 1,
+        };
+        let node = Node::new(
+            "shop.v1.Order".to_string(),
+            NodeType::Message,
+            "shop.v1".to_string(),
+            "Order".to_string(),
+            "shop/v1/order.proto".to_string(),
+            NodeDetails::Message {
+                reserved: Reserved::default(),
+                extension_ranges: vec![],
+                oneofs: vec![],
+                fields: vec![
+                    FieldInfo {
+                        name: "id".to_string(),
+                        number: 1,
+                        type_name: "string".to_string(),
+                        label: "optional".to_string(),
+                        span: Some(span(5)),
+                        ..Default::default()
+                    },
+                    FieldInfo {
+                        name: "note".to_string(),
+                        number: 2,
+                        type_name: "string".to_string(),
+                        label: "optional".to_string(),
+                        ..Default::default()
+                    },
+                ],
+            },
+        )
+        .with_span(Some(span(4)));
+
+        let reporter = MarkdownReporter::default().with_permalinks(Some(Permalinks::new(
+            "https://example.com/api/blob/{sha}/proto/{path}#L{line}-L{end_line}",
+            "abc123",
+        )));
+        let rendered = reporter.render_message(&node);
+        assert!(rendered.contains(
+            "#### [Order](https://example.com/api/blob/abc123/proto/shop/v1/order.proto#L4-L6)"
+        ));
+        assert!(rendered.contains(
+            "| 1 | [id](https://example.com/api/blob/abc123/proto/shop/v1/order.proto#L5-L7) |"
+        ));
+        assert!(rendered.contains("| 2 | note | string |"));
+
+        let plain = MarkdownReporter::default().render_message(&node);
+        assert!(plain.contains("#### Order\n"));
+    }
+
+    #[test]
+    fn test_foreign_section() {
+        let mut model = create_test_model();
+        model.nodes[2].foreign = true;
+
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("| Enums | 0 |"));
+        assert!(report.contains("| Foreign | 1 |"));
+        assert!(!report.contains("🏷️ Enums"));
+        assert!(report.contains("🌐 Foreign (1)"));
+        assert!(report.contains("#### Status"));
+        assert!(report.contains("| UNKNOWN | 0 |"));
+    }
+
+    #[test]
+    fn test_foreign_extensions_keep_their_columns() {
+        let mut model = create_test_model();
+        let mut extension = Node::new(
+            "acme.v1.sensitive".to_string(),
+            NodeType::Extension,
+            "acme.v1".to_string(),
+            "sensitive".to_string(),
+            "acme/v1/options.proto".to_string(),
+            NodeDetails::Extension {
+                extendee: "google.protobuf.FieldOptions".to_string(),
+                field: FieldInfo {
+                    name: "sensitive".to_string(),
+                    number: 50000,
+                    type_name: "bool".to_string(),
+                    label: "optional".to_string(),
+                    ..Default::default()
+                },
+            },
+        );
+        extension.foreign = true;
+        model.nodes.push(extension);
+
+        let report = MarkdownReporter::generate(&model);
+        assert!(!report.contains("🧩 Extensions"));
+        assert!(report.contains("🌐 Foreign (1)"));
+        assert!(report.contains(
+            "| sensitive | google.protobuf.FieldOptions | 50000 | bool | optional | acme/v1/options.proto |"
+        ));
+    }
+
+    #[test]
+    fn test_deprecated_marking_and_section() {
+        let mut model = create_test_model();
+        model.nodes[1].deprecated = true;
+        if let NodeDetails::Message { fields, .. } = &mut model.nodes[1].details {
+            fields[1].deprecated = true;
+        }
+        if let NodeDetails::Enum { values, .. } = &mut model.nodes[2].details {
+            values[1].deprecated = true;
+        }
+
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("#### ~~User~~ ⚠️ *deprecated*"));
+        assert!(report.contains("| 2 | ~~name~~ | string | optional |"));
+        assert!(report.contains("| ~~ACTIVE~~ | 1 |"));
+        assert!(report.contains("🚧 Deprecated (3)"));
+        assert!(report.contains("| User | Message | user/v1/user.proto | UserService |"));
+        assert!(report.contains("| User.name | Field | user/v1/user.proto | UserService |"));
+        assert!(report.contains("| Status.ACTIVE | Enum value | user/v1/user.proto |  |"));
+
+        // Members list whoever references the definition that owns them
+        model.edges.push(
+            Edge::new("user.v1.User".to_string(), "user.v1.Status".to_string())
+                .with_label(EdgeLabel::new(EdgeKind::Field).with_name("status")),
+        );
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("| Status.ACTIVE | Enum value | user/v1/user.proto | User |"));
+        assert!(!MarkdownReporter::generate(&create_test_model()).contains("🚧 Deprecated"));
+    }
+
+    #[test]
+    fn test_render_reserved_and_extension_ranges() {
+        let mut model = create_test_model();
+        if let NodeDetails::Message {
+            reserved,
+            extension_ranges,
+            ..
+        } = &mut model.nodes[1].details
+        {
+            *reserved = Reserved {
+                ranges: vec![
+                    NumberRange::new(3, 3, false),
+                    NumberRange::new(9, 11, false),
+                ],
+                names: vec!["email".to_string()],
+            };
+            extension_ranges.push(NumberRange::new(100, NumberRange::MAX_FIELD_NUMBER, false));
+        }
+
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("**Reserved**: 3, 9 to 11, `email`\n"));
+        assert!(report.contains("**Extensions**: 100 to max\n"));
+        assert!(!MarkdownReporter::generate(&create_test_model()).contains("**Reserved**"));
+    }
+
+    #[test]
+    fn test_render_json_names_defaults_and_aliases() {
+        let mut model = create_test_model();
+        if let NodeDetails::Message { fields, .. } = &mut model.nodes[1].details {
+            fields[0].json_name = Some("userId".to_string());
+            fields[1].default_value = Some("anonymous".to_string());
+        }
+        if let NodeDetails::Enum { values, .. } = &mut model.nodes[2].details {
+            values.push(EnumValue {
+                name: "ENABLED".to_string(),
+                number: 1,
+                alias_of: Some("ACTIVE".to_string()),
+                ..Default::default()
+            });
+        }
+
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("| 1 | id | string | optional |"));
+        assert!(report.contains("| 2 | name | string | optional [default = anonymous] |"));
+        assert!(report.contains("| ENABLED | 1 (alias of ACTIVE) |"));
+
+        let report = MarkdownReporter::default()
+            .with_json_names(true)
+            .render(&model);
+        assert!(report.contains("| 1 | userId | string | optional |"));
+        // Fields without a known JSON name keep their proto name
+        assert!(report.contains("| 2 | name |"));
+    }
+
+    #[test]
+    fn test_packages_section() {
+        let mut model = create_test_model();
+        model.packages = Package::tree(&model.nodes);
+
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("🗂️ Packages (2)"));
+        assert!(report.contains("| `user` | 1 | 1 | 1 | 0 |\n| &emsp;`user.v1` | 1 | 1 | 1 | 0 |"));
+        assert!(!MarkdownReporter::generate(&create_test_model()).contains("🗂️ Packages"));
+    }
+
+    #[test]
+    fn test_generate_contains_dependencies() {
+        let model = create_test_model();
+        let report = MarkdownReporter::generate(&model);
+        assert!(report.contains("🔗 Dependencies (1)"));
+        assert!(
+            report
+                .contains("| UserService | User | rpc output `GetUser`, rpc output `CreateUser` |")
+        );
+    }
+
     #[test]
     fn test_generate_contains_enums() {
         let model = create_test_model();
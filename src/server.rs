@@ -115,6 +115,7 @@ mod tests {
                         name: "GetUser".to_string(),
                         input_type: "GetUserRequest".to_string(),
                         output_type: "GetUserResponse".to_string(),
+                        ..Default::default()
                     }],
                     messages: vec![],
                 },
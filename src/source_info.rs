@@ -0,0 +1,113 @@
+//! Looking up `SourceCodeInfo` locations by definition name.
+//!
+//! Locations are addressed by descriptor paths (`[4, 0, 2, 1]` is the
+//! second field of the first message); the analyzer works with
+//! fully-qualified names instead, so this walks the descriptor once and
+//! indexes every location by the name of the definition it belongs to.
+
+use std::collections::HashMap;
+
+use prost_types::source_code_info::Location;
+use prost_types::{DescriptorProto, EnumDescriptorProto, FileDescriptorProto};
+
+// Field numbers of the repeated descriptor fields that make up paths
+const FILE_MESSAGE: i32 = 4;
+const FILE_ENUM: i32 = 5;
+const FILE_SERVICE: i32 = 6;
+const FILE_EXTENSION: i32 = 7;
+const MESSAGE_FIELD: i32 = 2;
+const MESSAGE_NESTED: i32 = 3;
+const MESSAGE_ENUM: i32 = 4;
+const MESSAGE_EXTENSION: i32 = 6;
+const ENUM_VALUE: i32 = 2;
+const SERVICE_METHOD: i32 = 2;
+
+/// Locations of the messages, fields, enums, enum values, services, methods
+/// and extensions of `file`, keyed by fully-qualified name with a leading
+/// dot. Enum values are keyed under their enum (`.pkg.Status.ACTIVE`).
+#[must_use]
+pub fn locations_by_name(file: &FileDescriptorProto) -> HashMap<String, Location> {
+    let Some(info) = &file.source_code_info else {
+        return HashMap::new();
+    };
+    let by_path: HashMap<&[i32], &Location> = info
+        .location
+        .iter()
+        .map(|l| (l.path.as_slice(), l))
+        .collect();
+
+    let mut index = Index {
+        by_path,
+        by_name: HashMap::new(),
+    };
+    let scope = match file.package() {
+        "" => String::new(),
+        package => format!(".{package}"),
+    };
+    for (i, message) in file.message_type.iter().enumerate() {
+        index.message(message, &scope, &[FILE_MESSAGE, i as i32]);
+    }
+    for (i, enum_type) in file.enum_type.iter().enumerate() {
+        index.enumeration(enum_type, &scope, &[FILE_ENUM, i as i32]);
+    }
+    for (i, service) in file.service.iter().enumerate() {
+        let path = [FILE_SERVICE, i as i32];
+        let name = format!("{scope}.{}", service.name());
+        for (j, method) in service.method.iter().enumerate() {
+            let method_path = [&path[..], &[SERVICE_METHOD, j as i32]].concat();
+            index.insert(&method_path, format!("{name}.{}", method.name()));
+        }
+        index.insert(&path, name);
+    }
+    for (i, extension) in file.extension.iter().enumerate() {
+        index.insert(
+            &[FILE_EXTENSION, i as i32],
+            format!("{scope}.{}", extension.name()),
+        );
+    }
+    index.by_name
+}
+
+struct Index<'a> {
+    by_path: HashMap<&'a [i32], &'a Location>,
+    by_name: HashMap<String, Location>,
+}
+
+impl Index<'_> {
+    fn insert(&mut self, path: &[i32], name: String) {
+        if let Some(location) = self.by_path.get(path) {
+            self.by_name.insert(name, (*location).clone());
+        }
+    }
+
+    fn message(&mut self, message: &DescriptorProto, scope: &str, path: &[i32]) {
+        let name = format!("{scope}.{}", message.name());
+        let child = |field: i32, i: usize| [path, &[field, i as i32]].concat();
+
+        for (i, field) in message.field.iter().enumerate() {
+            self.insert(&child(MESSAGE_FIELD, i), format!("{name}.{}", field.name()));
+        }
+        for (i, extension) in message.extension.iter().enumerate() {
+            self.insert(
+                &child(MESSAGE_EXTENSION, i),
+                format!("{name}.{}", extension.name()),
+            );
+        }
+        for (i, nested) in message.nested_type.iter().enumerate() {
+            self.message(nested, &name, &child(MESSAGE_NESTED, i));
+        }
+        for (i, enum_type) in message.enum_type.iter().enumerate() {
+            self.enumeration(enum_type, &name, &child(MESSAGE_ENUM, i));
+        }
+        self.insert(path, name);
+    }
+
+    fn enumeration(&mut self, enum_type: &EnumDescriptorProto, scope: &str, path: &[i32]) {
+        let name = format!("{scope}.{}", enum_type.name());
+        for (i, value) in enum_type.value.iter().enumerate() {
+            let value_path = [path, &[ENUM_VALUE, i as i32]].concat();
+            self.insert(&value_path, format!("{name}.{}", value.name()));
+        }
+        self.insert(path, name);
+    }
+}
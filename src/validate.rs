@@ -0,0 +1,244 @@
+//! Structural validation of descriptor sets before analysis.
+//!
+//! `Analyzer` assumes every imported file is present and every type
+//! reference resolves; when that does not hold it silently loses edges.
+//! [`validate`] reports those problems up front so callers can warn about
+//! them or, with [`check`], refuse to continue.
+
+use std::collections::{HashMap, HashSet};
+
+use prost_types::{DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet};
+
+use crate::error::{CoralError, Result};
+use crate::merge::defined_types;
+
+/// Collect every problem in `fds`, in file order.
+///
+/// Each diagnostic is one of [`CoralError::MissingDependency`],
+/// [`CoralError::DependencyOrder`] or [`CoralError::DanglingTypeReference`].
+#[must_use]
+pub fn validate(fds: &FileDescriptorSet) -> Vec<CoralError> {
+    let position: HashMap<&str, usize> = fds
+        .file
+        .iter()
+        .enumerate()
+        .map(|(index, file)| (file.name(), index))
+        .collect();
+    let types: HashSet<String> = fds.file.iter().flat_map(defined_types).collect();
+
+    let mut diagnostics = Vec::new();
+    for (index, file) in fds.file.iter().enumerate() {
+        for dependency in &file.dependency {
+            match position.get(dependency.as_str()) {
+                None => diagnostics.push(CoralError::MissingDependency {
+                    file: file.name().to_string(),
+                    dependency: dependency.clone(),
+                }),
+                Some(&dep_index) if dep_index > index => {
+                    diagnostics.push(CoralError::DependencyOrder {
+                        file: file.name().to_string(),
+                        dependency: dependency.clone(),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (element, type_name) in type_references(file) {
+            if !types.contains(&type_name) {
+                diagnostics.push(CoralError::DanglingTypeReference {
+                    file: file.name().to_string(),
+                    element,
+                    type_name,
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Fail with [`CoralError::Validation`] if [`validate`] reports anything.
+pub fn check(fds: &FileDescriptorSet) -> Result<()> {
+    let diagnostics = validate(fds);
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(CoralError::Validation { diagnostics })
+    }
+}
+
+/// `(element, type_name)` for every field type, extendee and method
+/// input/output in `file`; elements are fully-qualified without the
+/// leading dot.
+pub(crate) fn type_references(file: &FileDescriptorProto) -> Vec<(String, String)> {
+    let scope = file.package().to_string();
+    let mut references = Vec::new();
+
+    for message in &file.message_type {
+        collect_message_references(message, &scope, &mut references);
+    }
+    for extension in &file.extension {
+        collect_field_references(extension, &scope, &mut references);
+    }
+    for service in &file.service {
+        let service_name = qualify(&scope, service.name());
+        for method in &service.method {
+            let element = format!("{service_name}.{}", method.name());
+            for type_name in [&method.input_type, &method.output_type]
+                .into_iter()
+                .flatten()
+            {
+                references.push((element.clone(), type_name.clone()));
+            }
+        }
+    }
+    references
+}
+
+fn collect_message_references(
+    message: &DescriptorProto,
+    scope: &str,
+    out: &mut Vec<(String, String)>,
+) {
+    let name = qualify(scope, message.name());
+    for field in message.field.iter().chain(&message.extension) {
+        collect_field_references(field, &name, out);
+    }
+    for nested in &message.nested_type {
+        collect_message_references(nested, &name, out);
+    }
+}
+
+fn collect_field_references(
+    field: &FieldDescriptorProto,
+    scope: &str,
+    out: &mut Vec<(String, String)>,
+) {
+    let element = qualify(scope, field.name());
+    for type_name in [&field.type_name, &field.extendee].into_iter().flatten() {
+        out.push((element.clone(), type_name.clone()));
+    }
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost_types::{MethodDescriptorProto, ServiceDescriptorProto};
+
+    use super::*;
+
+    fn file(name: &str, dependencies: &[&str], field_type: Option<&str>) -> FileDescriptorProto {
+        FileDescriptorProto {
+            name: Some(name.to_string()),
+            package: Some(name.trim_end_matches(".proto").to_string()),
+            dependency: dependencies.iter().map(|d| d.to_string()).collect(),
+            message_type: vec![DescriptorProto {
+                name: Some("Msg".to_string()),
+                field: field_type
+                    .map(|t| FieldDescriptorProto {
+                        name: Some("ref".to_string()),
+                        number: Some(1),
+                        type_name: Some(t.to_string()),
+                        ..Default::default()
+                    })
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_set() {
+        let fds = FileDescriptorSet {
+            file: vec![
+                file("a.proto", &[], None),
+                file("b.proto", &["a.proto"], Some(".a.Msg")),
+            ],
+        };
+        assert!(validate(&fds).is_empty());
+        assert!(check(&fds).is_ok());
+    }
+
+    #[test]
+    fn test_missing_dependency() {
+        let fds = FileDescriptorSet {
+            file: vec![file("b.proto", &["a.proto"], None)],
+        };
+        let diagnostics = validate(&fds);
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [CoralError::MissingDependency { file, dependency }]
+                if file == "b.proto" && dependency == "a.proto"
+        ));
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let fds = FileDescriptorSet {
+            file: vec![
+                file("b.proto", &["a.proto"], Some(".a.Msg")),
+                file("a.proto", &[], None),
+            ],
+        };
+        let diagnostics = validate(&fds);
+        assert!(matches!(
+            diagnostics.as_slice(),
+            [CoralError::DependencyOrder { file, dependency }]
+                if file == "b.proto" && dependency == "a.proto"
+        ));
+    }
+
+    #[test]
+    fn test_dangling_type_references() {
+        let mut service_file = file("svc.proto", &[], Some(".gone.Msg"));
+        service_file.service.push(ServiceDescriptorProto {
+            name: Some("Svc".to_string()),
+            method: vec![MethodDescriptorProto {
+                name: Some("Call".to_string()),
+                input_type: Some(".svc.Msg".to_string()),
+                output_type: Some(".svc.Missing".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let fds = FileDescriptorSet {
+            file: vec![service_file],
+        };
+
+        let dangling: Vec<(String, String)> = validate(&fds)
+            .into_iter()
+            .filter_map(|d| match d {
+                CoralError::DanglingTypeReference {
+                    element, type_name, ..
+                } => Some((element, type_name)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            dangling,
+            vec![
+                ("svc.Msg.ref".to_string(), ".gone.Msg".to_string()),
+                ("svc.Svc.Call".to_string(), ".svc.Missing".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_collects_all_diagnostics() {
+        let fds = FileDescriptorSet {
+            file: vec![file("b.proto", &["a.proto"], Some(".a.Msg"))],
+        };
+        let err = check(&fds).expect_err("invalid");
+        assert!(matches!(&err, CoralError::Validation { diagnostics } if diagnostics.len() == 2));
+        assert!(err.to_string().contains("2 problems"));
+    }
+}
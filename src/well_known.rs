@@ -0,0 +1,176 @@
+//! The protobuf well-known types (`google/protobuf/*.proto`) and how the
+//! proto3 JSON mapping writes them.
+
+use crate::domain::{WellKnown, WellKnownCategory};
+
+struct Entry {
+    /// Fully-qualified name without the leading dot.
+    name: &'static str,
+    file: &'static str,
+    category: WellKnownCategory,
+    json: &'static str,
+    wraps: Option<&'static str>,
+}
+
+const fn entry(
+    name: &'static str,
+    file: &'static str,
+    category: WellKnownCategory,
+    json: &'static str,
+) -> Entry {
+    Entry {
+        name,
+        file,
+        category,
+        json,
+        wraps: None,
+    }
+}
+
+const fn wrapper(name: &'static str, json: &'static str, wraps: &'static str) -> Entry {
+    Entry {
+        name,
+        file: "google/protobuf/wrappers.proto",
+        category: WellKnownCategory::Wrapper,
+        json,
+        wraps: Some(wraps),
+    }
+}
+
+const TYPES: &[Entry] = &[
+    entry(
+        "google.protobuf.Timestamp",
+        "google/protobuf/timestamp.proto",
+        WellKnownCategory::Time,
+        "RFC 3339 string, e.g. \"2017-01-15T01:30:15.01Z\"",
+    ),
+    entry(
+        "google.protobuf.Duration",
+        "google/protobuf/duration.proto",
+        WellKnownCategory::Time,
+        "seconds with an \"s\" suffix, e.g. \"1.000340012s\"",
+    ),
+    entry(
+        "google.protobuf.Any",
+        "google/protobuf/any.proto",
+        WellKnownCategory::Any,
+        "object with an \"@type\" URL and the packed message's fields",
+    ),
+    entry(
+        "google.protobuf.Struct",
+        "google/protobuf/struct.proto",
+        WellKnownCategory::Struct,
+        "any JSON object",
+    ),
+    entry(
+        "google.protobuf.Value",
+        "google/protobuf/struct.proto",
+        WellKnownCategory::Struct,
+        "any JSON value",
+    ),
+    entry(
+        "google.protobuf.ListValue",
+        "google/protobuf/struct.proto",
+        WellKnownCategory::Struct,
+        "JSON array",
+    ),
+    entry(
+        "google.protobuf.NullValue",
+        "google/protobuf/struct.proto",
+        WellKnownCategory::Struct,
+        "null",
+    ),
+    entry(
+        "google.protobuf.FieldMask",
+        "google/protobuf/field_mask.proto",
+        WellKnownCategory::FieldMask,
+        "comma-separated lowerCamelCase paths, e.g. \"user.displayName,photo\"",
+    ),
+    entry(
+        "google.protobuf.Empty",
+        "google/protobuf/empty.proto",
+        WellKnownCategory::Empty,
+        "empty object {}",
+    ),
+    wrapper("google.protobuf.DoubleValue", "number or null", "double"),
+    wrapper("google.protobuf.FloatValue", "number or null", "float"),
+    wrapper(
+        "google.protobuf.Int64Value",
+        "decimal string or null",
+        "int64",
+    ),
+    wrapper(
+        "google.protobuf.UInt64Value",
+        "decimal string or null",
+        "uint64",
+    ),
+    wrapper("google.protobuf.Int32Value", "number or null", "int32"),
+    wrapper("google.protobuf.UInt32Value", "number or null", "uint32"),
+    wrapper("google.protobuf.BoolValue", "true, false or null", "bool"),
+    wrapper("google.protobuf.StringValue", "string or null", "string"),
+    wrapper(
+        "google.protobuf.BytesValue",
+        "base64 string or null",
+        "bytes",
+    ),
+];
+
+fn find(fq_type: &str) -> Option<&'static Entry> {
+    let name = fq_type.trim_start_matches('.');
+    TYPES.iter().find(|e| e.name == name)
+}
+
+/// Semantics of the well-known type `fq_type` (leading dot optional),
+/// together with the file that defines it.
+#[must_use]
+pub fn lookup(fq_type: &str) -> Option<(&'static str, WellKnown)> {
+    find(fq_type).map(|e| {
+        (
+            e.file,
+            WellKnown {
+                category: e.category,
+                json: e.json.to_string(),
+                wraps: e.wraps.map(str::to_string),
+            },
+        )
+    })
+}
+
+/// Scalar boxed by the wrapper type `fq_type`, e.g. `int64` for
+/// `.google.protobuf.Int64Value`.
+#[must_use]
+pub fn wrapped_scalar(fq_type: &str) -> Option<&'static str> {
+    find(fq_type).and_then(|e| e.wraps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let (file, timestamp) = lookup(".google.protobuf.Timestamp").expect("Timestamp");
+        assert_eq!(file, "google/protobuf/timestamp.proto");
+        assert_eq!(timestamp.category, WellKnownCategory::Time);
+
+        let (file, value) = lookup("google.protobuf.Value").expect("Value");
+        assert_eq!(file, "google/protobuf/struct.proto");
+        assert_eq!(value.category, WellKnownCategory::Struct);
+
+        assert!(lookup(".google.protobuf.FileOptions").is_none());
+        assert!(lookup(".acme.v1.Timestamp").is_none());
+    }
+
+    #[test]
+    fn test_wrapped_scalar() {
+        assert_eq!(
+            wrapped_scalar(".google.protobuf.StringValue"),
+            Some("string")
+        );
+        assert_eq!(
+            wrapped_scalar(".google.protobuf.UInt64Value"),
+            Some("uint64")
+        );
+        assert_eq!(wrapped_scalar(".google.protobuf.Timestamp"), None);
+    }
+}
@@ -1,10 +1,13 @@
 import { useEffect, useCallback, useRef, useState } from 'react';
 import type {
+  Comments,
   GraphNode,
   MethodSignature,
   FieldInfo,
   EnumValue,
   MessageDef,
+  NumberRange,
+  Reserved,
 } from '@/types/graph';
 import { useIsMobile } from '@/hooks/useIsMobile';
 import { useSwipeToClose } from '@/hooks/useSwipeToClose';
@@ -15,6 +18,11 @@ import {
   SWIPE_THRESHOLD,
 } from '@/constants/layout';
 
+/** Leading doc comment, falling back to the trailing one */
+function describe(comments?: Comments): string | undefined {
+  return comments?.leading ?? comments?.trailing;
+}
+
 interface DetailPanelProps {
   node: GraphNode | null;
   onClose: () => void;
@@ -110,7 +118,11 @@ export function DetailPanel({ node, onClose }: DetailPanelProps) {
 
       {/* Header */}
       <div className="flex items-center justify-between p-3 sm:p-4 border-b border-white/10 sticky top-0 bg-bg-dark/95 backdrop-blur-sm z-10">
-        <h2 className="text-base sm:text-lg font-bold text-neon-cyan truncate pr-2">{node.label}</h2>
+        <h2
+          className={`text-base sm:text-lg font-bold text-neon-cyan truncate pr-2 ${node.deprecated ? 'line-through' : ''}`}
+        >
+          {node.label}
+        </h2>
         <button
           className="min-h-[44px] min-w-[44px] sm:min-h-0 sm:min-w-0 sm:w-8 sm:h-8 flex items-center justify-center
                      text-xl sm:text-2xl text-text-secondary hover:text-white
@@ -127,10 +139,19 @@ export function DetailPanel({ node, onClose }: DetailPanelProps) {
       <div className="p-3 sm:p-4 space-y-4">
         {/* Meta info */}
         <div className="flex flex-col gap-1 text-xs sm:text-sm">
-          <span className="text-text-secondary break-all font-mono">{node.file}</span>
+          <span className="text-text-secondary break-all font-mono">
+            {node.file}
+            {node.span && `:${node.span.startLine}`}
+          </span>
           <span className="text-neon-cyan/70 break-all">{node.package}</span>
+          {node.deprecated && <span className="text-neon-yellow text-xs">⚠ deprecated</span>}
+          {node.foreign && <span className="text-text-secondary text-xs">🌐 foreign package</span>}
         </div>
 
+        {describe(node.comments) && (
+          <p className="text-sm text-text-secondary whitespace-pre-line">{describe(node.comments)}</p>
+        )}
+
         {node.details.kind === 'Service' && (
           <ServiceDetails
             methods={node.details.methods}
@@ -138,19 +159,60 @@ export function DetailPanel({ node, onClose }: DetailPanelProps) {
           />
         )}
 
+        {node.details.kind === 'Method' && (
+          <ul>
+            <MethodItem
+              method={node.details.signature}
+              expandedTypes={new Set()}
+              onToggleType={() => {}}
+              findMessage={() => undefined}
+            />
+          </ul>
+        )}
+
         {node.details.kind === 'Message' && (
-          <MessageDetails fields={node.details.fields} />
+          <>
+            <MessageDetails fields={node.details.fields} />
+            <ReservedDetails reserved={node.details.reserved} extensionRanges={node.details.extensionRanges} />
+          </>
         )}
 
         {node.details.kind === 'Enum' && (
-          <EnumDetails values={node.details.values} />
+          <>
+            <EnumDetails values={node.details.values} />
+            <ReservedDetails reserved={node.details.reserved} />
+          </>
+        )}
+
+        {node.details.kind === 'Extension' && (
+          <div className="space-y-3">
+            <p className="text-sm text-text-secondary">
+              Extends <span className="text-neon-cyan font-mono break-all">{node.details.extendee}</span>
+            </p>
+            <MessageDetails fields={[node.details.field]} />
+          </div>
         )}
 
         {node.details.kind === 'External' && (
           <div className="py-4">
-            <p className="text-text-secondary text-sm italic">External library - no additional details</p>
+            {node.details.wellKnown ? (
+              <div className="text-sm space-y-1">
+                <p className="text-text-secondary">Well-known type ({node.details.wellKnown.category})</p>
+                <p className="text-white">JSON: {node.details.wellKnown.json}</p>
+              </div>
+            ) : (
+              <p className="text-text-secondary text-sm italic">External library - no additional details</p>
+            )}
           </div>
         )}
+
+        {node.details.kind === 'File' && (
+          <FileDetails
+            unused={node.details.unusedImports ?? []}
+            indirect={node.details.indirectImports ?? []}
+            missing={node.details.missingImports ?? []}
+          />
+        )}
       </div>
     </div>
   );
@@ -177,8 +239,9 @@ function ServiceDetails({
     });
   };
 
-  const findMessage = (typeName: string): MessageDef | undefined => {
-    return messages.find((m) => m.name === typeName);
+  /** Looks up by type ref; graphs without refs fall back to short names */
+  const findMessage = (typeRef: string): MessageDef | undefined => {
+    return messages.find((m) => (m.typeRef || m.name) === typeRef);
   };
 
   return (
@@ -209,14 +272,14 @@ function ServiceDetails({
 interface MethodItemProps {
   method: MethodSignature;
   expandedTypes: Set<string>;
-  onToggleType: (typeName: string) => void;
-  findMessage: (typeName: string) => MessageDef | undefined;
+  onToggleType: (typeRef: string) => void;
+  findMessage: (typeRef: string) => MessageDef | undefined;
 }
 
 function MethodItem({ method, expandedTypes, onToggleType, findMessage }: MethodItemProps) {
-  const renderTypeLink = (typeName: string) => {
-    const messageDef = findMessage(typeName);
-    const isExpanded = expandedTypes.has(typeName);
+  const renderTypeLink = (typeName: string, typeRef: string) => {
+    const messageDef = findMessage(typeRef);
+    const isExpanded = expandedTypes.has(typeRef);
 
     if (messageDef) {
       return (
@@ -229,43 +292,62 @@ function MethodItem({ method, expandedTypes, onToggleType, findMessage }: Method
             min-h-[32px] sm:min-h-0 touch-manipulation
             ${isExpanded ? 'bg-neon-cyan/10' : ''}
           `}
-          onClick={() => onToggleType(typeName)}
+          title={typeRef}
+          onClick={() => onToggleType(typeRef)}
         >
           {typeName}
           <span className="text-[0.65rem] sm:text-xs opacity-70">{isExpanded ? '▼' : '▶'}</span>
         </button>
       );
     }
-    return <span className="text-text-secondary text-xs sm:text-sm">{typeName}</span>;
+    return (
+      <span className="text-text-secondary text-xs sm:text-sm" title={typeRef}>
+        {typeName}
+      </span>
+    );
   };
 
+  const inputRef = method.inputTypeRef ?? method.inputType;
+  const outputRef = method.outputTypeRef ?? method.outputType;
+  const clientStreams = method.streaming === 'clientStreaming' || method.streaming === 'bidiStreaming';
+  const serverStreams = method.streaming === 'serverStreaming' || method.streaming === 'bidiStreaming';
+
   return (
     <li className="p-2 sm:p-3 bg-white/5 rounded-lg border border-white/10">
       <div className="flex flex-col sm:flex-row sm:items-center gap-1 sm:gap-2">
-        <span className="font-semibold text-white text-sm sm:text-base">{method.name}</span>
+        <span
+          className={`font-semibold text-white text-sm sm:text-base ${method.deprecated ? 'line-through' : ''}`}
+        >
+          {method.name}
+        </span>
         <span className="text-xs sm:text-sm text-text-secondary flex items-center flex-wrap gap-1">
           <span>(</span>
-          {renderTypeLink(method.inputType)}
+          {clientStreams && <span className="text-neon-magenta">stream</span>}
+          {renderTypeLink(method.inputType, inputRef)}
           <span>)</span>
           <span className="mx-1">→</span>
-          {renderTypeLink(method.outputType)}
+          {serverStreams && <span className="text-neon-magenta">stream</span>}
+          {renderTypeLink(method.outputType, outputRef)}
         </span>
       </div>
-      <ExpandedFieldsTable typeName={method.inputType} expandedTypes={expandedTypes} findMessage={findMessage} />
-      <ExpandedFieldsTable typeName={method.outputType} expandedTypes={expandedTypes} findMessage={findMessage} />
+      {describe(method.comments) && (
+        <p className="mt-1 text-xs text-text-secondary whitespace-pre-line">{describe(method.comments)}</p>
+      )}
+      <ExpandedFieldsTable typeRef={inputRef} expandedTypes={expandedTypes} findMessage={findMessage} />
+      <ExpandedFieldsTable typeRef={outputRef} expandedTypes={expandedTypes} findMessage={findMessage} />
     </li>
   );
 }
 
 interface ExpandedFieldsTableProps {
-  typeName: string;
+  typeRef: string;
   expandedTypes: Set<string>;
-  findMessage: (typeName: string) => MessageDef | undefined;
+  findMessage: (typeRef: string) => MessageDef | undefined;
 }
 
-function ExpandedFieldsTable({ typeName, expandedTypes, findMessage }: ExpandedFieldsTableProps) {
-  if (!expandedTypes.has(typeName)) return null;
-  const messageDef = findMessage(typeName);
+function ExpandedFieldsTable({ typeRef, expandedTypes, findMessage }: ExpandedFieldsTableProps) {
+  if (!expandedTypes.has(typeRef)) return null;
+  const messageDef = findMessage(typeRef);
   if (!messageDef) return null;
 
   return (
@@ -282,8 +364,13 @@ function ExpandedFieldsTable({ typeName, expandedTypes, findMessage }: ExpandedF
           {messageDef.fields.map((f) => (
             <tr key={`${f.number}-${f.name}`} className="border-t border-white/5">
               <td className="p-1 sm:p-1.5 text-text-secondary font-mono">{f.number}</td>
-              <td className="p-1 sm:p-1.5 text-white font-medium">{f.name}</td>
-              <td className="p-1 sm:p-1.5 text-neon-cyan/80 font-mono">{f.typeName}</td>
+              <td className={`p-1 sm:p-1.5 text-white font-medium ${f.deprecated ? 'line-through' : ''}`}>
+                {f.name}
+              </td>
+              <td className="p-1 sm:p-1.5 text-neon-cyan/80 font-mono" title={f.typeRef}>
+                {f.typeName}
+                {f.nullable && '?'}
+              </td>
             </tr>
           ))}
         </tbody>
@@ -293,11 +380,24 @@ function ExpandedFieldsTable({ typeName, expandedTypes, findMessage }: ExpandedF
 }
 
 function MessageDetails({ fields }: { fields: FieldInfo[] }) {
+  // Connect/JSON clients only ever see the JSON names
+  const [jsonNames, setJsonNames] = useState(false);
+  const hasJsonNames = fields.some((f) => f.jsonName && f.jsonName !== f.name);
+
   return (
     <div className="space-y-3">
       <h3 className="text-sm sm:text-base font-semibold text-neon-cyan flex items-center gap-2">
         <span>📦</span>
         <span>Fields ({fields.length})</span>
+        {hasJsonNames && (
+          <button
+            className="ml-auto text-xs font-normal text-text-secondary hover:text-white px-2 py-0.5 rounded bg-white/5 hover:bg-white/10"
+            onClick={() => setJsonNames((prev) => !prev)}
+            aria-label={jsonNames ? 'Show proto field names' : 'Show JSON field names'}
+          >
+            {jsonNames ? 'JSON names' : 'proto names'}
+          </button>
+        )}
       </h3>
       {fields.length === 0 ? (
         <p className="text-text-secondary text-sm italic">No fields defined</p>
@@ -315,9 +415,25 @@ function MessageDetails({ fields }: { fields: FieldInfo[] }) {
               {fields.map((f) => (
                 <tr key={`${f.number}-${f.name}`} className="border-b border-white/5 hover:bg-white/5 transition-colors">
                   <td className="p-2 text-text-secondary font-mono">{f.number}</td>
-                  <td className="p-2 text-white font-medium">{f.name}</td>
-                  <td className={`p-2 font-mono ${f.label === 'repeated' ? 'text-neon-yellow' : 'text-neon-cyan/80'}`}>
+                  <td className="p-2 text-white font-medium" title={describe(f.comments)}>
+                    <span className={f.deprecated ? 'line-through' : ''}>
+                      {jsonNames ? (f.jsonName ?? f.name) : f.name}
+                    </span>
+                    {describe(f.comments) && (
+                      <span className="block text-text-secondary font-normal whitespace-pre-line">
+                        {describe(f.comments)}
+                      </span>
+                    )}
+                  </td>
+                  <td
+                    className={`p-2 font-mono ${f.label === 'repeated' ? 'text-neon-yellow' : 'text-neon-cyan/80'}`}
+                    title={f.typeRef}
+                  >
                     {f.typeName}
+                    {f.nullable && '?'}
+                    {f.defaultValue !== undefined && (
+                      <span className="text-text-secondary"> = {f.defaultValue}</span>
+                    )}
                   </td>
                 </tr>
               ))}
@@ -341,9 +457,17 @@ function EnumDetails({ values }: { values: EnumValue[] }) {
       ) : (
         <ul className="space-y-1">
           {values.map((v) => (
-            <li key={v.number} className="flex items-center justify-between p-2 hover:bg-white/5 rounded transition-colors">
-              <span className="text-white text-sm sm:text-base font-medium">{v.name}</span>
-              <span className="text-text-secondary text-xs sm:text-sm font-mono">= {v.number}</span>
+            <li key={`${v.number}-${v.name}`} className="flex items-center justify-between p-2 hover:bg-white/5 rounded transition-colors">
+              <span
+                className={`text-white text-sm sm:text-base font-medium ${v.deprecated ? 'line-through' : ''}`}
+                title={describe(v.comments)}
+              >
+                {v.name}
+              </span>
+              <span className="text-text-secondary text-xs sm:text-sm font-mono">
+                = {v.number}
+                {v.aliasOf && ` (alias of ${v.aliasOf})`}
+              </span>
             </li>
           ))}
         </ul>
@@ -351,3 +475,84 @@ function EnumDetails({ values }: { values: EnumValue[] }) {
     </div>
   );
 }
+
+/** `5`, `9 to 11` or `100 to max`, as written in the schema */
+function formatRange({ start, end }: NumberRange): string {
+  if (start === end) return `${start}`;
+  if (end === 536_870_911 || end === 2_147_483_647) return `${start} to max`;
+  return `${start} to ${end}`;
+}
+
+function ReservedDetails({
+  reserved,
+  extensionRanges = [],
+}: {
+  reserved?: Reserved;
+  extensionRanges?: NumberRange[];
+}) {
+  const items = [...(reserved?.ranges ?? []).map(formatRange), ...(reserved?.names ?? [])];
+  if (items.length === 0 && extensionRanges.length === 0) return null;
+
+  return (
+    <dl className="mt-3 text-sm space-y-1">
+      {items.length > 0 && (
+        <div className="flex gap-2">
+          <dt className="text-text-secondary">Reserved</dt>
+          <dd className="text-white font-mono break-all">{items.join(', ')}</dd>
+        </div>
+      )}
+      {extensionRanges.length > 0 && (
+        <div className="flex gap-2">
+          <dt className="text-text-secondary">Extensions</dt>
+          <dd className="text-white font-mono">{extensionRanges.map(formatRange).join(', ')}</dd>
+        </div>
+      )}
+    </dl>
+  );
+}
+
+function FileDetails({
+  unused,
+  indirect,
+  missing,
+}: {
+  unused: string[];
+  indirect: string[];
+  missing: string[];
+}) {
+  const sections = [
+    { title: 'Unused imports', icon: '🗑️', files: unused },
+    { title: 'Provided by public imports', icon: '🔀', files: indirect },
+    { title: 'Missing imports', icon: '❌', files: missing },
+  ].filter((section) => section.files.length > 0);
+
+  if (sections.length === 0) {
+    return (
+      <div className="py-4">
+        <p className="text-text-secondary text-sm italic">No import problems found</p>
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-3">
+      {sections.map((section) => (
+        <div key={section.title} className="space-y-1">
+          <h3 className="text-sm sm:text-base font-semibold text-neon-cyan flex items-center gap-2">
+            <span>{section.icon}</span>
+            <span>
+              {section.title} ({section.files.length})
+            </span>
+          </h3>
+          <ul className="space-y-1">
+            {section.files.map((file) => (
+              <li key={file} className="p-2 text-white text-sm font-mono break-all">
+                {file}
+              </li>
+            ))}
+          </ul>
+        </div>
+      ))}
+    </div>
+  );
+}
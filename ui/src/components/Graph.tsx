@@ -16,9 +16,12 @@ import '@xyflow/react/dist/style.css';
 
 import {
   ServiceNode,
+  MethodNode,
   MessageNode,
   ExternalNode,
   EnumNode,
+  ExtensionNode,
+  FileNode,
   PackageGroupNode,
   type PackageNodeData,
 } from '@/components/nodes';
@@ -29,7 +32,7 @@ import { useAutoLayout } from '@/hooks/useAutoLayout';
 import { usePackageGroups } from '@/hooks/usePackageGroups';
 import { usePackageVisibility } from '@/hooks/usePackageVisibility';
 import { useIsMobile } from '@/hooks/useIsMobile';
-import type { GraphData, NodeData, NodeType } from '@/types/graph';
+import type { EdgeLabel, GraphData, GraphEdge, NodeData, NodeType } from '@/types/graph';
 import {
   NEON_MAGENTA,
   NEON_CYAN,
@@ -43,9 +46,12 @@ import { BUTTON_BASE_STYLES } from '@/constants/styles';
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 const nodeTypes: Record<string, any> = {
   service: ServiceNode,
+  method: MethodNode,
   message: MessageNode,
   enum: EnumNode,
+  extension: ExtensionNode,
   external: ExternalNode,
+  file: FileNode,
   package: PackageGroupNode,
 };
 
@@ -64,10 +70,13 @@ interface CalculateLayoutOptions {
 const getNodeColor = (node: Node): string => {
   switch (node.type) {
     case 'service':
+    case 'method':
       return NEON_MAGENTA;
     case 'message':
+    case 'file':
       return NEON_CYAN;
     case 'enum':
+    case 'extension':
       return NEON_YELLOW;
     case 'external':
       return NEON_GRAY;
@@ -78,6 +87,29 @@ const getNodeColor = (node: Node): string => {
   }
 };
 
+const EDGE_KIND_TEXT: Record<EdgeLabel['kind'], string> = {
+  method: 'rpc',
+  rpcInput: 'input of',
+  rpcOutput: 'output of',
+  field: '',
+  mapValue: 'map',
+  extension: 'extends',
+  nesting: 'nested',
+  import: 'import',
+  publicImport: 'public import',
+  weakImport: 'weak import',
+};
+
+/** Short "why are these connected" text, e.g. `items[], input of GetOrder` */
+function describeEdge(edge: GraphEdge): string {
+  return (edge.labels ?? [])
+    .map((label) => {
+      const name = label.cardinality === 'repeated' ? `${label.name}[]` : label.name;
+      return [EDGE_KIND_TEXT[label.kind], name].filter(Boolean).join(' ');
+    })
+    .join(', ');
+}
+
 function calculateLayout(
   data: GraphData,
   options: CalculateLayoutOptions
@@ -133,6 +165,8 @@ function calculateLayout(
           package: node.package,
           nodeType: node.type as NodeType,
           details: node.details,
+          deprecated: node.deprecated,
+          foreign: node.foreign,
         };
         nodes.push({
           id: node.id,
@@ -189,6 +223,8 @@ function calculateLayout(
       id: `edge-${index}-${edgeKey}`,
       source: actualSource,
       target: actualTarget,
+      // Aggregated package edges stand for many relationships, so only label node-to-node edges
+      data: sourceExpanded && targetExpanded ? { label: describeEdge(edge) } : {},
       animated: true,
       style: { stroke: 'rgba(255, 255, 255, 0.4)', strokeWidth: 2 },
     });
@@ -271,13 +307,14 @@ export function Graph({ data }: GraphProps) {
         const highlighted = isEdgeHighlighted(edge.source, edge.target);
         return {
           ...edge,
+          label: selectedNodeId && highlighted ? (edge.data?.label as string | undefined) : undefined,
           style: highlighted
             ? { stroke: 'var(--color-neon-cyan)', strokeWidth: 3 }
             : { stroke: 'rgba(255, 255, 255, 0.1)', strokeWidth: 1 },
           animated: highlighted,
         };
       }),
-    [edges, isEdgeHighlighted]
+    [edges, isEdgeHighlighted, selectedNodeId]
   );
 
   const onConnect = useCallback(() => {}, []);
@@ -96,7 +96,8 @@ export const PackageFilterPanel = memo(function PackageFilterPanel({
       <div className="p-2 sm:p-3 space-y-1">
         {packages.map((pkg) => {
           const isVisible = visiblePackages.has(pkg.id);
-          const nodeCount = pkg.nodeIds.length;
+          const nodeCount = pkg.counts.nodes;
+          const depth = pkg.id ? pkg.id.split('.').length - 1 : 0;
 
           return (
             <label
@@ -108,6 +109,7 @@ export const PackageFilterPanel = memo(function PackageFilterPanel({
                   ? 'bg-neon-cyan/10 hover:bg-neon-cyan/15'
                   : 'bg-white/5 hover:bg-white/10 opacity-60'}
               `}
+              style={{ marginLeft: `${depth * 12}px` }}
             >
               <div
                 className={`
@@ -133,7 +135,7 @@ export const PackageFilterPanel = memo(function PackageFilterPanel({
                   className={`block text-sm font-medium truncate ${isVisible ? 'text-white' : 'text-text-secondary'}`}
                   title={pkg.id || 'default'}
                 >
-                  {pkg.id || 'default'}
+                  {pkg.parent ? pkg.id.slice(pkg.parent.length + 1) : pkg.id || 'default'}
                 </span>
               </div>
               <span className={`text-xs font-mono px-1.5 py-0.5 rounded ${isVisible ? 'bg-neon-cyan/20 text-neon-cyan' : 'bg-white/10 text-text-secondary'}`}>
@@ -20,11 +20,13 @@ export const BaseNode = memo(function BaseNode({
   children
 }: BaseNodeProps) {
   return (
-    <div className={`rounded-xl p-3 sm:p-4 min-w-[140px] sm:min-w-[180px] transition-all duration-300 hover:scale-[1.02] ${className}`}>
+    <div className={`rounded-xl p-3 sm:p-4 min-w-[140px] sm:min-w-[180px] transition-all duration-300 hover:scale-[1.02] ${className} ${data.foreign ? 'border-dashed opacity-75' : ''}`}>
       <Handle type="target" position={Position.Top} className="handle-top" />
       <div className="text-center">
         <div className="text-xl sm:text-2xl mb-1 sm:mb-2">{icon}</div>
-        <div className="font-semibold text-sm sm:text-base mb-1 break-words">{data.label}</div>
+        <div className={`font-semibold text-sm sm:text-base mb-1 break-words ${data.deprecated ? 'line-through opacity-70' : ''}`}>
+          {data.label}
+        </div>
         <div className="text-[0.65rem] sm:text-xs text-text-secondary break-all max-sm:hidden">{data.file}</div>
         {children}
       </div>
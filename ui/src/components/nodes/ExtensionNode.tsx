@@ -0,0 +1,17 @@
+import { memo } from 'react';
+import type { NodeData } from '@/types/graph';
+import { BaseNode } from './BaseNode';
+
+interface ExtensionNodeProps {
+  data: NodeData;
+}
+
+export const ExtensionNode = memo(function ExtensionNode({ data }: ExtensionNodeProps) {
+  return (
+    <BaseNode
+      data={data}
+      icon="🧩"
+      className="extension-node bg-neon-yellow/5 border border-dashed border-neon-yellow shadow-[0_0_8px_rgba(255,204,0,0.4)] hover:shadow-[0_0_15px_var(--color-neon-yellow),0_0_30px_rgba(255,204,0,0.4)]"
+    />
+  );
+});
@@ -0,0 +1,17 @@
+import { memo } from 'react';
+import type { NodeData } from '@/types/graph';
+import { BaseNode } from './BaseNode';
+
+interface FileNodeProps {
+  data: NodeData;
+}
+
+export const FileNode = memo(function FileNode({ data }: FileNodeProps) {
+  return (
+    <BaseNode
+      data={data}
+      icon="📄"
+      className="file-node bg-neon-cyan/5 border-2 border-neon-cyan shadow-[0_0_8px_rgba(0,255,255,0.4)] hover:shadow-[0_0_15px_var(--color-neon-cyan),0_0_30px_rgba(0,255,255,0.4)]"
+    />
+  );
+});
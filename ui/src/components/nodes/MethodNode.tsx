@@ -0,0 +1,17 @@
+import { memo } from 'react';
+import type { NodeData } from '@/types/graph';
+import { BaseNode } from './BaseNode';
+
+interface MethodNodeProps {
+  data: NodeData;
+}
+
+export const MethodNode = memo(function MethodNode({ data }: MethodNodeProps) {
+  return (
+    <BaseNode
+      data={data}
+      icon="↪"
+      className="method-node bg-neon-magenta/5 border border-dashed border-neon-magenta shadow-[0_0_8px_rgba(255,0,255,0.4)] hover:shadow-[0_0_15px_var(--color-neon-magenta),0_0_30px_rgba(255,0,255,0.4)]"
+    />
+  );
+});
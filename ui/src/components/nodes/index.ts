@@ -1,6 +1,9 @@
 export { ServiceNode } from './ServiceNode';
+export { MethodNode } from './MethodNode';
 export { MessageNode } from './MessageNode';
 export { EnumNode } from './EnumNode';
+export { ExtensionNode } from './ExtensionNode';
 export { ExternalNode } from './ExternalNode';
+export { FileNode } from './FileNode';
 export { PackageGroupNode } from './PackageGroupNode';
 export type { PackageNodeData } from './PackageGroupNode';
@@ -27,17 +27,31 @@ export function usePackageVisibility(data: GraphData): UsePackageVisibilityRetur
     return map;
   }, [data.packages]);
 
+  const childrenOf = useMemo(() => {
+    const map = new Map<string, string[]>();
+    data.packages.forEach((pkg) => map.set(pkg.id, pkg.children ?? []));
+    return map;
+  }, [data.packages]);
+
+  // Toggling a package applies to its whole subtree, so e.g. `order` hides
+  // `order.v1` and `order.v2` too
   const togglePackageVisibility = useCallback((packageId: string) => {
     setVisiblePackages((prev) => {
       const next = new Set(prev);
-      if (next.has(packageId)) {
-        next.delete(packageId);
-      } else {
-        next.add(packageId);
+      const show = !next.has(packageId);
+      const pending = [packageId];
+      while (pending.length > 0) {
+        const id = pending.pop()!;
+        if (show) {
+          next.add(id);
+        } else {
+          next.delete(id);
+        }
+        pending.push(...(childrenOf.get(id) ?? []));
       }
       return next;
     });
-  }, []);
+  }, [childrenOf]);
 
   const showAllPackages = useCallback(() => {
     setVisiblePackages(new Set(data.packages.map((pkg) => pkg.id)));
@@ -1,34 +1,147 @@
-export type NodeType = 'service' | 'message' | 'enum' | 'external';
+export type NodeType = 'service' | 'method' | 'message' | 'enum' | 'extension' | 'external' | 'file';
+
+export type StreamingMode = 'unary' | 'clientStreaming' | 'serverStreaming' | 'bidiStreaming';
 
 export interface MethodSignature {
   name: string;
   inputType: string;
   outputType: string;
+  /** Node ID of the input type */
+  inputTypeRef?: string;
+  /** Node ID of the output type */
+  outputTypeRef?: string;
+  streaming: StreamingMode;
+  comments?: Comments;
+  /** Marked `deprecated = true` in the schema */
+  deprecated?: boolean;
+  span?: Span;
+}
+
+/** Doc comments from the .proto source */
+export interface Comments {
+  /** Comment block directly above the definition */
+  leading?: string;
+  /** Comment on the same line, after the definition */
+  trailing?: string;
 }
 
+/** Position in the .proto file; 1-based lines and columns, both ends inclusive */
+export interface Span {
+  startLine: number;
+  startColumn: number;
+  endLine: number;
+  endColumn: number;
+}
+
+export type FieldPresence = 'explicit' | 'implicit' | 'required';
+
+export type FieldEncoding = 'lengthPrefixed' | 'delimited' | 'packed' | 'expanded';
+
 export interface FieldInfo {
   name: string;
   number: number;
+  /** Key in the proto3 JSON mapping (`json_name` option or lowerCamelCase name) */
+  jsonName?: string;
   typeName: string;
+  /** Node ID of the referenced message/enum (map value type for maps); absent for scalars */
+  typeRef?: string;
   label: string;
+  /** Effective presence after resolving syntax/edition features; absent for repeated fields */
+  presence?: FieldPresence;
+  /** Effective wire encoding; absent for singular scalar fields */
+  encoding?: FieldEncoding;
+  /** Key/value types of a map field (`typeName` reads `map<K, V>`) */
+  map?: MapType;
+  /** `oneof` group the field belongs to (synthetic proto3 optional oneofs excluded) */
+  oneof?: string;
+  /** Declared with the proto3 `optional` keyword */
+  proto3Optional?: boolean;
+  /** proto2 `[default = ...]` in descriptor text form */
+  defaultValue?: string;
+  /** Wrapper type shown as the scalar it wraps (`typeName`), which may be null */
+  nullable?: boolean;
+  comments?: Comments;
+  /** Marked `deprecated = true` in the schema */
+  deprecated?: boolean;
+  span?: Span;
+}
+
+export interface MapType {
+  keyType: string;
+  valueType: string;
+}
+
+/** `oneof` declared in a message, members in declaration order (synthetic oneofs excluded) */
+export interface OneofGroup {
+  name: string;
+  fields: string[];
 }
 
 export interface EnumValue {
   name: string;
   number: number;
+  /** First value with the same number, when this one is an alias of it */
+  aliasOf?: string;
+  comments?: Comments;
+  /** Marked `deprecated = true` in the schema */
+  deprecated?: boolean;
+  span?: Span;
+}
+
+export type WellKnownCategory = 'time' | 'any' | 'struct' | 'fieldMask' | 'wrapper' | 'empty';
+
+/** Semantics of a protobuf well-known type */
+export interface WellKnown {
+  category: WellKnownCategory;
+  /** How values are written in the proto3 JSON mapping */
+  json: string;
+  /** Scalar boxed by a wrapper type, e.g. `string` for `StringValue` */
+  wraps?: string;
 }
 
 /** Message definition with fields (used in Service details for expandable RPC types) */
 export interface MessageDef {
   name: string;
+  /** Node ID of the message, matching method type refs */
+  typeRef: string;
   fields: FieldInfo[];
 }
 
+/** Inclusive range of field or enum value numbers */
+export interface NumberRange {
+  start: number;
+  end: number;
+}
+
+/** Numbers and names that fields or enum values must not use */
+export interface Reserved {
+  ranges?: NumberRange[];
+  names?: string[];
+}
+
 export type NodeDetails =
   | { kind: 'Service'; methods: MethodSignature[]; messages: MessageDef[] }
-  | { kind: 'Message'; fields: FieldInfo[] }
-  | { kind: 'Enum'; values: EnumValue[] }
-  | { kind: 'External' };
+  | { kind: 'Method'; signature: MethodSignature }
+  | {
+      kind: 'Message';
+      fields: FieldInfo[];
+      oneofs?: OneofGroup[];
+      reserved?: Reserved;
+      extensionRanges?: NumberRange[];
+    }
+  | { kind: 'Enum'; values: EnumValue[]; closed: boolean; allowAlias?: boolean; reserved?: Reserved }
+  /** Field declared in an `extend` block; `extendee` is the extended message's node ID */
+  | { kind: 'Extension'; extendee: string; field: FieldInfo }
+  | { kind: 'External'; wellKnown?: WellKnown }
+  | {
+      kind: 'File';
+      /** Imports none of whose types are used */
+      unusedImports?: string[];
+      /** Files whose types are only reachable through another import's `import public` */
+      indirectImports?: string[];
+      /** Files whose types are used without being imported */
+      missingImports?: string[];
+    };
 
 export interface GraphNode {
   id: string;
@@ -36,18 +149,73 @@ export interface GraphNode {
   package: string;
   label: string;
   file: string;
+  /** buf module (e.g. `buf.build/acme/partner`), present for buf image input */
+  module?: string;
+  /** ID of the message this definition is nested in */
+  parent?: string;
+  comments?: Comments;
+  /** Marked `deprecated = true` in the schema */
+  deprecated?: boolean;
+  /** Where the definition is written in `file` */
+  span?: Span;
+  /** In a package configured as foreign: third-party, but shown in full */
+  foreign?: boolean;
   details: NodeDetails;
 }
 
+export type EdgeKind =
+  | 'method'
+  | 'rpcInput'
+  | 'rpcOutput'
+  | 'field'
+  | 'mapValue'
+  | 'extension'
+  | 'nesting'
+  | 'import'
+  | 'publicImport'
+  | 'weakImport';
+
+export type Cardinality = 'singular' | 'repeated' | 'map';
+
+/** One relationship behind an edge */
+export interface EdgeLabel {
+  kind: EdgeKind;
+  /** Field or method inducing the relationship; absent for nesting */
+  name?: string;
+  cardinality?: Cardinality;
+}
+
 export interface GraphEdge {
   source: string;
   target: string;
+  /** Every relationship between source and target */
+  labels?: EdgeLabel[];
+}
+
+/** Definitions in a package and all of its subpackages */
+export interface PackageCounts {
+  services: number;
+  messages: number;
+  enums: number;
+  extensions: number;
+  /** Every node, including methods, externals and files */
+  nodes: number;
 }
 
+/**
+ * Node of the package tree (`order` → `order.v1`). Packages arrive in
+ * depth-first order; intermediate ones may have no `nodeIds` of their own.
+ */
 export interface Package {
   id: string;
   label?: string;
+  /** Nodes declared directly in this package */
   nodeIds: string[];
+  /** Enclosing package; absent for top-level packages */
+  parent?: string;
+  /** Direct subpackages, sorted by ID */
+  children?: string[];
+  counts: PackageCounts;
 }
 
 export interface GraphData {
@@ -64,4 +232,6 @@ export interface NodeData extends Record<string, unknown> {
   package: string;
   nodeType: NodeType;
   details: NodeDetails;
+  deprecated?: boolean;
+  foreign?: boolean;
 }